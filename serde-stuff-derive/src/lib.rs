@@ -0,0 +1,258 @@
+//! # serde-stuff-derive
+//! Field attributes for [serde-stuff](https://docs.rs/serde-stuff).
+//!
+//! Using a `serde-stuff` module on an `Option` field takes three serde
+//! attributes, `with`, `default` and `skip_serializing_if`, and forgetting
+//! `default` only shows up when the field is missing.  The [stuff](macro@stuff)
+//! attribute macro turns a short `#[stuff(...)]` on a field into the right
+//! `#[serde(...)]` attributes for the field's type.
+//!
+//! Use it through the `derive` feature of `serde-stuff`, and put it before
+//! the serde derives:
+//!
+//! ```rust,ignore
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[serde_stuff::stuff]
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[stuff(base64)]
+//!     pub key: Vec<u8>,
+//!     #[stuff(base64, standard)]
+//!     pub cert: Option<Vec<u8>>,
+//!     #[stuff(one_or_many)]
+//!     pub hosts: Vec<String>,
+//!     #[stuff(timestamp = "millis")]
+//!     pub expires: Option<SystemTime>,
+//! }
+//! ```
+//!
+//! The field options are:
+//! - `base64`, with `url_safe`, the default, or `standard` for the alphabet.
+//! - `hex`
+//! - `one_or_many`
+//! - `timestamp = "secs"`, or `"millis"`, `"micros"` or `"nanos"`.
+//!
+//! On an `Option` field, each also adds `default` and
+//! `skip_serializing_if = "Option::is_none"`.
+//!
+//! Generated paths start with `serde_stuff`.  Within a crate that renames
+//! it, or within `serde-stuff` itself, give the path with
+//! `#[stuff(crate = "...")]`, as for serde's own `crate` attribute.
+//!
+//! # Partial
+//! [derive(Partial)](derive@Partial) generates a `PartialName` struct for
+//! the body of a PATCH request, with each field as a `Patch`, which tells a
+//! missing field from a `null`.  Its `apply` merges the fields that are
+//! present into a `Name`:
+//!
+//! ```rust,ignore
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::Partial;
+//!
+//! #[derive(Debug, Deserialize, Serialize, Partial)]
+//! #[partial(derive(Debug))]
+//! pub struct User {
+//!     pub name: String,
+//!     pub nickname: Option<String>,
+//! }
+//!
+//! let mut user = User { name: "Al".into(), nickname: Some("Ally".into()) };
+//! let patch: PartialUser = serde_json::from_str(r#"{"nickname": null}"#)?;
+//! patch.apply(&mut user);
+//! assert_eq!(user.nickname, None);
+//! ```
+//!
+//! An `Option` field is cleared by `null`.  Any other field can't be, so
+//! `null` is an error.  The struct's `rename_all` and
+//! `deny_unknown_fields`, and each field's `rename` and `alias`, are copied
+//! to the patch.  A field's `with`, `serialize_with` and `deserialize_with`
+//! are applied to the value of its patch, and an `Option` field written
+//! with one must be `Clone`.  `skip` and `skip_deserializing` fields are
+//! left out, and `flatten` is an error.  `#[partial(derive(...))]` adds
+//! derives to the patch, and `#[partial(crate = "...")]` sets the path,
+//! as for `stuff`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_macro_input, Attribute, DeriveInput, Error, Fields, GenericArgument, Item, LitStr,
+    PathArguments, Result, Type,
+};
+
+mod partial;
+
+/// Generate `PartialName`, with each field as a `serde_stuff::patch::Patch`,
+/// and its `apply` to merge the present fields into a `Name`.  See the
+/// [crate] docs.
+#[proc_macro_derive(Partial, attributes(partial))]
+pub fn derive_partial(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match partial::expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Rewrite `#[stuff(...)]` field attributes into serde attributes.  See the
+/// [crate] docs.
+#[proc_macro_attribute]
+pub fn stuff(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut krate = "serde_stuff".to_string();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("crate") {
+            krate = meta.value()?.parse::<LitStr>()?.value();
+            Ok(())
+        } else {
+            Err(meta.error("expected `crate = \"...\"`"))
+        }
+    });
+    parse_macro_input!(args with parser);
+
+    let mut item = parse_macro_input!(input as Item);
+    match expand(&mut item, &krate) {
+        Ok(()) => quote!(#item).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(item: &mut Item, krate: &str) -> Result<()> {
+    match item {
+        Item::Struct(s) => expand_fields(&mut s.fields, krate),
+        Item::Enum(e) => e
+            .variants
+            .iter_mut()
+            .try_for_each(|variant| expand_fields(&mut variant.fields, krate)),
+        _ => Err(Error::new(
+            Span::call_site(),
+            "#[stuff] only applies to structs and enums",
+        )),
+    }
+}
+
+fn expand_fields(fields: &mut Fields, krate: &str) -> Result<()> {
+    for field in fields.iter_mut() {
+        let mut attrs = Vec::with_capacity(field.attrs.len());
+        for attr in field.attrs.drain(..) {
+            match attr.path().is_ident("stuff") {
+                true => attrs.extend(serde_attrs(&attr, &field.ty, krate)?),
+                false => attrs.push(attr),
+            }
+        }
+        field.attrs = attrs;
+    }
+    Ok(())
+}
+
+/// How a field is encoded.
+enum Encoding {
+    Base64 { standard: bool },
+    Hex,
+    OneOrMany,
+    Timestamp(String),
+}
+
+fn parse_encoding(attr: &Attribute) -> Result<Encoding> {
+    let mut encoding = None;
+    let mut standard = None;
+    let mut set = |encoding_: Encoding, span: Span| match encoding {
+        Some(_) => Err(Error::new(span, "only one encoding is allowed per field")),
+        None => {
+            encoding = Some(encoding_);
+            Ok(())
+        }
+    };
+    attr.parse_nested_meta(|meta| {
+        let span = meta.path.segments[0].ident.span();
+        if meta.path.is_ident("base64") {
+            set(Encoding::Base64 { standard: false }, span)
+        } else if meta.path.is_ident("hex") {
+            set(Encoding::Hex, span)
+        } else if meta.path.is_ident("one_or_many") {
+            set(Encoding::OneOrMany, span)
+        } else if meta.path.is_ident("timestamp") {
+            let unit = meta.value()?.parse::<LitStr>()?;
+            match unit.value().as_str() {
+                "secs" | "millis" | "micros" | "nanos" => {
+                    set(Encoding::Timestamp(unit.value()), span)
+                }
+                _ => Err(Error::new(
+                    unit.span(),
+                    "expected \"secs\", \"millis\", \"micros\" or \"nanos\"",
+                )),
+            }
+        } else if meta.path.is_ident("standard") {
+            standard = Some(true);
+            Ok(())
+        } else if meta.path.is_ident("url_safe") {
+            standard = Some(false);
+            Ok(())
+        } else {
+            Err(meta.error("unknown stuff attribute"))
+        }
+    })?;
+    match (encoding, standard) {
+        (Some(Encoding::Base64 { .. }), Some(standard)) => Ok(Encoding::Base64 { standard }),
+        (Some(_), Some(_)) => Err(Error::new_spanned(
+            attr,
+            "`standard` and `url_safe` only apply to `base64`",
+        )),
+        (Some(encoding), None) => Ok(encoding),
+        (None, _) => Err(Error::new_spanned(
+            attr,
+            "expected an encoding, such as `base64`",
+        )),
+    }
+}
+
+/// The `T` of a type spelled `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) if last.ident == "Option" && args.args.len() == 1 => {
+            match &args.args[0] {
+                GenericArgument::Type(inner) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn serde_attrs(attr: &Attribute, ty: &Type, krate: &str) -> Result<Vec<Attribute>> {
+    let encoding = parse_encoding(attr)?;
+    let option = option_inner(ty).is_some();
+    let adapter = |adapter: String| match option {
+        true => format!("{krate}::adapters::As::<Option<{adapter}>>"),
+        false => format!("{krate}::adapters::As::<{adapter}>"),
+    };
+    let with = match encoding {
+        Encoding::Base64 { standard } => {
+            let alphabet = if standard { "Standard" } else { "UrlSafe" };
+            adapter(format!(
+                "{krate}::adapters::Base64<{krate}::adapters::{alphabet}>"
+            ))
+        }
+        Encoding::Hex => adapter(format!("{krate}::adapters::Hex")),
+        Encoding::OneOrMany => adapter(format!("{krate}::adapters::OneOrMany")),
+        Encoding::Timestamp(unit) => match option {
+            true => format!("{krate}::option_timestamp_{unit}"),
+            false => format!("{krate}::timestamp_{unit}"),
+        },
+    };
+    let mut attrs: Punctuated<proc_macro2::TokenStream, syn::Token![,]> = Punctuated::new();
+    attrs.push(quote!(with = #with));
+    if option {
+        attrs.push(quote!(default));
+        attrs.push(quote!(skip_serializing_if = "Option::is_none"));
+    }
+    Ok(vec![
+        syn::parse_quote_spanned!(attr.bracket_token.span.join() => #[serde(#attrs)]),
+    ])
+}
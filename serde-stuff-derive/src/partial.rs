@@ -0,0 +1,225 @@
+//! `#[derive(Partial)]`: a struct of patches, and an `apply` to merge it.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, ExprLit, ExprPath, Field, Fields, Lit, LitStr, Meta,
+    Path, Result, Token,
+};
+
+use crate::option_inner;
+
+/// The `#[serde(...)]` items of `attr` named in `keep`.
+fn serde_items(attr: &Attribute, keep: &[&str]) -> Result<Vec<Meta>> {
+    let items = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+    Ok(items
+        .into_iter()
+        .filter(|item| keep.iter().any(|name| item.path().is_ident(name)))
+        .collect())
+}
+
+/// The `#[serde(...)]` items of a field that carry over to its patch.
+#[derive(Default)]
+struct FieldItems {
+    /// `rename` and `alias`, copied as they are.
+    copied: Vec<Meta>,
+    /// `skip` or `skip_deserializing`: the field can't be patched.
+    skip: bool,
+    /// The `with` or `deserialize_with` function.
+    deserialize: Option<ExprPath>,
+    /// The `with` or `serialize_with` function.
+    serialize: Option<ExprPath>,
+}
+
+fn field_items(field: &Field) -> Result<FieldItems> {
+    let mut items = FieldItems::default();
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        for meta in metas {
+            let path = meta.path();
+            if path.is_ident("rename") || path.is_ident("alias") {
+                items.copied.push(meta);
+            } else if path.is_ident("skip") || path.is_ident("skip_deserializing") {
+                items.skip = true;
+            } else if path.is_ident("with") {
+                let with = meta_str(&meta)?;
+                items.deserialize = Some(syn::parse_str(&format!("{with}::deserialize"))?);
+                items.serialize = Some(syn::parse_str(&format!("{with}::serialize"))?);
+            } else if path.is_ident("deserialize_with") {
+                items.deserialize = Some(syn::parse_str(&meta_str(&meta)?)?);
+            } else if path.is_ident("serialize_with") {
+                items.serialize = Some(syn::parse_str(&meta_str(&meta)?)?);
+            } else if path.is_ident("flatten") {
+                return Err(Error::new_spanned(
+                    meta,
+                    "#[derive(Partial)] can't patch a flattened field",
+                ));
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// The string value of a `name = "..."` item.
+fn meta_str(meta: &Meta) -> Result<String> {
+    match meta {
+        Meta::NameValue(nv) => match &nv.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) => Ok(value.value()),
+            value => Err(Error::new_spanned(value, "expected a string")),
+        },
+        _ => Err(Error::new_spanned(meta, "expected `name = \"...\"`")),
+    }
+}
+
+pub(crate) fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let mut krate = "serde_stuff".to_string();
+    let mut derives: Vec<Path> = Vec::new();
+    let mut container = Vec::new();
+    for attr in &input.attrs {
+        if attr.path().is_ident("partial") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("crate") {
+                    krate = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else if meta.path.is_ident("derive") {
+                    meta.parse_nested_meta(|derive| {
+                        derives.push(derive.path);
+                        Ok(())
+                    })
+                } else {
+                    Err(meta.error("expected `crate = \"...\"` or `derive(...)`"))
+                }
+            })?;
+        } else if attr.path().is_ident("serde") {
+            container.extend(serde_items(attr, &["rename_all", "deny_unknown_fields"])?);
+        }
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, NAMED_ONLY)),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, NAMED_ONLY)),
+    };
+
+    let patch: Path = syn::parse_str(&format!("{krate}::patch::Patch"))?;
+    let is_missing = format!("{krate}::patch::Patch::is_missing");
+    let required = format!("{krate}::patch::required");
+    let mut partial_fields = Vec::with_capacity(fields.len());
+    let mut applies = Vec::with_capacity(fields.len());
+    let mut helpers = Vec::new();
+    let partial = format_ident!("Partial{}", input.ident);
+    for field in fields {
+        let ident = &field.ident;
+        let vis = &field.vis;
+        let items = field_items(field)?;
+        if items.skip {
+            continue;
+        }
+        let mut copied = items.copied;
+        let field_name = ident.as_ref().map(|i| i.to_string()).unwrap_or_default();
+        let inner = option_inner(&field.ty);
+        let value_ty = inner.unwrap_or(&field.ty);
+        match (&items.deserialize, inner) {
+            (Some(de), Some(inner)) => {
+                let helper = format_ident!("__patch_deserialize_{}", field_name);
+                helpers.push(quote! {
+                    #[doc(hidden)]
+                    fn #helper<'de, D: ::serde::Deserializer<'de>>(
+                        d: D,
+                    ) -> ::core::result::Result<#patch<#inner>, D::Error> {
+                        #de(d).map(|v: ::core::option::Option<#inner>| {
+                            v.map_or(#patch::Null, #patch::Value)
+                        })
+                    }
+                });
+                let path = format!("{partial}::{helper}");
+                copied.push(syn::parse_quote!(deserialize_with = #path));
+            }
+            (Some(de), None) => {
+                let ty = &field.ty;
+                let helper = format_ident!("__patch_deserialize_{}", field_name);
+                helpers.push(quote! {
+                    #[doc(hidden)]
+                    fn #helper<'de, D: ::serde::Deserializer<'de>>(
+                        d: D,
+                    ) -> ::core::result::Result<#patch<#ty>, D::Error> {
+                        #de(d).map(#patch::Value)
+                    }
+                });
+                let path = format!("{partial}::{helper}");
+                copied.push(syn::parse_quote!(deserialize_with = #path));
+            }
+            (None, Some(_)) => {}
+            (None, None) => copied.push(syn::parse_quote!(deserialize_with = #required)),
+        }
+        if let Some(ser) = &items.serialize {
+            let helper = format_ident!("__patch_serialize_{}", field_name);
+            // An `Option` module takes an `&Option`, which the patch
+            // doesn't hold, so the value is cloned into one.
+            let value = match inner {
+                Some(_) => quote!(&::core::option::Option::Some(::core::clone::Clone::clone(
+                    v
+                ))),
+                None => quote!(v),
+            };
+            helpers.push(quote! {
+                #[doc(hidden)]
+                fn #helper<S: ::serde::Serializer>(
+                    v: &#patch<#value_ty>,
+                    s: S,
+                ) -> ::core::result::Result<S::Ok, S::Error> {
+                    match v {
+                        #patch::Value(v) => #ser(#value, s),
+                        _ => s.serialize_none(),
+                    }
+                }
+            });
+            let path = format!("{partial}::{helper}");
+            copied.push(syn::parse_quote!(serialize_with = #path));
+        }
+        partial_fields.push(quote! {
+            #[serde(#(#copied,)* default, skip_serializing_if = #is_missing)]
+            #vis #ident: #patch<#value_ty>
+        });
+        match inner {
+            Some(_) => applies.push(quote!(self.#ident.apply_option(&mut target.#ident);)),
+            None => applies.push(quote!(self.#ident.apply(&mut target.#ident);)),
+        }
+    }
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let doc = format!("A patch for each field of [`{name}`].");
+    let generics = &input.generics;
+    let where_clause = &generics.where_clause;
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let container = match container.is_empty() {
+        true => quote!(),
+        false => quote!(#[serde(#(#container),*)]),
+    };
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Default, ::serde::Deserialize, ::serde::Serialize, #(#derives),*)]
+        #container
+        #vis struct #partial #generics #where_clause {
+            #(#partial_fields,)*
+        }
+
+        impl #impl_generics #partial #ty_generics #where_clause {
+            /// Merge every field that is present into `target`.
+            #vis fn apply(self, target: &mut #name #ty_generics) {
+                #(#applies)*
+            }
+
+            #(#helpers)*
+        }
+    })
+}
+
+const NAMED_ONLY: &str = "#[derive(Partial)] only applies to structs with named fields";
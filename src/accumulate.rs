@@ -0,0 +1,721 @@
+//! Collect every field error in a document, instead of stopping at the first
+//!
+//! A derived `Deserialize` stops at the first bad field, so fixing a large
+//! config means one run per mistake.  [deserialize] reads the whole
+//! document, and reports every field that doesn't fit, each with its path,
+//! such as `servers[2].port`, along with the value if one could still be
+//! built.
+//!
+//! A field of the wrong type is reported, and read as a placeholder, such
+//! as `0` or `""`, so the rest of the document is still checked.  Any other
+//! error, such as from a `with` module or an unknown variant, is reported,
+//! and the document is read again with that field as a placeholder, or
+//! `None` if it is an `Option`.  If the type still can't be built, say
+//! because a required field is missing, the errors so far are returned
+//! without a value.
+//!
+//! Since the document is buffered as [Content], the format must be self
+//! describing.  Paths stop at enum variants.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use serde_stuff::accumulate;
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Server {
+//!     pub host: String,
+//!     pub port: u16,
+//! }
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Config {
+//!     pub name: String,
+//!     pub servers: Vec<Server>,
+//! }
+//!
+//! let json = r#"{
+//!     "name": 7,
+//!     "servers": [{"host": "a", "port": 80}, {"host": "b", "port": "http"}]
+//! }"#;
+//! let report: accumulate::Report<Config> =
+//!     accumulate::deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+//! let errors: Vec<String> = report.errors.iter().map(|e| e.to_string()).collect();
+//! assert_eq!(
+//!     errors,
+//!     [
+//!         "invalid type: integer `7`, expected a string at name",
+//!         "invalid type: string \"http\", expected u16 at servers[1].port",
+//!     ]
+//! );
+//! assert_eq!(report.value.unwrap().servers[0].port, 80);
+//! ```
+
+use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::cell::RefCell;
+use std::fmt;
+
+use super::content::{Content, ContentDeserializer};
+use super::strictness;
+
+/// An error, and the path of the field it was found at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldError {
+    /// Dotted field names and `[index]`es, empty for the document itself.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.path.is_empty() {
+            true => f.write_str(&self.message),
+            false => write!(f, "{} at {}", self.message, self.path),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Every error found, and the value, if one could be built.
+#[derive(Debug)]
+pub struct Report<T> {
+    pub value: Option<T>,
+    pub errors: Vec<FieldError>,
+}
+
+impl<T> Report<T> {
+    /// The value if there were no errors, or else the errors.
+    pub fn into_result(self) -> Result<T, Vec<FieldError>> {
+        match (self.value, self.errors.is_empty()) {
+            (Some(value), true) => Ok(value),
+            (_, _) => Err(self.errors),
+        }
+    }
+}
+
+/// Read the whole document, and report every field error.  The error is
+/// only for input the format itself can't read.
+pub fn deserialize<'de, D, T>(d: D) -> Result<Report<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    Content::deserialize(d).map(from_content)
+}
+
+/// [deserialize] from a buffered document.
+pub fn from_content<T: DeserializeOwned>(content: Content) -> Report<T> {
+    collect(content, false)
+}
+
+/// Deserialize, reading fields of the wrong type as placeholders, or with
+/// `coerce`, as the expected type where possible.
+pub(crate) fn collect<T: DeserializeOwned>(content: Content, coerce: bool) -> Report<T> {
+    let mut errors = Vec::new();
+    let mut placeholders = Vec::new();
+    loop {
+        let state = State {
+            errors: RefCell::new(Vec::new()),
+            fatal: RefCell::new(None),
+            placeholders: &placeholders,
+            fail_fast: false,
+            coerce,
+        };
+        let result = T::deserialize(Walker {
+            content: content.clone(),
+            path: String::new(),
+            state: &state,
+        });
+        state.repairs("");
+        for error in state.errors.into_inner() {
+            if !errors.contains(&error) {
+                errors.push(error);
+            }
+        }
+        let error = match result {
+            Ok(value) => {
+                return Report {
+                    value: Some(value),
+                    errors,
+                }
+            }
+            Err(e) => state.fatal.into_inner().unwrap_or(FieldError {
+                path: String::new(),
+                message: e.to_string(),
+            }),
+        };
+        let path = error.path.clone();
+        if !errors.contains(&error) {
+            errors.push(error);
+        }
+        if path.is_empty() || placeholders.contains(&path) {
+            return Report {
+                value: None,
+                errors,
+            };
+        }
+        placeholders.push(path);
+    }
+}
+
+/// Deserialize, stopping at the first error, with the path of the field it
+/// was found at.
+pub(crate) fn first_error<T: DeserializeOwned>(content: Content) -> Result<T, FieldError> {
+    let state = State {
+        errors: RefCell::new(Vec::new()),
+        fatal: RefCell::new(None),
+        placeholders: &[],
+        fail_fast: true,
+        coerce: false,
+    };
+    T::deserialize(Walker {
+        content,
+        path: String::new(),
+        state: &state,
+    })
+    .map_err(|e| {
+        state.fatal.into_inner().unwrap_or(FieldError {
+            path: String::new(),
+            message: e.to_string(),
+        })
+    })
+}
+
+pub(crate) fn field_path(path: &str, key: &Content) -> String {
+    let key = match key {
+        Content::String(key) => key.clone(),
+        Content::I64(key) => key.to_string(),
+        Content::U64(key) => key.to_string(),
+        other => format!("{:?}", other),
+    };
+    match path.is_empty() {
+        true => key,
+        false => format!("{}.{}", path, key),
+    }
+}
+
+pub(crate) fn index_path(path: &str, index: usize) -> String {
+    format!("{}[{}]", path, index)
+}
+
+struct State<'a> {
+    /// Fields of the wrong type, read as placeholders.
+    errors: RefCell<Vec<FieldError>>,
+    /// The innermost field of an error that ended the attempt.
+    fatal: RefCell<Option<FieldError>>,
+    /// Fields that failed an earlier attempt, read as placeholders.
+    placeholders: &'a [String],
+    /// Return fields of the wrong type as errors, rather than recording them.
+    fail_fast: bool,
+    /// Read fields of the wrong type as the expected type where possible,
+    /// and record the repairs of the lenient modules.
+    coerce: bool,
+}
+
+impl State<'_> {
+    fn fatal(&self, path: &str, error: Error) -> Error {
+        let mut fatal = self.fatal.borrow_mut();
+        if fatal.is_none() {
+            *fatal = Some(FieldError {
+                path: path.to_string(),
+                message: error.to_string(),
+            });
+        }
+        error
+    }
+
+    /// Record the repairs reported since the last call as at `path`.
+    fn repairs(&self, path: &str) {
+        if self.coerce {
+            let mut errors = self.errors.borrow_mut();
+            for message in strictness::take_repairs() {
+                errors.push(FieldError {
+                    path: path.to_string(),
+                    message,
+                });
+            }
+        }
+    }
+}
+
+/// What a leaf can be coerced to.
+#[derive(Clone, Copy)]
+enum Coerce {
+    Bool,
+    Int,
+    Float,
+    Str,
+    /// Not coerced.
+    Never,
+}
+
+/// Replays a [Content], recording fields of the wrong type.
+struct Walker<'a> {
+    content: Content,
+    path: String,
+    state: &'a State<'a>,
+}
+
+impl Walker<'_> {
+    fn placeholder(&self) -> bool {
+        self.state.placeholders.contains(&self.path)
+    }
+
+    /// Record that the field isn't the `expected` type.
+    fn mismatch(&self, expected: &str) {
+        if !self.placeholder() {
+            self.state.errors.borrow_mut().push(FieldError {
+                path: self.path.clone(),
+                message: <Error as de::Error>::invalid_type(self.content.unexpected(), &expected)
+                    .to_string(),
+            });
+        }
+    }
+
+    /// The field as a `kind`, when coercing, and recording that it was.
+    fn coerced(&self, kind: Coerce, expected: &str) -> Option<Content> {
+        if !self.state.coerce || self.placeholder() {
+            return None;
+        }
+        let coerced = match (kind, &self.content) {
+            (Coerce::Bool, Content::String(s)) => match s.trim().to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Some(Content::Bool(true)),
+                "false" | "no" | "off" | "0" => Some(Content::Bool(false)),
+                _ => None,
+            },
+            (Coerce::Bool, Content::I64(0) | Content::U64(0)) => Some(Content::Bool(false)),
+            (Coerce::Bool, Content::I64(1) | Content::U64(1)) => Some(Content::Bool(true)),
+            (Coerce::Int, Content::String(s)) => {
+                let s = s.trim();
+                s.parse()
+                    .map(Content::I64)
+                    .or_else(|_| s.parse().map(Content::U64))
+                    .ok()
+            }
+            (Coerce::Int, Content::F64(v)) if v.fract() == 0.0 && v.abs() < 9.0e15 => {
+                Some(Content::I64(*v as i64))
+            }
+            (Coerce::Float, Content::String(s)) => s.trim().parse().map(Content::F64).ok(),
+            (Coerce::Str, Content::Bool(v)) => Some(Content::String(v.to_string())),
+            (Coerce::Str, Content::I64(v)) => Some(Content::String(v.to_string())),
+            (Coerce::Str, Content::U64(v)) => Some(Content::String(v.to_string())),
+            (Coerce::Str, Content::F64(v)) => Some(Content::String(v.to_string())),
+            _ => None,
+        }?;
+        self.coercion(expected);
+        Some(coerced)
+    }
+
+    /// Record that the field was read as `expected`.
+    fn coercion(&self, expected: &str) {
+        self.state.errors.borrow_mut().push(FieldError {
+            path: self.path.clone(),
+            message: format!("read {} as {}", self.content.unexpected(), expected),
+        });
+    }
+
+    fn visit_seq<'de, V: Visitor<'de>>(
+        self,
+        items: Vec<Content>,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let mut seq = WalkerSeq {
+            items: items.into_iter().enumerate(),
+            path: self.path,
+            state: self.state,
+        };
+        let value = visitor.visit_seq(&mut seq)?;
+        match seq.items.len() {
+            0 => Ok(value),
+            n => Err(de::Error::invalid_length(n, &"fewer elements in sequence")),
+        }
+    }
+
+    fn visit_map<'de, V: Visitor<'de>>(
+        self,
+        entries: Vec<(Content, Content)>,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_map(WalkerMap {
+            entries: entries.into_iter(),
+            value: None,
+            path: self.path,
+            state: self.state,
+        })
+    }
+}
+
+/// Read a leaf of the expected shape, or else record it and visit the
+/// placeholder, unless failing fast.
+macro_rules! leaf {
+    ($($method:ident => $shape:pat, $coerce:ident, $expected:literal, $visit:ident($($placeholder:expr)?);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match &self.content {
+                    $shape if !self.placeholder() => {
+                        ContentDeserializer::new(self.content).$method(visitor)
+                    }
+                    _ if self.state.fail_fast => {
+                        ContentDeserializer::new(self.content).$method(visitor)
+                    }
+                    _ => match self.coerced(Coerce::$coerce, $expected) {
+                        Some(content) => ContentDeserializer::new(content).$method(visitor),
+                        None => {
+                            self.mismatch($expected);
+                            visitor.$visit($($placeholder)?)
+                        }
+                    },
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Walker<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.content {
+            Content::Seq(items) => Walker {
+                content: Content::Unit,
+                ..self
+            }
+            .visit_seq(items, visitor),
+            Content::Map(entries) => Walker {
+                content: Content::Unit,
+                ..self
+            }
+            .visit_map(entries, visitor),
+            content => ContentDeserializer::new(content).deserialize_any(visitor),
+        }
+    }
+
+    leaf! {
+        deserialize_bool => Content::Bool(_), Bool, "a boolean", visit_bool(false);
+        deserialize_i8 => Content::I64(_) | Content::U64(_), Int, "i8", visit_i8(0);
+        deserialize_i16 => Content::I64(_) | Content::U64(_), Int, "i16", visit_i16(0);
+        deserialize_i32 => Content::I64(_) | Content::U64(_), Int, "i32", visit_i32(0);
+        deserialize_i64 => Content::I64(_) | Content::U64(_), Int, "i64", visit_i64(0);
+        deserialize_i128 => Content::I64(_) | Content::U64(_), Int, "i128", visit_i128(0);
+        deserialize_u8 => Content::I64(_) | Content::U64(_), Int, "u8", visit_u8(0);
+        deserialize_u16 => Content::I64(_) | Content::U64(_), Int, "u16", visit_u16(0);
+        deserialize_u32 => Content::I64(_) | Content::U64(_), Int, "u32", visit_u32(0);
+        deserialize_u64 => Content::I64(_) | Content::U64(_), Int, "u64", visit_u64(0);
+        deserialize_u128 => Content::I64(_) | Content::U64(_), Int, "u128", visit_u128(0);
+        deserialize_f32 => Content::I64(_) | Content::U64(_) | Content::F64(_), Float, "f32", visit_f32(0.0);
+        deserialize_f64 => Content::I64(_) | Content::U64(_) | Content::F64(_), Float, "f64", visit_f64(0.0);
+        deserialize_char => Content::String(_), Str, "a character", visit_char('\0');
+        deserialize_str => Content::String(_), Str, "a string", visit_str("");
+        deserialize_string => Content::String(_), Str, "a string", visit_str("");
+        deserialize_bytes => Content::String(_) | Content::Bytes(_) | Content::Seq(_), Str, "bytes", visit_bytes(&[]);
+        deserialize_byte_buf => Content::String(_) | Content::Bytes(_) | Content::Seq(_), Str, "bytes", visit_bytes(&[]);
+        deserialize_unit => Content::Unit, Never, "null", visit_unit();
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.content {
+            _ if self.placeholder() => visitor.visit_none(),
+            Content::Unit => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.content {
+            Content::Seq(items) if !self.placeholder() => Walker {
+                content: Content::Unit,
+                ..self
+            }
+            .visit_seq(items, visitor),
+            content if self.state.fail_fast => {
+                ContentDeserializer::new(content).deserialize_seq(visitor)
+            }
+            _ if self.state.coerce && !self.placeholder() => {
+                let items = match &self.content {
+                    Content::Unit => Vec::new(),
+                    content => vec![content.clone()],
+                };
+                self.coercion(match items.is_empty() {
+                    true => "an empty sequence",
+                    false => "a sequence of one",
+                });
+                Walker {
+                    content: Content::Unit,
+                    ..self
+                }
+                .visit_seq(items, visitor)
+            }
+            _ => {
+                self.mismatch("a sequence");
+                visitor.visit_seq(SeqDeserializer::new(std::iter::empty::<Content>()))
+            }
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.content {
+            Content::Map(entries) if !self.placeholder() => Walker {
+                content: Content::Unit,
+                ..self
+            }
+            .visit_map(entries, visitor),
+            content if self.state.fail_fast => {
+                ContentDeserializer::new(content).deserialize_map(visitor)
+            }
+            Content::Unit if self.state.coerce && !self.placeholder() => {
+                self.coercion("an empty map");
+                visitor.visit_map(MapDeserializer::new(
+                    std::iter::empty::<(Content, Content)>(),
+                ))
+            }
+            _ => {
+                self.mismatch("a map");
+                visitor.visit_map(MapDeserializer::new(
+                    std::iter::empty::<(Content, Content)>(),
+                ))
+            }
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        ContentDeserializer::new(self.content).deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        ContentDeserializer::new(self.content).deserialize_identifier(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct WalkerSeq<'a> {
+    items: std::iter::Enumerate<std::vec::IntoIter<Content>>,
+    path: String,
+    state: &'a State<'a>,
+}
+
+impl<'de> SeqAccess<'de> for WalkerSeq<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        let Some((index, content)) = self.items.next() else {
+            return Ok(None);
+        };
+        let path = index_path(&self.path, index);
+        let walker = Walker {
+            content,
+            path: path.clone(),
+            state: self.state,
+        };
+        let result = seed.deserialize(walker);
+        self.state.repairs(&path);
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(e) => Err(self.state.fatal(&path, e)),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+struct WalkerMap<'a> {
+    entries: std::vec::IntoIter<(Content, Content)>,
+    value: Option<(String, Content)>,
+    path: String,
+    state: &'a State<'a>,
+}
+
+impl<'de> MapAccess<'de> for WalkerMap<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        let path = field_path(&self.path, &key);
+        self.value = Some((path.clone(), value));
+        match seed.deserialize(ContentDeserializer::new(key)) {
+            Ok(key) => Ok(Some(key)),
+            Err(e) => Err(self.state.fatal(&path, e)),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (path, content) = self
+            .value
+            .take()
+            .ok_or_else(|| <Error as de::Error>::custom("value is missing"))?;
+        let walker = Walker {
+            content,
+            path: path.clone(),
+            state: self.state,
+        };
+        let result = seed.deserialize(walker);
+        self.state.repairs(&path);
+        result.map_err(|e| self.state.fatal(&path, e))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+#[cfg(all(test, feature = "base64"))]
+mod tests {
+    use serde::Deserialize;
+
+    use super::{FieldError, Report};
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    pub struct Server {
+        pub host: String,
+        pub port: u16,
+        #[serde(default, with = "crate::base64")]
+        pub key: Vec<u8>,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    pub struct Config {
+        pub name: String,
+        pub debug: Option<bool>,
+        #[serde(default, with = "crate::option_base64")]
+        pub secret: Option<Vec<u8>>,
+        pub servers: Vec<Server>,
+    }
+
+    fn report(json: &str) -> Report<Config> {
+        super::deserialize(&mut serde_json::Deserializer::from_str(json)).expect("Oops!")
+    }
+
+    fn errors(report: &Report<Config>) -> Vec<String> {
+        report.errors.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn no_errors() {
+        let json = r#"{"name": "prod", "servers": [{"host": "a", "port": 80}]}"#;
+        let config = report(json).into_result().expect("Oops!");
+        assert_eq!(config.servers[0].port, 80);
+    }
+
+    #[test]
+    fn type_errors() {
+        let json = r#"{
+            "name": ["prod"],
+            "debug": "yes",
+            "servers": [{"host": "a", "port": 80}, {"host": 1, "port": -1}]
+        }"#;
+        let report = report(json);
+        assert_eq!(
+            errors(&report),
+            [
+                "invalid type: sequence, expected a string at name",
+                "invalid type: string \"yes\", expected a boolean at debug",
+                "invalid type: integer `1`, expected a string at servers[1].host",
+                "invalid value: integer `-1`, expected u16 at servers[1].port",
+            ]
+        );
+        assert_eq!(report.errors[0].path, "name");
+        let config = report.value.expect("Oops!");
+        assert_eq!(config.debug, Some(false));
+        assert_eq!(config.servers[1].port, 0);
+    }
+
+    #[test]
+    fn module_errors() {
+        let json = r#"{
+            "name": "prod",
+            "secret": "!!",
+            "servers": [{"host": "a", "port": 80, "key": "??"}]
+        }"#;
+        let report = report(json);
+        let errors = errors(&report);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].ends_with(" at secret"), "{:?}", errors);
+        assert!(errors[1].ends_with(" at servers[0].key"), "{:?}", errors);
+        let config = report.value.expect("Oops!");
+        assert_eq!(config.secret, None);
+        assert_eq!(config.servers[0].key, Vec::<u8>::new());
+    }
+
+    #[test]
+    fn missing_field() {
+        let json = r#"{"debug": 1, "servers": [{"port": 80}]}"#;
+        let report = report(json);
+        assert!(report.value.is_none());
+        assert_eq!(
+            report.errors,
+            [
+                FieldError {
+                    path: "debug".to_string(),
+                    message: "invalid type: integer `1`, expected a boolean".to_string(),
+                },
+                FieldError {
+                    path: "servers[0]".to_string(),
+                    message: "missing field `host`".to_string(),
+                },
+            ]
+        );
+    }
+}
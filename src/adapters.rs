@@ -0,0 +1,589 @@
+//! Adapter types that nest inside `Option`, `Vec` and maps
+//!
+//! A `with` module handles exactly the type of the field, so
+//! `serde_stuff::base64` can't be used on an `Option<Vec<u8>>` or a
+//! `Vec<Vec<u8>>` without another module for each shape.  Adapters fix that,
+//! in the style of [serde_with](https://docs.rs/serde_with).  An adapter is a
+//! type that implements [SerializeAs] and [DeserializeAs] for the value it
+//! stands in for.  [As] turns any adapter into a `with` module, and the
+//! adapter can be written in the same shape as the field:
+//!
+//! - `Option<A>`, `Vec<A>`, `BTreeMap<KA, VA>` and `HashMap<KA, VA>` apply
+//!   `A` to each value.
+//! - [Same] leaves a value as it is, such as the keys of a map.
+//! - [Base64], with the [UrlSafe] or [Standard] alphabet, and [Hex] for bytes.
+//! - [OneOrMany] for a `Vec` that may be a single value.
+//! - [StringOrStruct] for a type that is a string or a map.
+//! - [DisplayFromStr] for a type written with `Display` and read with
+//!   `FromStr`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::collections::BTreeMap;
+//! use serde_stuff::adapters::{As, Hex, OneOrMany, Same};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "As::<Option<Hex>>")]
+//!     pub key: Option<Vec<u8>>,
+//!     #[serde(with = "As::<Vec<Hex>>")]
+//!     pub hashes: Vec<Vec<u8>>,
+//!     #[serde(with = "As::<BTreeMap<Same, OneOrMany>>")]
+//!     pub hosts: BTreeMap<String, Vec<String>>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "key": "000102",
+//!     "hashes": ["00ff", "beef"],
+//!     "hosts": {"web": "a.example", "db": ["b.example", "c.example"]}
+//! }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+#[cfg(feature = "base64")]
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+#[cfg(feature = "base64")]
+use base64::{engine::general_purpose, Engine as _};
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::str::FromStr;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use super::content::{Content, ContentDeserializer};
+
+/// Serialize a `T` in the way `Self` describes.
+pub trait SerializeAs<T: ?Sized> {
+    fn serialize_as<S: Serializer>(source: &T, s: S) -> Result<S::Ok, S::Error>;
+}
+
+/// Deserialize a `T` in the way `Self` describes.
+pub trait DeserializeAs<'de, T>: Sized {
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<T, D::Error>;
+}
+
+/// Use an adapter as a `with` module: `#[serde(with = "As::<Vec<Hex>>")]`.
+pub struct As<A: ?Sized>(PhantomData<A>);
+
+impl<A: ?Sized> As<A> {
+    pub fn serialize<S, T>(v: &T, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: ?Sized,
+        A: SerializeAs<T>,
+    {
+        A::serialize_as(v, s)
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        A: DeserializeAs<'de, T>,
+    {
+        A::deserialize_as(d)
+    }
+}
+
+/// Serializes a borrowed `T` with the adapter `A`.
+pub struct SerializeAsWrap<'a, T: ?Sized, A: ?Sized> {
+    value: &'a T,
+    marker: PhantomData<A>,
+}
+
+impl<'a, T: ?Sized, A: ?Sized> SerializeAsWrap<'a, T, A> {
+    pub fn new(value: &'a T) -> Self {
+        SerializeAsWrap {
+            value,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized, A: ?Sized + SerializeAs<T>> Serialize for SerializeAsWrap<'_, T, A> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        A::serialize_as(self.value, s)
+    }
+}
+
+/// Deserializes a `T` with the adapter `A`.
+pub struct DeserializeAsWrap<T, A> {
+    value: T,
+    marker: PhantomData<A>,
+}
+
+impl<T, A> DeserializeAsWrap<T, A> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<'de, T, A: DeserializeAs<'de, T>> Deserialize<'de> for DeserializeAsWrap<T, A> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        A::deserialize_as(d).map(|value| DeserializeAsWrap {
+            value,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Leaves the value as it is.
+pub struct Same;
+
+impl<T: ?Sized + Serialize> SerializeAs<T> for Same {
+    fn serialize_as<S: Serializer>(source: &T, s: S) -> Result<S::Ok, S::Error> {
+        source.serialize(s)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeAs<'de, T> for Same {
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<T, D::Error> {
+        T::deserialize(d)
+    }
+}
+
+impl<T, A: SerializeAs<T>> SerializeAs<Option<T>> for Option<A> {
+    fn serialize_as<S: Serializer>(source: &Option<T>, s: S) -> Result<S::Ok, S::Error> {
+        match source {
+            Some(v) => s.serialize_some(&SerializeAsWrap::<T, A>::new(v)),
+            None => s.serialize_none(),
+        }
+    }
+}
+
+impl<'de, T, A: DeserializeAs<'de, T>> DeserializeAs<'de, Option<T>> for Option<A> {
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<Option<T>, D::Error> {
+        Ok(Option::<DeserializeAsWrap<T, A>>::deserialize(d)?.map(DeserializeAsWrap::into_inner))
+    }
+}
+
+impl<T, A: SerializeAs<T>> SerializeAs<Vec<T>> for Vec<A> {
+    fn serialize_as<S: Serializer>(source: &Vec<T>, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(source.iter().map(SerializeAsWrap::<T, A>::new))
+    }
+}
+
+impl<'de, T, A: DeserializeAs<'de, T>> DeserializeAs<'de, Vec<T>> for Vec<A> {
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<Vec<T>, D::Error> {
+        struct SeqVisitor<T, A>(PhantomData<(T, A)>);
+
+        impl<'de, T, A: DeserializeAs<'de, T>> Visitor<'de> for SeqVisitor<T, A> {
+            type Value = Vec<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sequence")
+            }
+
+            fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Vec<T>, S::Error> {
+                let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(item) = seq.next_element::<DeserializeAsWrap<T, A>>()? {
+                    items.push(item.into_inner());
+                }
+                Ok(items)
+            }
+        }
+
+        d.deserialize_seq(SeqVisitor::<T, A>(PhantomData))
+    }
+}
+
+/// Read a map with the adapters `KA` and `VA`, into any map type.
+fn deserialize_map_as<'de, D, M, K, V, KA, VA>(d: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: FromIterator<(K, V)>,
+    KA: DeserializeAs<'de, K>,
+    VA: DeserializeAs<'de, V>,
+{
+    struct MapVisitor<M, K, V, KA, VA>(PhantomData<(M, K, V, KA, VA)>);
+
+    impl<'de, M, K, V, KA, VA> Visitor<'de> for MapVisitor<M, K, V, KA, VA>
+    where
+        M: FromIterator<(K, V)>,
+        KA: DeserializeAs<'de, K>,
+        VA: DeserializeAs<'de, V>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<M, A::Error> {
+            let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+            while let Some((k, v)) =
+                map.next_entry::<DeserializeAsWrap<K, KA>, DeserializeAsWrap<V, VA>>()?
+            {
+                entries.push((k.into_inner(), v.into_inner()));
+            }
+            Ok(entries.into_iter().collect())
+        }
+    }
+
+    d.deserialize_map(MapVisitor::<M, K, V, KA, VA>(PhantomData))
+}
+
+impl<K, V, KA: SerializeAs<K>, VA: SerializeAs<V>> SerializeAs<BTreeMap<K, V>>
+    for BTreeMap<KA, VA>
+{
+    fn serialize_as<S: Serializer>(source: &BTreeMap<K, V>, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_map(source.iter().map(|(k, v)| {
+            (
+                SerializeAsWrap::<K, KA>::new(k),
+                SerializeAsWrap::<V, VA>::new(v),
+            )
+        }))
+    }
+}
+
+impl<'de, K: Ord, V, KA, VA> DeserializeAs<'de, BTreeMap<K, V>> for BTreeMap<KA, VA>
+where
+    KA: DeserializeAs<'de, K>,
+    VA: DeserializeAs<'de, V>,
+{
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<BTreeMap<K, V>, D::Error> {
+        deserialize_map_as::<D, _, K, V, KA, VA>(d)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, H, KA, VA> SerializeAs<HashMap<K, V, H>> for HashMap<KA, VA, H>
+where
+    KA: SerializeAs<K>,
+    VA: SerializeAs<V>,
+{
+    fn serialize_as<S: Serializer>(source: &HashMap<K, V, H>, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_map(source.iter().map(|(k, v)| {
+            (
+                SerializeAsWrap::<K, KA>::new(k),
+                SerializeAsWrap::<V, VA>::new(v),
+            )
+        }))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de, K, V, H, KA, VA> DeserializeAs<'de, HashMap<K, V, H>> for HashMap<KA, VA, H>
+where
+    K: Eq + Hash,
+    H: BuildHasher + Default,
+    KA: DeserializeAs<'de, K>,
+    VA: DeserializeAs<'de, V>,
+{
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<HashMap<K, V, H>, D::Error> {
+        deserialize_map_as::<D, _, K, V, KA, VA>(d)
+    }
+}
+
+/// A base64 alphabet for [Base64].
+#[cfg(feature = "base64")]
+pub trait Alphabet {
+    fn encode(bytes: &[u8]) -> String;
+    fn decode(text: &str) -> Result<Vec<u8>, String>;
+}
+
+/// The URL and filename safe alphabet, with padding, as used by the
+/// [base64](crate::base64) module.
+#[cfg(feature = "base64")]
+pub struct UrlSafe;
+
+#[cfg(feature = "base64")]
+impl Alphabet for UrlSafe {
+    fn encode(bytes: &[u8]) -> String {
+        general_purpose::URL_SAFE.encode(bytes)
+    }
+
+    fn decode(text: &str) -> Result<Vec<u8>, String> {
+        general_purpose::URL_SAFE
+            .decode(text)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// The standard alphabet, with padding.
+#[cfg(feature = "base64")]
+pub struct Standard;
+
+#[cfg(feature = "base64")]
+impl Alphabet for Standard {
+    fn encode(bytes: &[u8]) -> String {
+        general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn decode(text: &str) -> Result<Vec<u8>, String> {
+        general_purpose::STANDARD
+            .decode(text)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Bytes as a base64 string, URL safe unless another [Alphabet] is given.
+#[cfg(feature = "base64")]
+pub struct Base64<A: Alphabet = UrlSafe>(PhantomData<A>);
+
+#[cfg(feature = "base64")]
+impl<T: AsRef<[u8]>, A: Alphabet> SerializeAs<T> for Base64<A> {
+    fn serialize_as<S: Serializer>(source: &T, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&A::encode(source.as_ref()))
+    }
+}
+
+#[cfg(feature = "base64")]
+impl<'de, A: Alphabet> DeserializeAs<'de, Vec<u8>> for Base64<A> {
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(d)?;
+        A::decode(&text).map_err(de::Error::custom)
+    }
+}
+
+/// Bytes as a lowercase hex string.  Either case is read.
+pub struct Hex;
+
+impl<T: AsRef<[u8]>> SerializeAs<T> for Hex {
+    fn serialize_as<S: Serializer>(source: &T, s: S) -> Result<S::Ok, S::Error> {
+        let hex: String = source
+            .as_ref()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        s.serialize_str(&hex)
+    }
+}
+
+impl<'de> DeserializeAs<'de, Vec<u8>> for Hex {
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(d)?;
+        let invalid = || de::Error::custom(format!("invalid hex string \"{}\"", text));
+        if text.len() % 2 != 0 || !text.is_ascii() {
+            return Err(invalid());
+        }
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| invalid()))
+            .collect()
+    }
+}
+
+/// A `Vec` that may be written as a single value, as in
+/// [vec_or_one](crate::vec_or_one), with the adapter `A` for each element.
+/// The value is buffered, so the format must be self describing.
+pub struct OneOrMany<A = Same>(PhantomData<A>);
+
+impl<T, A: SerializeAs<T>> SerializeAs<Vec<T>> for OneOrMany<A> {
+    fn serialize_as<S: Serializer>(source: &Vec<T>, s: S) -> Result<S::Ok, S::Error> {
+        match source.as_slice() {
+            [one] => A::serialize_as(one, s),
+            _ => Vec::<A>::serialize_as(source, s),
+        }
+    }
+}
+
+impl<'de, T, A: DeserializeAs<'de, T>> DeserializeAs<'de, Vec<T>> for OneOrMany<A> {
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<Vec<T>, D::Error> {
+        match Content::deserialize(d)? {
+            content @ Content::Seq(_) => {
+                Vec::<A>::deserialize_as(ContentDeserializer::<D::Error>::new(content))
+            }
+            content => {
+                A::deserialize_as(ContentDeserializer::<D::Error>::new(content)).map(|v| vec![v])
+            }
+        }
+    }
+}
+
+/// A type read from a string with `FromStr`, or from a map, as in
+/// [string_or_struct](crate::string_or_struct).  Written unchanged.
+pub struct StringOrStruct;
+
+impl<T: Serialize> SerializeAs<T> for StringOrStruct {
+    fn serialize_as<S: Serializer>(source: &T, s: S) -> Result<S::Ok, S::Error> {
+        source.serialize(s)
+    }
+}
+
+impl<'de, T> DeserializeAs<'de, T> for StringOrStruct
+where
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+{
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<T, D::Error> {
+        crate::string_or_struct::try_deserialize(d)
+    }
+}
+
+/// A type written with `Display` and read with `FromStr`.
+pub struct DisplayFromStr;
+
+impl<T: Display> SerializeAs<T> for DisplayFromStr {
+    fn serialize_as<S: Serializer>(source: &T, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(source)
+    }
+}
+
+impl<'de, T> DeserializeAs<'de, T> for DisplayFromStr
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<T, D::Error> {
+        let text = String::deserialize(d)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "std", feature = "base64"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+    use std::net::Ipv4Addr;
+
+    use super::{As, Base64, DisplayFromStr, Hex, OneOrMany, Same, Standard, StringOrStruct};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(default, with = "As::<Option<Base64>>")]
+        pub key: Option<Vec<u8>>,
+        #[serde(with = "As::<Vec<Hex>>")]
+        pub hashes: Vec<Vec<u8>>,
+        #[serde(with = "As::<BTreeMap<Same, OneOrMany>>")]
+        pub hosts: BTreeMap<String, Vec<String>>,
+        #[serde(with = "As::<HashMap<DisplayFromStr, Vec<Base64<Standard>>>>")]
+        pub certs: HashMap<Ipv4Addr, Vec<Vec<u8>>>,
+        #[serde(with = "As::<OneOrMany<DisplayFromStr>>")]
+        pub ports: Vec<u16>,
+    }
+
+    fn outer() -> Outer {
+        Outer {
+            key: Some(vec![0, 1, 2]),
+            hashes: vec![vec![0x00, 0xff], vec![0xbe, 0xef]],
+            hosts: BTreeMap::from([
+                ("db".to_string(), vec!["b".to_string(), "c".to_string()]),
+                ("web".to_string(), vec!["a".to_string()]),
+            ]),
+            certs: HashMap::from([(Ipv4Addr::LOCALHOST, vec![vec![0xfb, 0xff]])]),
+            ports: vec![443],
+        }
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "key": "AAEC",
+            "hashes": ["00FF", "beef"],
+            "hosts": {"web": "a", "db": ["b", "c"]},
+            "certs": {"127.0.0.1": ["+/8="]},
+            "ports": "443"
+        }"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result, outer());
+
+        let json = r#"{"key": null, "hashes": [], "hosts": {}, "certs": {}, "ports": ["1", "2"]}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.key, None);
+        assert_eq!(result.ports, vec![1, 2]);
+    }
+
+    #[test]
+    fn serialize() {
+        let result = serde_json::to_string(&outer()).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"key":"AAEC","hashes":["00ff","beef"],"hosts":{"db":["b","c"],"web":"a"},"certs":{"127.0.0.1":["+/8="]},"ports":"443"}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (json, msg) in [
+            (r#"["0g"]"#, "invalid hex string \"0g\""),
+            (r#"["abc"]"#, "invalid hex string \"abc\""),
+        ] {
+            let err = As::<Vec<Hex>>::deserialize::<_, Vec<Vec<u8>>>(
+                &mut serde_json::Deserializer::from_str(json),
+            )
+            .unwrap_err()
+            .to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+
+    #[test]
+    fn string_or_struct() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Inner {
+            pub item: String,
+        }
+
+        impl std::str::FromStr for Inner {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, String> {
+                Ok(Inner {
+                    item: s.to_string(),
+                })
+            }
+        }
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Items {
+            #[serde(with = "As::<Vec<StringOrStruct>>")]
+            pub items: Vec<Inner>,
+        }
+
+        let json = r#"{"items": ["a", {"item": "b"}]}"#;
+        let result: Items = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.items[0].item, "a");
+        assert_eq!(result.items[1].item, "b");
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn stuff_attribute() {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        #[crate::stuff(crate = "crate")]
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[stuff(base64)]
+            pub key: Vec<u8>,
+            #[stuff(base64, standard)]
+            pub cert: Option<Vec<u8>>,
+            #[stuff(hex)]
+            pub hash: Option<Vec<u8>>,
+            #[stuff(one_or_many)]
+            pub hosts: Vec<String>,
+            #[stuff(timestamp = "millis")]
+            pub expires: Option<SystemTime>,
+            #[serde(rename = "v")]
+            pub version: u8,
+        }
+
+        let json = r#"{"key": "-_8=", "cert": "+/8=", "hosts": "a", "expires": 1500, "v": 1}"#;
+        let outer = Outer {
+            key: vec![0xfb, 0xff],
+            cert: Some(vec![0xfb, 0xff]),
+            hash: None,
+            hosts: vec!["a".to_string()],
+            expires: Some(UNIX_EPOCH + Duration::from_millis(1500)),
+            version: 1,
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+        assert_eq!(
+            serde_json::to_string(&outer).expect("Oops!"),
+            r#"{"key":"-_8=","cert":"+/8=","hosts":"a","expires":1500,"v":1}"#
+        );
+    }
+}
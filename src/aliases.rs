@@ -0,0 +1,195 @@
+//! Deserialize with a table of old field and variant names
+//!
+//! Historical data keeps the names a type had when it was written.
+//! `#[serde(alias)]` handles that, but the aliases stay on the type forever.
+//! This module takes an alias table, from old names to current ones, at
+//! runtime.  While deserializing, a struct field or enum variant that is
+//! unknown by its old name is read as its current name, at any depth.
+//! Serializing is unchanged, so current names are written.
+//!
+//! [deserialize_with] takes the table directly, and the
+//! [aliases!](crate::aliases!) macro generates a `with` module for a fixed
+//! one:
+//!
+//! ```rust
+//! mod legacy {
+//!     serde_stuff::aliases!("colour" => "color");
+//! }
+//!
+//! serde_stuff::aliases!(pub v1, "colour" => "color", "Warn" => "Warning");
+//! ```
+//!
+//! An alias only applies where its current name is a field or variant of
+//! the type being read, so map keys are never renamed.  Since the value is
+//! buffered as [Content](crate::content::Content), the format must be self
+//! describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! serde_stuff::aliases!(v1, "colour" => "color", "Warn" => "Warning");
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub enum Level {
+//!     Info,
+//!     Warning,
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Theme {
+//!     pub color: String,
+//!     pub level: Level,
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "v1")]
+//!     pub theme: Theme,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "theme": {"colour": "red", "level": "Warn"}
+//! }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+
+use super::content::Content;
+use super::renamed::Renamed;
+
+/// Deserialize a `T`, reading the old names in `aliases` as the new ones.
+pub fn deserialize_with<'de, D, T>(d: D, aliases: &[(&str, &str)]) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let rename = |name: &str, names: &'static [&'static str]| {
+        aliases
+            .iter()
+            .filter(|(old, _)| *old == name)
+            .find_map(|(_, new)| names.iter().find(|known| *known == new).copied())
+    };
+    T::deserialize(Renamed::new(Content::deserialize(d)?, &rename))
+}
+
+/// Generate a serde `with` module for a fixed alias table.
+///
+/// With only the table, the functions are generated in place.  With a
+/// visibility and name first, a module of that name is declared.
+#[macro_export]
+macro_rules! aliases {
+    ($vis:vis $name:ident, $($old:literal => $new:literal),+ $(,)?) => {
+        $vis mod $name {
+            $crate::aliases!($($old => $new),+);
+        }
+    };
+    ($($old:literal => $new:literal),+ $(,)?) => {
+        #[allow(dead_code)]
+        pub fn serialize<S, T>(v: &T, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+            T: serde::Serialize,
+        {
+            v.serialize(s)
+        }
+
+        #[allow(dead_code)]
+        pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+            T: serde::de::DeserializeOwned,
+        {
+            $crate::aliases::deserialize_with(d, &[$(($old, $new)),+])
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::HashMap;
+
+    crate::aliases!(v1, "colour" => "color", "Warn" => "Warning", "Hex" => "Rgb");
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub enum Level {
+        Info,
+        Warning,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub enum Color {
+        Named(String),
+        Rgb { colour: u32 },
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Theme {
+        pub color: Color,
+        pub levels: Vec<Level>,
+        pub labels: HashMap<String, String>,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "v1")]
+        pub theme: Theme,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"theme": {
+            "colour": {"Hex": {"colour": 255}},
+            "levels": ["Info", "Warn"],
+            "labels": {"colour": "red"}
+        }}"#;
+        let outer = Outer {
+            theme: Theme {
+                color: Color::Rgb { colour: 255 },
+                levels: vec![Level::Info, Level::Warning],
+                labels: HashMap::from([("colour".to_string(), "red".to_string())]),
+            },
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            theme: Theme {
+                color: Color::Named("red".to_string()),
+                levels: vec![Level::Warning],
+                labels: HashMap::new(),
+            },
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"theme":{"color":{"Named":"red"},"levels":["Warning"],"labels":{}}}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_with() {
+        let json = r#"{"color": {"Named": "red"}, "levels": ["Alert"], "labels": {}}"#;
+        let mut de = serde_json::Deserializer::from_str(json);
+        let theme: Theme =
+            super::deserialize_with(&mut de, &[("Alert", "Warning")]).expect("Oops!");
+        assert_eq!(theme.levels, vec![Level::Warning]);
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let err = super::deserialize_with::<_, Theme>(&mut de, &[]).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown variant `Alert`"),
+            "{}",
+            err
+        );
+    }
+}
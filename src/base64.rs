@@ -13,6 +13,8 @@
 //! }
 //! ```
 
+use alloc::string::String;
+use alloc::vec::Vec;
 use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
 use serde::{Deserializer, Serializer};
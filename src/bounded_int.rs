@@ -0,0 +1,108 @@
+//! Deserialize an integer, rejecting values outside of `MIN..=MAX`
+//!
+//! The bounds are const generics, so the range check lives right next to the
+//! field instead of in a separate validation pass.  Since const generics can't
+//! be passed through `#[serde(with = "...")]`, reference the functions
+//! directly and supply the bounds with a turbofish.  Any integer type that
+//! converts losslessly into `i128` is supported.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(deserialize_with = "serde_stuff::bounded_int::deserialize::<_, _, 1, 10>")]
+//!     pub level: u8,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "level": 5
+//! }
+//! ```
+//! The following fails with `invalid value: integer `11`, expected an integer in the range 1..=10`
+//! ```json
+//! {
+//!     "level": 11
+//! }
+//! ```
+
+use serde::de::{self, Unexpected};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn deserialize<'de, D, T, const MIN: i64, const MAX: i64>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Copy + Into<i128>,
+{
+    let value = T::deserialize(d)?;
+    let n: i128 = value.into();
+    if n < MIN as i128 || n > MAX as i128 {
+        let unexpected = match i64::try_from(n) {
+            Ok(n) => Unexpected::Signed(n),
+            Err(_) => Unexpected::Other("an integer out of the i64 range"),
+        };
+        let expected = format!("an integer in the range {}..={}", MIN, MAX);
+        return Err(de::Error::invalid_value(unexpected, &expected.as_str()));
+    }
+    Ok(value)
+}
+
+/// Bounded integers serialize unchanged.  This is provided so the module can
+/// be referenced symmetrically with `serialize_with`.
+pub fn serialize<S: Serializer, T: Serialize>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    v.serialize(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    pub struct Outer {
+        #[serde(deserialize_with = "crate::bounded_int::deserialize::<_, _, 1, 10>")]
+        pub level: u8,
+        #[serde(deserialize_with = "crate::bounded_int::deserialize::<_, _, -5, 5>")]
+        pub offset: i32,
+    }
+
+    #[test]
+    fn deserialize_in_range() {
+        let json = r#"{"level": 10, "offset": -5}"#;
+        let outer = Outer {
+            level: 10,
+            offset: -5,
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_out_of_range() {
+        let json = r#"{"level": 11, "offset": 0}"#;
+        let result = serde_json::from_str::<Outer>(json);
+        assert!(result.is_err());
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("1..=10"), "{}", msg);
+
+        let json = r#"{"level": 1, "offset": -6}"#;
+        let result = serde_json::from_str::<Outer>(json);
+        assert!(result.is_err());
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("-5..=5"), "{}", msg);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            level: 3,
+            offset: -2,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"level":3,"offset":-2}"#);
+    }
+}
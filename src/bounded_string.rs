@@ -0,0 +1,96 @@
+//! Deserialize a `String`, enforcing a maximum (and optional minimum) length
+//!
+//! As with [bounded_int](crate::bounded_int), the limits are const generics
+//! supplied with a turbofish.  Use `deserialize::<_, MAX>` to cap the length,
+//! or `deserialize_between::<_, MIN, MAX>` to bound it on both sides.  Length
+//! is measured in characters, matching `VARCHAR(n)` style column constraints.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(deserialize_with = "serde_stuff::bounded_string::deserialize::<_, 64>")]
+//!     pub title: String,
+//!     #[serde(deserialize_with = "serde_stuff::bounded_string::deserialize_between::<_, 3, 16>")]
+//!     pub username: String,
+//! }
+//! ```
+
+use serde::de;
+use serde::{Deserialize, Deserializer, Serializer};
+
+fn check<E: de::Error>(value: String, min: usize, max: usize) -> Result<String, E> {
+    let len = value.chars().count();
+    if len < min || len > max {
+        let expected = match min {
+            0 => format!("a string of at most {} characters", max),
+            _ => format!("a string of {} to {} characters", min, max),
+        };
+        return Err(E::invalid_length(len, &expected.as_str()));
+    }
+    Ok(value)
+}
+
+pub fn deserialize<'de, D, const MAX: usize>(d: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    check(String::deserialize(d)?, 0, MAX)
+}
+
+pub fn deserialize_between<'de, D, const MIN: usize, const MAX: usize>(
+    d: D,
+) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    check(String::deserialize(d)?, MIN, MAX)
+}
+
+/// Bounded strings serialize unchanged.  This is provided so the module can
+/// be referenced symmetrically with `serialize_with`.
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(v)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(deserialize_with = "crate::bounded_string::deserialize::<_, 5>")]
+        pub code: String,
+        #[serde(deserialize_with = "crate::bounded_string::deserialize_between::<_, 2, 4>")]
+        pub name: String,
+    }
+
+    #[test]
+    fn deserialize_in_bounds() {
+        let outer = Outer {
+            code: "ÅÄÖÅÄ".to_string(),
+            name: "ab".to_string(),
+        };
+        let result: Outer =
+            serde_json::from_str(r#"{"code": "ÅÄÖÅÄ", "name": "ab"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_out_of_bounds() {
+        let result = serde_json::from_str::<Outer>(r#"{"code": "abcdef", "name": "ab"}"#);
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("at most 5 characters"), "{}", msg);
+
+        let result = serde_json::from_str::<Outer>(r#"{"code": "", "name": "a"}"#);
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("2 to 4 characters"), "{}", msg);
+
+        let result = serde_json::from_str::<Outer>(r#"{"code": "", "name": "abcde"}"#);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,303 @@
+//! Serialize to canonical JSON, as in RFC 8785 (JCS)
+//!
+//! A signature or hash over a JSON document only verifies if both sides
+//! produce the same bytes, but the same value can be written many ways,
+//! with keys in any order, and numbers and strings in more than one form.
+//! [to_vec] writes the one form defined by the JSON Canonicalization
+//! Scheme: no whitespace, object keys sorted by their UTF-16 code units,
+//! numbers as ECMAScript writes them, and only the required escapes in
+//! strings.  Sign the bytes, and put the signature in a field using the
+//! [base64](crate::base64) module.
+//!
+//! JCS numbers are IEEE doubles, so integers beyond 2^53 are an error,
+//! rather than silently rounded, as are `NaN` and infinities.  Write such
+//! values as strings.  Map keys must be strings.  Bytes are written as an
+//! array of numbers.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::Serialize;
+//! use serde_stuff::canonical_json;
+//!
+//! #[derive(Serialize)]
+//! pub struct Outer {
+//!     pub name: String,
+//!     pub amount: f64,
+//!     pub id: u32,
+//! }
+//!
+//! let outer = Outer { name: "caf\u{e9}".to_string(), amount: 1e21, id: 7 };
+//! assert_eq!(
+//!     canonical_json::to_string(&outer).unwrap(),
+//!     r#"{"amount":1e+21,"id":7,"name":"café"}"#
+//! );
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use serde::de::{value::Error, Error as _};
+use serde::Serialize;
+
+use super::content::Content;
+
+/// The largest integer that a double holds exactly.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Serialize `value` as canonical JSON.
+pub fn to_string<T: ?Sized + Serialize>(value: &T) -> Result<String, Error> {
+    let mut out = String::new();
+    write_content(&mut out, &Content::from_value(value)?)?;
+    Ok(out)
+}
+
+/// Serialize `value` as canonical JSON, UTF-8 encoded.
+pub fn to_vec<T: ?Sized + Serialize>(value: &T) -> Result<Vec<u8>, Error> {
+    to_string(value).map(String::into_bytes)
+}
+
+fn write_content(out: &mut String, content: &Content) -> Result<(), Error> {
+    match content {
+        Content::Unit => out.push_str("null"),
+        Content::Bool(v) => out.push_str(if *v { "true" } else { "false" }),
+        Content::I64(v) if v.unsigned_abs() <= MAX_SAFE_INTEGER => {
+            let _ = write!(out, "{}", v);
+        }
+        Content::U64(v) if *v <= MAX_SAFE_INTEGER => {
+            let _ = write!(out, "{}", v);
+        }
+        Content::I64(v) => return Err(unsafe_integer(v)),
+        Content::U64(v) => return Err(unsafe_integer(v)),
+        Content::F64(v) => write_number(out, *v)?,
+        Content::String(v) => write_string(out, v),
+        Content::Bytes(v) => {
+            out.push('[');
+            for (i, byte) in v.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write!(out, "{}", byte);
+            }
+            out.push(']');
+        }
+        Content::Seq(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_content(out, item)?;
+            }
+            out.push(']');
+        }
+        Content::Map(entries) => {
+            let mut sorted = Vec::with_capacity(entries.len());
+            for (key, value) in entries {
+                match key {
+                    Content::String(key) => sorted.push((key.encode_utf16().collect(), key, value)),
+                    _ => return Err(Error::custom("map keys must be strings")),
+                }
+            }
+            sorted.sort_by(|(a, _, _): &(Vec<u16>, _, _), (b, _, _)| a.cmp(b));
+            if sorted.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+                return Err(Error::custom("duplicate map key"));
+            }
+            out.push('{');
+            for (i, (_, key, value)) in sorted.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_content(out, value)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn unsafe_integer(v: impl core::fmt::Display) -> Error {
+    Error::custom(format!(
+        "integer {} is too large for canonical JSON, write it as a string",
+        v
+    ))
+}
+
+/// Write `v` as ECMAScript's `Number.prototype.toString` does.
+fn write_number(out: &mut String, v: f64) -> Result<(), Error> {
+    if !v.is_finite() {
+        return Err(Error::custom(format!("{} is not a JSON number", v)));
+    }
+    if v == 0.0 {
+        out.push('0');
+        return Ok(());
+    }
+    // The shortest digits that round trip, and the exponent of the first.
+    let sci = format!("{:e}", v.abs());
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    // The position of the decimal point, after the first `n` digits.
+    let n = exp.parse::<i32>().unwrap_or(0) + 1;
+    if v < 0.0 {
+        out.push('-');
+    }
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(core::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(core::iter::repeat_n('0', -n as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        let _ = write!(out, "e{}{}", if n > 0 { "+" } else { "-" }, (n - 1).abs());
+    }
+    Ok(())
+}
+
+fn write_string(out: &mut String, v: &str) {
+    out.push('"');
+    for c in v.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::to_string;
+
+    fn number(v: f64) -> String {
+        to_string(&v).expect("Oops!")
+    }
+
+    #[test]
+    fn numbers() {
+        // From the RFC 8785 appendix, and the ECMAScript rules.
+        assert_eq!(number(0.0), "0");
+        assert_eq!(number(-0.0), "0");
+        assert_eq!(number(1.0), "1");
+        assert_eq!(number(-1.5), "-1.5");
+        assert_eq!(number(1e21), "1e+21");
+        assert_eq!(number(1e20), "100000000000000000000");
+        assert_eq!(number(1e-7), "1e-7");
+        assert_eq!(number(0.000001), "0.000001");
+        assert_eq!(number(123.456), "123.456");
+        assert_eq!(number(5e-324), "5e-324");
+        assert_eq!(number(1.7976931348623157e308), "1.7976931348623157e+308");
+        assert_eq!(number(9007199254740992.0), "9007199254740992");
+        assert_eq!(number(295147905179352830000.0), "295147905179352830000");
+        assert_eq!(number(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(number(333333333.3333332), "333333333.3333332");
+    }
+
+    #[test]
+    fn bad_numbers() {
+        let err = to_string(&f64::NAN).expect_err("Oops!").to_string();
+        assert!(err.contains("not a JSON number"), "{}", err);
+        let err = to_string(&u64::MAX).expect_err("Oops!").to_string();
+        let msg = "integer 18446744073709551615 is too large for canonical JSON";
+        assert!(err.contains(msg), "{}", err);
+        assert_eq!(
+            to_string(&9007199254740991u64).expect("Oops!"),
+            "9007199254740991"
+        );
+    }
+
+    #[test]
+    fn strings() {
+        let json = to_string("\u{20ac}$\u{f}\nA'B\"\\\\\"/").expect("Oops!");
+        assert_eq!(json, "\"\u{20ac}$\\u000f\\nA'B\\\"\\\\\\\\\\\"/\"");
+    }
+
+    #[test]
+    fn sorted_keys() {
+        // Sorted by UTF-16 code units, so the astral emoji sorts before
+        // U+FB33, unlike in UTF-8 or by `char`.
+        let map: HashMap<&str, u32> = [
+            ("\u{20ac}", 1),
+            ("\r", 2),
+            ("\u{fb33}", 3),
+            ("1", 4),
+            ("\u{1f600}", 5),
+            ("\u{80}", 6),
+            ("\u{f6}", 7),
+        ]
+        .into_iter()
+        .collect();
+        let json = to_string(&map).expect("Oops!");
+        assert_eq!(
+            json,
+            "{\"\\r\":2,\"1\":4,\"\u{80}\":6,\"\u{f6}\":7,\"\u{20ac}\":1,\"\u{1f600}\":5,\"\u{fb33}\":3}"
+        );
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn structs() {
+        use serde::Serialize;
+
+        use super::to_vec;
+
+        #[derive(Serialize)]
+        pub struct Inner {
+            pub b: Vec<u8>,
+            pub a: Option<bool>,
+        }
+
+        #[derive(Serialize)]
+        pub struct Outer {
+            pub z: String,
+            pub inner: Inner,
+            #[serde(with = "crate::base64")]
+            pub signature: Vec<u8>,
+        }
+
+        let outer = Outer {
+            z: "last".to_string(),
+            inner: Inner {
+                b: vec![1, 2],
+                a: None,
+            },
+            signature: vec![0xde, 0xad],
+        };
+        let json = to_vec(&outer).expect("Oops!");
+        assert_eq!(
+            json,
+            br#"{"inner":{"a":null,"b":[1,2]},"signature":"3q0=","z":"last"}"#
+        );
+    }
+
+    #[test]
+    fn non_string_keys() {
+        let map: HashMap<u32, u32> = [(1, 2)].into_iter().collect();
+        let err = to_string(&map).expect_err("Oops!").to_string();
+        assert!(err.contains("map keys must be strings"), "{}", err);
+    }
+}
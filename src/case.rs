@@ -0,0 +1,60 @@
+//! Shared implementation for the [lowercase](crate::lowercase) and
+//! [uppercase](crate::uppercase) modules.
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    d: D,
+    convert: fn(&str) -> String,
+) -> Result<String, D::Error> {
+    let value = String::deserialize(d)?;
+    Ok(convert(&value))
+}
+
+pub(crate) fn serialize<S: Serializer>(
+    v: &str,
+    s: S,
+    convert: fn(&str) -> String,
+) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&convert(v))
+}
+
+pub(crate) fn deserialize_keys<'de, D, V, M>(
+    d: D,
+    convert: fn(&str) -> String,
+) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+    M: FromIterator<(String, V)>,
+{
+    struct KeysVisitor<V, M>(fn(&str) -> String, PhantomData<(V, M)>);
+
+    impl<'de, V, M> Visitor<'de> for KeysVisitor<V, M>
+    where
+        V: Deserialize<'de>,
+        M: FromIterator<(String, V)>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map with string keys")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<M, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+            while let Some((key, value)) = map.next_entry::<String, V>()? {
+                entries.push(((self.0)(&key), value));
+            }
+            Ok(entries.into_iter().collect())
+        }
+    }
+
+    d.deserialize_map(KeysVisitor(convert, PhantomData))
+}
@@ -0,0 +1,249 @@
+//! Deserialize enum variants regardless of case
+//!
+//! Human edited configs write `"Info"`, `"INFO"` and `"info"` and expect
+//! them all to work.  This module matches the variant name of an enum
+//! case-insensitively against the names serde knows, including any
+//! `rename` or `rename_all`, and then deserializes as usual.  Serializing is
+//! unchanged, so the canonical casing is written back.
+//!
+//! The [loose] submodule also ignores `-`, `_` and spaces, so `"dry-run"`,
+//! `"DRY_RUN"` and `"DryRun"` all match `DryRun`.
+//!
+//! In [strict](crate::strictness) mode, only the exact name matches.
+//!
+//! Variants with data, written as `{"Variant": ...}`, are matched the same
+//! way.  Since the value is buffered as [Content](crate::content::Content),
+//! the format must be self describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub enum Level {
+//!     Debug,
+//!     Info,
+//!     Warn,
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::case_insensitive")]
+//!     pub level: Level,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "level": "INFO"
+//! }
+//! ```
+
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::marker::PhantomData;
+
+use super::content::{Content, ContentDeserializer};
+use super::strictness;
+
+/// Replays a [Content], renaming the variant of an enum to the one it
+/// matches.
+struct CaseInsensitive<E> {
+    content: Content,
+    fold: fn(&str) -> String,
+    marker: PhantomData<E>,
+}
+
+/// The variant in `variants` that `name` folds to, if it is not one already.
+fn rename(name: Content, variants: &[&str], fold: fn(&str) -> String) -> Content {
+    let matched = match name.as_str() {
+        Some(name) if !variants.contains(&name) => {
+            let folded = fold(name);
+            let matched = variants.iter().find(|variant| fold(variant) == folded);
+            if let Some(variant) = matched {
+                strictness::repaired(format!("read {:?} as `{}`", name, variant));
+            }
+            matched
+        }
+        _ => None,
+    };
+    match matched {
+        Some(variant) => Content::String(variant.to_string()),
+        None => name,
+    }
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for CaseInsensitive<E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        ContentDeserializer::new(self.content).deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        ContentDeserializer::new(self.content).deserialize_option(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        let content = match self.content {
+            Content::Map(mut entries) if entries.len() == 1 => {
+                let (variant, value) = entries.pop().unwrap();
+                Content::Map(vec![(rename(variant, variants, self.fold), value)])
+            }
+            Content::Map(entries) => Content::Map(entries),
+            variant => rename(variant, variants, self.fold),
+        };
+        ContentDeserializer::new(content).deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+fn fold_case(name: &str) -> String {
+    name.to_lowercase()
+}
+
+fn fold_loose(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn deserialize_with<'de, D, T>(d: D, fold: fn(&str) -> String) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    if strictness::is_strict() {
+        return T::deserialize(d);
+    }
+    T::deserialize(CaseInsensitive {
+        content: Content::deserialize(d)?,
+        fold,
+        marker: PhantomData,
+    })
+}
+
+pub fn serialize<S: Serializer, T: Serialize>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    v.serialize(s)
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    deserialize_with(d, fold_case)
+}
+
+/// Also ignore `-`, `_` and spaces in variant names.
+pub mod loose {
+    use serde::de::DeserializeOwned;
+    use serde::{Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, T: Serialize>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        v.serialize(s)
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: DeserializeOwned,
+    {
+        super::deserialize_with(d, super::fold_loose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub enum Level {
+        Debug,
+        Info,
+        #[serde(rename = "warning")]
+        Warn,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub enum Mode {
+        DryRun,
+        Apply { force: bool },
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::case_insensitive")]
+        pub level: Level,
+        #[serde(with = "crate::case_insensitive::loose")]
+        pub mode: Mode,
+    }
+
+    #[test]
+    fn deserialize() {
+        for (level, mode, outer) in [
+            (
+                "INFO",
+                r#""dry-run""#,
+                Outer {
+                    level: Level::Info,
+                    mode: Mode::DryRun,
+                },
+            ),
+            (
+                "Warning",
+                r#""DRY_RUN""#,
+                Outer {
+                    level: Level::Warn,
+                    mode: Mode::DryRun,
+                },
+            ),
+            (
+                "debug",
+                r#"{"APPLY": {"force": true}}"#,
+                Outer {
+                    level: Level::Debug,
+                    mode: Mode::Apply { force: true },
+                },
+            ),
+        ] {
+            let json = format!(r#"{{"level": "{}", "mode": {}}}"#, level, mode);
+            let result: Outer = serde_json::from_str(&json).expect("Oops!");
+            assert_eq!(&outer, &result);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            level: Level::Warn,
+            mode: Mode::DryRun,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"level":"warning","mode":"DryRun"}"#);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"level": "dry-run", "mode": "DryRun"}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("unknown variant `dry-run`"), "{}", err);
+
+        let json = r#"{"level": "info", "mode": "dry run!"}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("unknown variant `dry run!`"), "{}", err);
+    }
+}
@@ -0,0 +1,86 @@
+//! Serialize and Deserialize a `char` as a single-character string
+//!
+//! Many formats have no native character type, and serde's default handling
+//! differs between them.  This module always writes a one-character string,
+//! and only accepts a string containing exactly one character.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::char_string")]
+//!     pub separator: char,
+//! }
+//! ```
+//! The following will deserialize to `Outer { separator: ';' }`
+//! ```json
+//! {
+//!     "separator": ";"
+//! }
+//! ```
+//! The following fails with `invalid length 2, expected exactly one character`
+//! ```json
+//! {
+//!     "separator": ";;"
+//! }
+//! ```
+
+use serde::de;
+use serde::{Deserialize, Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(v: &char, s: S) -> Result<S::Ok, S::Error> {
+    let mut buf = [0u8; 4];
+    s.serialize_str(v.encode_utf8(&mut buf))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<char, D::Error> {
+    let value = String::deserialize(d)?;
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(de::Error::invalid_length(
+            value.chars().count(),
+            &"exactly one character",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::char_string")]
+        pub separator: char,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer { separator: 'é' };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"separator":"é"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer { separator: ';' };
+        let result: Outer = serde_json::from_str(r#"{"separator": ";"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_wrong_length() {
+        let result = serde_json::from_str::<Outer>(r#"{"separator": ""}"#);
+        assert!(result.is_err());
+        let result = serde_json::from_str::<Outer>(r#"{"separator": ";;"}"#);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("expected exactly one character"));
+    }
+}
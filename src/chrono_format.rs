@@ -0,0 +1,262 @@
+//! Serialize and Deserialize `chrono` types with a custom strftime format
+//!
+//! Requires the `chrono` feature.  One-off vendor date formats don't each
+//! need a hand-written module: the [datetime_format!](crate::datetime_format!)
+//! macro generates `serialize` and `deserialize` functions for a format
+//! string, plus an `option` companion module.
+//!
+//! The macro can be invoked inside a module you declare, or given a module
+//! name to declare:
+//!
+//! ```rust
+//! mod vendor_date {
+//!     serde_stuff::datetime_format!("%d/%m/%Y %H:%M");
+//! }
+//!
+//! serde_stuff::datetime_format!(pub us_date, "%m/%d/%Y");
+//! ```
+//!
+//! [ChronoFormat] is implemented for `DateTime<Utc>`, `DateTime<FixedOffset>`,
+//! `NaiveDateTime` and `NaiveDate`.  A `DateTime<Utc>` is parsed as UTC when
+//! the format has no offset.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use chrono::{DateTime, NaiveDate, Utc};
+//! use serde::{Deserialize, Serialize};
+//!
+//! mod vendor_date {
+//!     serde_stuff::datetime_format!("%d/%m/%Y %H:%M");
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "vendor_date")]
+//!     pub created: DateTime<Utc>,
+//!     #[serde(default, with = "vendor_date::option")]
+//!     pub closed: Option<DateTime<Utc>>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "created": "14/11/2023 22:13"
+//! }
+//! ```
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::fmt::Write;
+
+/// `chrono` types that can be formatted and parsed with a strftime format.
+pub trait ChronoFormat: Sized {
+    fn format_with(&self, format: &str) -> Result<String, String>;
+    fn parse_with(value: &str, format: &str) -> Result<Self, String>;
+}
+
+fn write_format(value: impl std::fmt::Display, format: &str) -> Result<String, String> {
+    let mut out = String::new();
+    write!(out, "{}", value).map_err(|_| format!("invalid date format \"{}\"", format))?;
+    Ok(out)
+}
+
+fn parse_error(e: chrono::ParseError, value: &str, format: &str) -> String {
+    format!("{} in \"{}\" for format \"{}\"", e, value, format)
+}
+
+impl ChronoFormat for DateTime<Utc> {
+    fn format_with(&self, format: &str) -> Result<String, String> {
+        write_format(self.format(format), format)
+    }
+
+    fn parse_with(value: &str, format: &str) -> Result<Self, String> {
+        match DateTime::parse_from_str(value, format) {
+            Ok(v) => Ok(v.with_timezone(&Utc)),
+            Err(_) => NaiveDateTime::parse_from_str(value, format)
+                .map(|v| v.and_utc())
+                .map_err(|e| parse_error(e, value, format)),
+        }
+    }
+}
+
+impl ChronoFormat for DateTime<FixedOffset> {
+    fn format_with(&self, format: &str) -> Result<String, String> {
+        write_format(self.format(format), format)
+    }
+
+    fn parse_with(value: &str, format: &str) -> Result<Self, String> {
+        DateTime::parse_from_str(value, format).map_err(|e| parse_error(e, value, format))
+    }
+}
+
+impl ChronoFormat for NaiveDateTime {
+    fn format_with(&self, format: &str) -> Result<String, String> {
+        write_format(self.format(format), format)
+    }
+
+    fn parse_with(value: &str, format: &str) -> Result<Self, String> {
+        NaiveDateTime::parse_from_str(value, format).map_err(|e| parse_error(e, value, format))
+    }
+}
+
+impl ChronoFormat for NaiveDate {
+    fn format_with(&self, format: &str) -> Result<String, String> {
+        write_format(self.format(format), format)
+    }
+
+    fn parse_with(value: &str, format: &str) -> Result<Self, String> {
+        NaiveDate::parse_from_str(value, format).map_err(|e| parse_error(e, value, format))
+    }
+}
+
+/// Serialize `v` with a strftime `format`.
+pub fn serialize_with<S, T>(v: &T, s: S, format: &str) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ChronoFormat,
+{
+    let value = v.format_with(format).map_err(serde::ser::Error::custom)?;
+    String::serialize(&value, s)
+}
+
+/// Deserialize a string with a strftime `format`.
+pub fn deserialize_with<'de, D, T>(d: D, format: &str) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ChronoFormat,
+{
+    let value = String::deserialize(d)?;
+    T::parse_with(&value, format).map_err(serde::de::Error::custom)
+}
+
+/// Serialize an `Option` with a strftime `format`.
+pub fn serialize_option_with<S, T>(v: &Option<T>, s: S, format: &str) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ChronoFormat,
+{
+    let value = v
+        .as_ref()
+        .map(|v| v.format_with(format))
+        .transpose()
+        .map_err(serde::ser::Error::custom)?;
+    <Option<String>>::serialize(&value, s)
+}
+
+/// Deserialize an `Option` with a strftime `format`.
+pub fn deserialize_option_with<'de, D, T>(d: D, format: &str) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ChronoFormat,
+{
+    <Option<String>>::deserialize(d)?
+        .map(|v| T::parse_with(&v, format))
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+/// Generate a serde `with` module for a chrono strftime format.
+///
+/// With only a format, the functions are generated in place.  With a
+/// visibility and name first, a module of that name is declared.
+#[macro_export]
+macro_rules! datetime_format {
+    ($vis:vis $name:ident, $format:literal) => {
+        $vis mod $name {
+            $crate::datetime_format!($format);
+        }
+    };
+    ($format:literal) => {
+        #[allow(dead_code)]
+        pub fn serialize<S, T>(v: &T, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+            T: $crate::chrono_format::ChronoFormat,
+        {
+            $crate::chrono_format::serialize_with(v, s, $format)
+        }
+
+        #[allow(dead_code)]
+        pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+            T: $crate::chrono_format::ChronoFormat,
+        {
+            $crate::chrono_format::deserialize_with(d, $format)
+        }
+
+        /// The `Option` companion.  Use with `default`.
+        #[allow(dead_code)]
+        pub mod option {
+            pub fn serialize<S, T>(v: &Option<T>, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                T: $crate::chrono_format::ChronoFormat,
+            {
+                $crate::chrono_format::serialize_option_with(v, s, $format)
+            }
+
+            pub fn deserialize<'de, D, T>(d: D) -> Result<Option<T>, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                T: $crate::chrono_format::ChronoFormat,
+            {
+                $crate::chrono_format::deserialize_option_with(d, $format)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    mod vendor {
+        crate::datetime_format!("%d/%m/%Y %H:%M");
+    }
+
+    crate::datetime_format!(pub offset_date, "%Y-%m-%d %H:%M:%S %z");
+    crate::datetime_format!(pub us_date, "%m/%d/%Y");
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "vendor")]
+        pub created: DateTime<Utc>,
+        #[serde(default, with = "vendor::option")]
+        pub closed: Option<DateTime<Utc>>,
+        #[serde(with = "offset_date")]
+        pub local: DateTime<FixedOffset>,
+        #[serde(with = "us_date")]
+        pub due: NaiveDate,
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{"created":"14/11/2023 22:13","closed":null,"local":"2023-11-14 23:13:20 +0100","due":"12/31/2023"}"#;
+        let outer = Outer {
+            created: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 0).unwrap(),
+            closed: None,
+            local: FixedOffset::east_opt(3600)
+                .unwrap()
+                .with_ymd_and_hms(2023, 11, 14, 23, 13, 20)
+                .unwrap(),
+            due: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+        assert_eq!(serde_json::to_string(&outer).expect("Oops!"), json);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json =
+            r#"{"created":"2023-11-14","local":"2023-11-14 23:13:20 +0100","due":"12/31/2023"}"#;
+        let result = serde_json::from_str::<Outer>(json);
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("%d/%m/%Y %H:%M"), "{}", msg);
+    }
+}
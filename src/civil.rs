@@ -0,0 +1,493 @@
+//! Calendar arithmetic and RFC 3339 formatting for epoch timestamps.
+//!
+//! This lets the time modules read and write RFC 3339 strings for any
+//! [EpochTime](crate::epoch::EpochTime), including `SystemTime`, without
+//! depending on a date library.  All values are nanoseconds since the Unix
+//! epoch, and always formatted in UTC.
+
+use crate::epoch::NANOS_PER_SEC;
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Proleptic Gregorian `(year, month, day)` for days since 1970-01-01.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub(crate) fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub(crate) fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        _ => 28,
+    }
+}
+
+/// Format as `YYYY-MM-DDTHH:MM:SS[.fff]Z`.
+///
+/// With `digits` of `None`, the fraction is written with 0, 3, 6 or 9 digits,
+/// whichever is the shortest that is exact.  Otherwise exactly `digits`
+/// (at most 9) are written, truncating any extra precision.
+pub(crate) fn format_rfc3339(nanos: i128, digits: Option<usize>) -> Result<String, String> {
+    format_rfc3339_offset(nanos, 0, digits)
+}
+
+/// Format in the local time of `offset` seconds east of UTC, as
+/// `YYYY-MM-DDTHH:MM:SS[.fff](Z|+HH:MM|-HH:MM)`.  A zero offset is written
+/// as `Z`.  See [format_rfc3339] for `digits`.
+///
+/// RFC 3339 offsets have no seconds, so an `offset` that isn't a whole
+/// number of minutes is an error rather than being silently truncated.
+pub(crate) fn format_rfc3339_offset(
+    nanos: i128,
+    offset: i32,
+    digits: Option<usize>,
+) -> Result<String, String> {
+    if offset % 60 != 0 {
+        return Err(format!(
+            "offset of {} seconds can not be formatted as RFC 3339",
+            offset
+        ));
+    }
+    let nanos = nanos + offset as i128 * NANOS_PER_SEC;
+    let secs = nanos.div_euclid(NANOS_PER_SEC);
+    let subsec = nanos.rem_euclid(NANOS_PER_SEC) as u32;
+    let secs = i64::try_from(secs).map_err(|_| "timestamp out of range".to_string())?;
+    let (year, month, day) = civil_from_days(secs.div_euclid(SECS_PER_DAY));
+    if !(0..=9999).contains(&year) {
+        return Err(format!("year {} can not be formatted as RFC 3339", year));
+    }
+    let tod = secs.rem_euclid(SECS_PER_DAY);
+    let mut out = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        tod / 3600,
+        tod / 60 % 60,
+        tod % 60
+    );
+    let digits = match digits {
+        Some(digits) => digits.min(9),
+        None if subsec == 0 => 0,
+        None if subsec.is_multiple_of(1_000_000) => 3,
+        None if subsec.is_multiple_of(1_000) => 6,
+        None => 9,
+    };
+    if digits > 0 {
+        let fraction = format!("{:09}", subsec);
+        out.push('.');
+        out.push_str(&fraction[..digits]);
+    }
+    if offset == 0 {
+        out.push('Z');
+    } else {
+        let sign = if offset < 0 { '-' } else { '+' };
+        let offset = offset.unsigned_abs();
+        out.push_str(&format!(
+            "{}{:02}:{:02}",
+            sign,
+            offset / 3600,
+            offset / 60 % 60
+        ));
+    }
+    Ok(out)
+}
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+const FULL_WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Format in the local time of `offset` seconds east of UTC, as
+/// `Tue, 01 Jul 2003 10:52:37 +0200`.  Sub-second precision is dropped.
+pub(crate) fn format_rfc2822(nanos: i128, offset: i32) -> Result<String, String> {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let abs = offset.unsigned_abs();
+    Ok(format!(
+        "{} {}{:02}{:02}",
+        format_weekday_date(nanos, offset, "RFC 2822")?,
+        sign,
+        abs / 3600,
+        abs / 60 % 60
+    ))
+}
+
+/// Format as an HTTP IMF-fixdate, `Sun, 06 Nov 1994 08:49:37 GMT`.
+/// Sub-second precision is dropped.
+pub(crate) fn format_http_date(nanos: i128) -> Result<String, String> {
+    Ok(format!(
+        "{} GMT",
+        format_weekday_date(nanos, 0, "an HTTP date")?
+    ))
+}
+
+/// The shared `Tue, 01 Jul 2003 10:52:37` prefix of RFC 2822 and HTTP dates.
+fn format_weekday_date(nanos: i128, offset: i32, kind: &str) -> Result<String, String> {
+    let secs = (nanos + offset as i128 * NANOS_PER_SEC).div_euclid(NANOS_PER_SEC);
+    let secs = i64::try_from(secs).map_err(|_| "timestamp out of range".to_string())?;
+    let days = secs.div_euclid(SECS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    if !(0..=9999).contains(&year) {
+        return Err(format!("year {} can not be formatted as {}", year, kind));
+    }
+    let tod = secs.rem_euclid(SECS_PER_DAY);
+    Ok(format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02}",
+        WEEKDAYS[(days + 4).rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        tod / 3600,
+        tod / 60 % 60,
+        tod % 60
+    ))
+}
+
+fn number(value: &str, field: &str, kind: &str, input: &str) -> Result<u32, String> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("invalid {} in {} date \"{}\"", field, kind, input));
+    }
+    value
+        .parse()
+        .map_err(|_| format!("invalid {} in {} date \"{}\"", field, kind, input))
+}
+
+/// Parse up to 9 digits of fractional seconds into nanoseconds.  Extra digits
+/// are truncated.
+fn fraction(digits: &str, kind: &str, input: &str) -> Result<u32, String> {
+    let digits = &digits[..digits.len().min(9)];
+    Ok(number(digits, "fraction", kind, input)? * 10u32.pow(9 - digits.len() as u32))
+}
+
+/// The pieces of a parsed date-time, before conversion to UTC.
+pub(crate) struct DateTimeParts {
+    pub(crate) year: i64,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    pub(crate) nanos: u32,
+    /// Offset from UTC in seconds, east positive.
+    pub(crate) offset: i32,
+}
+
+impl DateTimeParts {
+    /// Nanoseconds since the Unix epoch, in UTC.
+    pub(crate) fn epoch_nanos(&self) -> i128 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let secs = days * SECS_PER_DAY + (self.hour * 3600 + self.minute * 60 + self.second) as i64
+            - self.offset as i64;
+        secs as i128 * NANOS_PER_SEC + self.nanos as i128
+    }
+
+    /// Reject out of range fields, including leap seconds.
+    fn validate(self, input: &str) -> Result<Self, String> {
+        if !(1..=12).contains(&self.month)
+            || self.day == 0
+            || self.day > days_in_month(self.year, self.month)
+            || self.hour > 23
+            || self.minute > 59
+            || self.second > 59
+        {
+            return Err(format!("date out of range in \"{}\"", input));
+        }
+        Ok(self)
+    }
+}
+
+/// Strictly parse `YYYY-MM-DDTHH:MM:SS[.f+](Z|+HH:MM|-HH:MM)`.
+///
+/// Only the `T` separator and `Z` designator may be lower case, as RFC 3339
+/// allows.  Leap seconds are rejected.
+pub(crate) fn parse_rfc3339(input: &str) -> Result<DateTimeParts, String> {
+    const KIND: &str = "RFC 3339";
+    let bad = || format!("invalid RFC 3339 date \"{}\"", input);
+    let bytes = input.as_bytes();
+    if !input.is_ascii()
+        || bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return Err(bad());
+    }
+    let mut rest = &input[19..];
+    let mut nanos = 0;
+    if let Some(digits) = rest.strip_prefix('.') {
+        let len = digits.bytes().take_while(|b| b.is_ascii_digit()).count();
+        if len == 0 {
+            return Err(bad());
+        }
+        nanos = fraction(&digits[..len], KIND, input)?;
+        rest = &digits[len..];
+    }
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return Err(bad()),
+            };
+            let hours = number(&rest[1..3], "offset", KIND, input)?;
+            let minutes = number(&rest[4..6], "offset", KIND, input)?;
+            if hours > 23 || minutes > 59 {
+                return Err(bad());
+            }
+            sign * (hours * 3600 + minutes * 60) as i32
+        }
+        _ => return Err(bad()),
+    };
+    DateTimeParts {
+        year: number(&input[0..4], "year", KIND, input)? as i64,
+        month: number(&input[5..7], "month", KIND, input)?,
+        day: number(&input[8..10], "day", KIND, input)?,
+        hour: number(&input[11..13], "hour", KIND, input)?,
+        minute: number(&input[14..16], "minute", KIND, input)?,
+        second: number(&input[17..19], "second", KIND, input)?,
+        nanos,
+        offset,
+    }
+    .validate(input)
+}
+
+/// Parse `YYYY-MM-DD HH:MM:SS[.f+]`, the `%Y-%m-%d %H:%M:%S` format used by
+/// SQL databases, as UTC.
+pub(crate) fn parse_naive(input: &str) -> Result<DateTimeParts, String> {
+    const KIND: &str = "%Y-%m-%d %H:%M:%S";
+    let bytes = input.as_bytes();
+    if !input.is_ascii()
+        || bytes.len() < 19
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || bytes[10] != b' '
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return Err(format!("invalid {} date \"{}\"", KIND, input));
+    }
+    let nanos = match &input[19..] {
+        "" => 0,
+        rest => match rest.strip_prefix('.') {
+            Some(digits) => fraction(digits, KIND, input)?,
+            None => return Err(format!("invalid {} date \"{}\"", KIND, input)),
+        },
+    };
+    DateTimeParts {
+        year: number(&input[0..4], "year", KIND, input)? as i64,
+        month: number(&input[5..7], "month", KIND, input)?,
+        day: number(&input[8..10], "day", KIND, input)?,
+        hour: number(&input[11..13], "hour", KIND, input)?,
+        minute: number(&input[14..16], "minute", KIND, input)?,
+        second: number(&input[17..19], "second", KIND, input)?,
+        nanos,
+        offset: 0,
+    }
+    .validate(input)
+}
+
+/// Parse an RFC 2822 date, such as `Tue, 01 Jul 2003 10:52:37 +0200`.
+///
+/// The day of the week and seconds are optional, and the obsolete forms from
+/// RFC 2822 section 4.3 are accepted: two and three digit years, and the
+/// `UT`, `GMT` and North American zone names.  Military zones are treated as
+/// `-0000`, as the RFC recommends.
+pub(crate) fn parse_rfc2822(input: &str) -> Result<DateTimeParts, String> {
+    const KIND: &str = "RFC 2822";
+    let bad = || format!("invalid RFC 2822 date \"{}\"", input);
+    let mut rest = input.trim();
+    if let Some((weekday, after)) = rest.split_once(',') {
+        let weekday = weekday.trim();
+        if !WEEKDAYS.iter().any(|d| d.eq_ignore_ascii_case(weekday)) {
+            return Err(bad());
+        }
+        rest = after;
+    }
+    let parts: Vec<&str> = rest.split_whitespace().collect();
+    let [day, month, year, time, zone] = parts[..] else {
+        return Err(bad());
+    };
+    let month = MONTHS
+        .iter()
+        .position(|m| m.eq_ignore_ascii_case(month))
+        .ok_or_else(bad)? as u32
+        + 1;
+    let year = match year.len() {
+        2 => match number(year, "year", KIND, input)? {
+            y if y < 50 => 2000 + y,
+            y => 1900 + y,
+        },
+        3 => 1900 + number(year, "year", KIND, input)?,
+        4 => number(year, "year", KIND, input)?,
+        _ => return Err(bad()),
+    };
+    let mut time = time.split(':');
+    let hour = time.next().ok_or_else(bad)?;
+    let minute = time.next().ok_or_else(bad)?;
+    let second = time.next().unwrap_or("00");
+    if time.next().is_some() || hour.len() != 2 || minute.len() != 2 || second.len() != 2 {
+        return Err(bad());
+    }
+    let offset = parse_zone(zone).ok_or_else(bad)?;
+    if day.is_empty() || day.len() > 2 {
+        return Err(bad());
+    }
+    DateTimeParts {
+        year: year as i64,
+        month,
+        day: number(day, "day", KIND, input)?,
+        hour: number(hour, "hour", KIND, input)?,
+        minute: number(minute, "minute", KIND, input)?,
+        second: number(second, "second", KIND, input)?,
+        nanos: 0,
+        offset,
+    }
+    .validate(input)
+}
+
+/// An RFC 2822 zone, as seconds east of UTC.
+fn parse_zone(zone: &str) -> Option<i32> {
+    let named = match zone.to_ascii_uppercase().as_str() {
+        "UT" | "GMT" | "Z" => Some(0),
+        "EDT" => Some(-4),
+        "EST" | "CDT" => Some(-5),
+        "CST" | "MDT" => Some(-6),
+        "MST" | "PDT" => Some(-7),
+        "PST" => Some(-8),
+        z if z.len() == 1 && z.bytes().all(|b| b.is_ascii_alphabetic()) => Some(0),
+        _ => None,
+    };
+    if let Some(hours) = named {
+        return Some(hours * 3600);
+    }
+    let bytes = zone.as_bytes();
+    if bytes.len() != 5 || !bytes[1..].iter().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let sign = match bytes[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let hours: i32 = zone[1..3].parse().ok()?;
+    let minutes: i32 = zone[3..5].parse().ok()?;
+    if minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Parse an HTTP date in any of the three forms RFC 9110 requires
+/// recipients to accept:
+///
+/// - IMF-fixdate: `Sun, 06 Nov 1994 08:49:37 GMT`
+/// - RFC 850: `Sunday, 06-Nov-94 08:49:37 GMT`
+/// - asctime: `Sun Nov  6 08:49:37 1994`
+///
+/// Two digit RFC 850 years below 50 are taken as 20xx.  All forms are UTC.
+pub(crate) fn parse_http_date(input: &str) -> Result<DateTimeParts, String> {
+    const KIND: &str = "HTTP";
+    let bad = || format!("invalid HTTP date \"{}\"", input);
+    let parts: Vec<&str> = input.split_whitespace().collect();
+    let (weekday, day, month, year, time) = match parts[..] {
+        [weekday, day, month, year, time, "GMT"] => (
+            weekday.strip_suffix(',').ok_or_else(bad)?,
+            day,
+            month,
+            year,
+            time,
+        ),
+        [weekday, date, time, "GMT"] => {
+            let weekday = weekday.strip_suffix(',').ok_or_else(bad)?;
+            let mut date = date.split('-');
+            let (Some(day), Some(month), Some(year), None) =
+                (date.next(), date.next(), date.next(), date.next())
+            else {
+                return Err(bad());
+            };
+            (weekday, day, month, year, time)
+        }
+        [weekday, month, day, time, year] => (weekday, day, month, year, time),
+        _ => return Err(bad()),
+    };
+    if !WEEKDAYS
+        .iter()
+        .chain(FULL_WEEKDAYS.iter())
+        .any(|d| d.eq_ignore_ascii_case(weekday))
+    {
+        return Err(bad());
+    }
+    let month = MONTHS
+        .iter()
+        .position(|m| m.eq_ignore_ascii_case(month))
+        .ok_or_else(bad)? as u32
+        + 1;
+    let year = match year.len() {
+        2 => match number(year, "year", KIND, input)? {
+            y if y < 50 => 2000 + y,
+            y => 1900 + y,
+        },
+        4 => number(year, "year", KIND, input)?,
+        _ => return Err(bad()),
+    };
+    let mut time = time.split(':');
+    let (Some(hour), Some(minute), Some(second), None) =
+        (time.next(), time.next(), time.next(), time.next())
+    else {
+        return Err(bad());
+    };
+    if hour.len() != 2 || minute.len() != 2 || second.len() != 2 || day.is_empty() || day.len() > 2
+    {
+        return Err(bad());
+    }
+    DateTimeParts {
+        year: year as i64,
+        month,
+        day: number(day, "day", KIND, input)?,
+        hour: number(hour, "hour", KIND, input)?,
+        minute: number(minute, "minute", KIND, input)?,
+        second: number(second, "second", KIND, input)?,
+        nanos: 0,
+        offset: 0,
+    }
+    .validate(input)
+}
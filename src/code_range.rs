@@ -0,0 +1,224 @@
+//! Deserialize an integer code into an enum, rejecting codes outside of `MIN..=MAX`
+//!
+//! Enums often stand for ranges of numeric codes, such as HTTP status
+//! classes or syslog severities.  This module reads the code, checks it is
+//! within `MIN..=MAX`, and converts it with the enum's `TryFrom<u16>`.  Any
+//! failure names the valid range, rather than the conversion's own error.
+//! The enum serializes back as its code with `Into<u16>`.
+//!
+//! As with [bounded_int](crate::bounded_int), the bounds are const generics,
+//! so reference the functions directly and supply them with a turbofish.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Copy, Debug)]
+//! pub enum StatusClass {
+//!     Informational,
+//!     Success,
+//!     Redirection,
+//!     ClientError,
+//!     ServerError,
+//! }
+//!
+//! impl TryFrom<u16> for StatusClass {
+//!     type Error = ();
+//!
+//!     fn try_from(code: u16) -> Result<Self, ()> {
+//!         match code / 100 {
+//!             1 => Ok(StatusClass::Informational),
+//!             2 => Ok(StatusClass::Success),
+//!             3 => Ok(StatusClass::Redirection),
+//!             4 => Ok(StatusClass::ClientError),
+//!             5 => Ok(StatusClass::ServerError),
+//!             _ => Err(()),
+//!         }
+//!     }
+//! }
+//!
+//! impl From<StatusClass> for u16 {
+//!     fn from(class: StatusClass) -> u16 {
+//!         (class as u16 + 1) * 100
+//!     }
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         serialize_with = "serde_stuff::code_range::serialize",
+//!         deserialize_with = "serde_stuff::code_range::deserialize::<_, _, 100, 599>"
+//!     )]
+//!     pub status: StatusClass,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "status": 404
+//! }
+//! ```
+//! The following fails with `invalid value: integer `600`, expected a code in the range 100..=599`
+//! ```json
+//! {
+//!     "status": 600
+//! }
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{self, Unexpected, Visitor};
+use serde::{Deserializer, Serializer};
+
+struct CodeVisitor<T, const MIN: u16, const MAX: u16>(PhantomData<T>);
+
+impl<T, const MIN: u16, const MAX: u16> CodeVisitor<T, MIN, MAX> {
+    fn check<E: de::Error>(code: Option<u16>, unexpected: Unexpected) -> Result<T, E>
+    where
+        T: TryFrom<u16>,
+    {
+        code.filter(|code| (MIN..=MAX).contains(code))
+            .and_then(|code| T::try_from(code).ok())
+            .ok_or_else(|| {
+                let expected = format!("a code in the range {}..={}", MIN, MAX);
+                E::invalid_value(unexpected, &expected.as_str())
+            })
+    }
+}
+
+impl<'de, T, const MIN: u16, const MAX: u16> Visitor<'de> for CodeVisitor<T, MIN, MAX>
+where
+    T: TryFrom<u16>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a code in the range {}..={}", MIN, MAX)
+    }
+
+    fn visit_i64<E: de::Error>(self, code: i64) -> Result<T, E> {
+        Self::check(u16::try_from(code).ok(), Unexpected::Signed(code))
+    }
+
+    fn visit_u64<E: de::Error>(self, code: u64) -> Result<T, E> {
+        Self::check(u16::try_from(code).ok(), Unexpected::Unsigned(code))
+    }
+}
+
+pub fn deserialize<'de, D, T, const MIN: u16, const MAX: u16>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<u16>,
+{
+    d.deserialize_u16(CodeVisitor::<T, MIN, MAX>(PhantomData))
+}
+
+pub fn serialize<S, T>(v: &T, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Clone + Into<u16>,
+{
+    s.serialize_u16(v.clone().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum Severity {
+        Emergency,
+        Alert,
+        Critical,
+        Error,
+    }
+
+    impl TryFrom<u16> for Severity {
+        type Error = String;
+
+        fn try_from(code: u16) -> Result<Self, String> {
+            match code {
+                0 => Ok(Severity::Emergency),
+                1 => Ok(Severity::Alert),
+                2 => Ok(Severity::Critical),
+                3 => Ok(Severity::Error),
+                _ => Err(format!("no severity {}", code)),
+            }
+        }
+    }
+
+    impl From<Severity> for u16 {
+        fn from(severity: Severity) -> u16 {
+            severity as u16
+        }
+    }
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    pub struct Outer {
+        #[serde(
+            serialize_with = "crate::code_range::serialize",
+            deserialize_with = "crate::code_range::deserialize::<_, _, 0, 3>"
+        )]
+        pub severity: Severity,
+    }
+
+    #[test]
+    fn deserialize() {
+        let result: Outer = serde_json::from_str(r#"{"severity": 2}"#).expect("Oops!");
+        assert_eq!(
+            result,
+            Outer {
+                severity: Severity::Critical
+            }
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            severity: Severity::Alert,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"severity":1}"#);
+    }
+
+    #[test]
+    fn deserialize_out_of_range() {
+        for code in ["4", "-1", "70000"] {
+            let json = format!(r#"{{"severity": {}}}"#, code);
+            let err = serde_json::from_str::<Outer>(&json)
+                .unwrap_err()
+                .to_string();
+            let msg = format!(
+                "invalid value: integer `{}`, expected a code in the range 0..=3",
+                code
+            );
+            assert!(err.contains(&msg), "{}", err);
+        }
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        #[derive(Debug, Deserialize, Serialize, PartialEq)]
+        pub struct Event {
+            #[serde(
+                serialize_with = "crate::code_range::serialize",
+                deserialize_with = "crate::code_range::deserialize::<_, _, 0, 3>"
+            )]
+            pub severity: Severity,
+            pub count: u8,
+        }
+
+        let event = Event {
+            severity: Severity::Critical,
+            count: 7,
+        };
+        let bytes = bincode::serialize(&event).expect("Oops!");
+        assert_eq!(bytes, [2, 0, 7]);
+        let result: Event = bincode::deserialize(&bytes).expect("Oops!");
+        assert_eq!(result, event);
+        assert!(bincode::deserialize::<Event>(&[4, 0, 7]).is_err());
+    }
+}
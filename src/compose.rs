@@ -0,0 +1,219 @@
+//! Chain a conversion step with an adapter
+//!
+//! Writing a module for every combination, such as trim then parse, or
+//! gzip then base64, doesn't scale.  [Compose] builds one per field from
+//! parts: a [Step] that converts the field's value to an inner value and
+//! back, followed by an [adapter](crate::adapters) for the inner value.
+//! Since `Compose` is an adapter too, steps chain:
+//!
+//! ```text
+//! Compose<Parse, Compose<Trim>>
+//! ```
+//!
+//! On serialize, the value goes through the steps left to right, and the
+//! last adapter writes it.  On deserialize the last adapter reads the inner
+//! value, and the steps convert it back right to left.  So the example
+//! above reads a string, trims it, then parses it.  The adapter defaults to
+//! [Same].
+//!
+//! The provided steps are [Trim], [Lowercase], [Parse] and [Dedup].
+//! Implement [Step] for others, such as compression.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::adapters::As;
+//! # #[cfg(feature = "base64")]
+//! use serde_stuff::adapters::Base64;
+//! use serde_stuff::compose::{Compose, Dedup, Parse, Step, Trim};
+//!
+//! /// Stand-in for a compression step.
+//! pub struct Reverse;
+//!
+//! impl Step<Vec<u8>> for Reverse {
+//!     type Inner = Vec<u8>;
+//!
+//!     fn to_inner(value: &Vec<u8>) -> Result<Vec<u8>, String> {
+//!         Ok(value.iter().rev().copied().collect())
+//!     }
+//!
+//!     fn from_inner(inner: Vec<u8>) -> Result<Vec<u8>, String> {
+//!         Ok(inner.into_iter().rev().collect())
+//!     }
+//! }
+//!
+//! # #[cfg(feature = "base64")]
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "As::<Compose<Parse, Compose<Trim>>>")]
+//!     pub port: u16,
+//!     #[serde(with = "As::<Compose<Reverse, Base64>>")]
+//!     pub blob: Vec<u8>,
+//!     #[serde(with = "As::<Compose<Dedup>>")]
+//!     pub tags: Vec<String>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`, with `port` of `443` and
+//! `tags` of `["a", "b"]`
+//! ```json
+//! {
+//!     "port": " 443 ",
+//!     "blob": "AgE=",
+//!     "tags": ["a", "b", "a"]
+//! }
+//! ```
+
+use serde::{de, ser, Deserializer, Serializer};
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use super::adapters::{DeserializeAs, Same, SerializeAs};
+
+/// Converts a `T` to an inner value for serializing, and back.
+pub trait Step<T> {
+    type Inner;
+
+    fn to_inner(value: &T) -> Result<Self::Inner, String>;
+    fn from_inner(inner: Self::Inner) -> Result<T, String>;
+}
+
+/// The step `S`, then the adapter `A`.
+pub struct Compose<S, A = Same>(PhantomData<(S, A)>);
+
+impl<T, S: Step<T>, A: SerializeAs<S::Inner>> SerializeAs<T> for Compose<S, A> {
+    fn serialize_as<Ser: Serializer>(source: &T, s: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let inner = S::to_inner(source).map_err(ser::Error::custom)?;
+        A::serialize_as(&inner, s)
+    }
+}
+
+impl<'de, T, S: Step<T>, A: DeserializeAs<'de, S::Inner>> DeserializeAs<'de, T> for Compose<S, A> {
+    fn deserialize_as<D: Deserializer<'de>>(d: D) -> Result<T, D::Error> {
+        S::from_inner(A::deserialize_as(d)?).map_err(de::Error::custom)
+    }
+}
+
+/// Trims whitespace from a `String`, both ways.
+pub struct Trim;
+
+impl Step<String> for Trim {
+    type Inner = String;
+
+    fn to_inner(value: &String) -> Result<String, String> {
+        Ok(value.trim().to_string())
+    }
+
+    fn from_inner(inner: String) -> Result<String, String> {
+        Ok(inner.trim().to_string())
+    }
+}
+
+/// Lowercases a `String`, both ways.
+pub struct Lowercase;
+
+impl Step<String> for Lowercase {
+    type Inner = String;
+
+    fn to_inner(value: &String) -> Result<String, String> {
+        Ok(value.to_lowercase())
+    }
+
+    fn from_inner(inner: String) -> Result<String, String> {
+        Ok(inner.to_lowercase())
+    }
+}
+
+/// Converts a value to a `String` with `Display`, and back with `FromStr`.
+pub struct Parse;
+
+impl<T: Display + FromStr> Step<T> for Parse
+where
+    T::Err: Display,
+{
+    type Inner = String;
+
+    fn to_inner(value: &T) -> Result<String, String> {
+        Ok(value.to_string())
+    }
+
+    fn from_inner(inner: String) -> Result<T, String> {
+        inner
+            .parse()
+            .map_err(|e| format!("invalid value \"{}\": {}", inner, e))
+    }
+}
+
+/// Drops repeated elements of a `Vec`, keeping the first, both ways.
+pub struct Dedup;
+
+impl<T: Eq + Hash + Clone> Step<Vec<T>> for Dedup {
+    type Inner = Vec<T>;
+
+    fn to_inner(value: &Vec<T>) -> Result<Vec<T>, String> {
+        Self::from_inner(value.clone())
+    }
+
+    fn from_inner(inner: Vec<T>) -> Result<Vec<T>, String> {
+        let mut seen: HashSet<T> = HashSet::with_capacity(inner.len());
+        Ok(inner
+            .into_iter()
+            .filter(|item| seen.insert(item.clone()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    use super::{Compose, Dedup, Lowercase, Parse, Trim};
+    use crate::adapters::{As, Hex, OneOrMany};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "As::<Compose<Parse, Compose<Trim>>>")]
+        pub port: u16,
+        #[serde(with = "As::<Vec<Compose<Lowercase, Compose<Trim>>>>")]
+        pub hosts: Vec<String>,
+        #[serde(with = "As::<Compose<Dedup, OneOrMany<Hex>>>")]
+        pub keys: Vec<Vec<u8>>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"port": " 443 ", "hosts": [" A.Example "], "keys": ["00ff", "00FF", "01"]}"#;
+        let outer = Outer {
+            port: 443,
+            hosts: vec!["a.example".to_string()],
+            keys: vec![vec![0x00, 0xff], vec![0x01]],
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            port: 443,
+            hosts: vec!["A.example ".to_string()],
+            keys: vec![vec![0x01], vec![0x01]],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"port":"443","hosts":["a.example"],"keys":"01"}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"port": " http ", "hosts": [], "keys": []}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("invalid value \"http\""), "{}", err);
+    }
+}
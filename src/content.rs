@@ -0,0 +1,810 @@
+//! A buffered, format independent value
+//!
+//! Some modules need to hold on to, or rearrange, a value before the
+//! target type sees it, such as [nested](crate::nested) regrouping dotted
+//! keys, or [Extra](crate::extra::Extra) keeping unknown fields.  [Content]
+//! holds any value from a self describing format, and serializes back to
+//! the same shape.  [Content::deserialize_into] reads it as another type.
+//!
+//! Since some formats, such as properties and env files, only hold
+//! strings, a [lenient](ContentDeserializer::lenient) deserializer also
+//! reads a string as a number or a boolean when the target asks for one,
+//! and a map whose keys are `0` to `n - 1` as a sequence.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::DeserializeOwned;
+use serde::de::{self, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::rename_all::Case;
+
+/// Any self describing value.  `null` and `None` are both [Content::Unit].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Content {
+    Unit,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Seq(Vec<Content>),
+    Map(Vec<(Content, Content)>),
+}
+
+impl Content {
+    /// Capture `value` as a `Content`.
+    pub fn from_value<T: ?Sized + Serialize>(value: &T) -> Result<Content, de::value::Error> {
+        value.serialize(ContentSerializer::new())
+    }
+
+    /// Deserialize a `T` from this value.
+    pub fn deserialize_into<T: DeserializeOwned>(self) -> Result<T, de::value::Error> {
+        T::deserialize(ContentDeserializer::new(self))
+    }
+
+    /// The string, if this is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Content::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// This value, for an `invalid_type` error.
+    pub(crate) fn unexpected(&self) -> de::Unexpected<'_> {
+        match self {
+            Content::Unit => de::Unexpected::Unit,
+            Content::Bool(v) => de::Unexpected::Bool(*v),
+            Content::I64(v) => de::Unexpected::Signed(*v),
+            Content::U64(v) => de::Unexpected::Unsigned(*v),
+            Content::F64(v) => de::Unexpected::Float(*v),
+            Content::String(v) => de::Unexpected::Str(v),
+            Content::Bytes(v) => de::Unexpected::Bytes(v),
+            Content::Seq(_) => de::Unexpected::Seq,
+            Content::Map(_) => de::Unexpected::Map,
+        }
+    }
+
+    /// The values of a map whose keys are `0` to `n - 1`, in index order.
+    fn into_indexed(
+        entries: Vec<(Content, Content)>,
+    ) -> Result<Vec<Content>, Vec<(Content, Content)>> {
+        let mut seen = vec![false; entries.len()];
+        let mut index = Vec::with_capacity(entries.len());
+        for (key, _) in &entries {
+            match key.as_str().and_then(|k| k.parse::<usize>().ok()) {
+                Some(i) if i < seen.len() && !seen[i] => {
+                    seen[i] = true;
+                    index.push(i);
+                }
+                _ => return Err(entries),
+            }
+        }
+        let mut items: Vec<(usize, Content)> = index
+            .into_iter()
+            .zip(entries.into_iter().map(|(_, v)| v))
+            .collect();
+        items.sort_by_key(|(i, _)| *i);
+        Ok(items.into_iter().map(|(_, v)| v).collect())
+    }
+}
+
+impl Serialize for Content {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Content::Unit => s.serialize_unit(),
+            Content::Bool(v) => s.serialize_bool(*v),
+            Content::I64(v) => s.serialize_i64(*v),
+            Content::U64(v) => s.serialize_u64(*v),
+            Content::F64(v) => s.serialize_f64(*v),
+            Content::String(v) => s.serialize_str(v),
+            Content::Bytes(v) => s.serialize_bytes(v),
+            Content::Seq(v) => s.collect_seq(v),
+            Content::Map(v) => s.collect_map(v.iter().map(|(k, v)| (k, v))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Content {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        struct ContentVisitor;
+
+        impl<'de> Visitor<'de> for ContentVisitor {
+            type Value = Content;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("any value")
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Content, E> {
+                Ok(Content::Bool(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Content, E> {
+                Ok(Content::I64(v))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Content, E> {
+                Ok(Content::U64(v))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Content, E> {
+                Ok(Content::F64(v))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Content, E> {
+                Ok(Content::String(v.to_string()))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<Content, E> {
+                Ok(Content::String(v))
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Content, E> {
+                Ok(Content::Bytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Content, E> {
+                Ok(Content::Bytes(v))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Content, E> {
+                Ok(Content::Unit)
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<Content, E> {
+                Ok(Content::Unit)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Content, D::Error> {
+                Content::deserialize(d)
+            }
+
+            fn visit_newtype_struct<D: Deserializer<'de>>(self, d: D) -> Result<Content, D::Error> {
+                Content::deserialize(d)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Content, A::Error> {
+                let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(Content::Seq(items))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Content, A::Error> {
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Content::Map(entries))
+            }
+        }
+
+        d.deserialize_any(ContentVisitor)
+    }
+}
+
+/// Replays a [Content], with errors of type `E`.
+pub struct ContentDeserializer<E> {
+    content: Content,
+    lenient: bool,
+    marker: PhantomData<E>,
+}
+
+impl<E> ContentDeserializer<E> {
+    pub fn new(content: Content) -> Self {
+        ContentDeserializer {
+            content,
+            lenient: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Also read strings as numbers and booleans, and index keyed maps as
+    /// sequences, all the way down.
+    pub fn lenient(content: Content) -> Self {
+        ContentDeserializer {
+            content,
+            lenient: true,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, E: de::Error> IntoDeserializer<'de, E> for ContentDeserializer<E> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de, E: de::Error> IntoDeserializer<'de, E> for Content {
+    type Deserializer = ContentDeserializer<E>;
+
+    fn into_deserializer(self) -> ContentDeserializer<E> {
+        ContentDeserializer::new(self)
+    }
+}
+
+/// Parse a string leaf for a target that asked for `$visit`.
+macro_rules! parse_string {
+    ($($method:ident => $ty:ty, $visit:ident;)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+                match &self.content {
+                    Content::String(s) if self.lenient => match s.trim().parse::<$ty>() {
+                        Ok(v) => visitor.$visit(v),
+                        Err(_) => Err(de::Error::invalid_value(de::Unexpected::Str(s), &visitor)),
+                    },
+                    _ => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for ContentDeserializer<E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        let lenient = self.lenient;
+        let child = |content| ContentDeserializer::<E> {
+            content,
+            lenient,
+            marker: PhantomData,
+        };
+        match self.content {
+            Content::Unit => visitor.visit_unit(),
+            Content::Bool(v) => visitor.visit_bool(v),
+            Content::I64(v) => visitor.visit_i64(v),
+            Content::U64(v) => visitor.visit_u64(v),
+            Content::F64(v) => visitor.visit_f64(v),
+            Content::String(v) => visitor.visit_string(v),
+            Content::Bytes(v) => visitor.visit_byte_buf(v),
+            Content::Seq(v) => {
+                let mut seq = SeqDeserializer::new(v.into_iter().map(child));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Content::Map(v) => {
+                let mut map =
+                    MapDeserializer::new(v.into_iter().map(|(k, v)| (child(k), child(v))));
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    parse_string! {
+        deserialize_bool => bool, visit_bool;
+        deserialize_i8 => i8, visit_i8;
+        deserialize_i16 => i16, visit_i16;
+        deserialize_i32 => i32, visit_i32;
+        deserialize_i64 => i64, visit_i64;
+        deserialize_u8 => u8, visit_u8;
+        deserialize_u16 => u16, visit_u16;
+        deserialize_u32 => u32, visit_u32;
+        deserialize_u64 => u64, visit_u64;
+        deserialize_f32 => f32, visit_f32;
+        deserialize_f64 => f64, visit_f64;
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Unit => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Map(entries) if self.lenient => {
+                let content = match Content::into_indexed(entries) {
+                    Ok(items) => Content::Seq(items),
+                    Err(entries) => Content::Map(entries),
+                };
+                ContentDeserializer::<E>::lenient(content).deserialize_any(visitor)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, E> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        let lenient = self.lenient;
+        let (variant, value) = match self.content {
+            Content::Map(mut entries) if entries.len() == 1 => {
+                let (variant, value) = entries.pop().unwrap();
+                (variant, Some(value))
+            }
+            Content::Map(_) => {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Map,
+                    &"a map with a single key",
+                ))
+            }
+            variant => (variant, None),
+        };
+        visitor.visit_enum(EnumDeserializer {
+            variant,
+            value,
+            lenient,
+            marker: PhantomData,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct EnumDeserializer<E> {
+    variant: Content,
+    value: Option<Content>,
+    lenient: bool,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E: de::Error> EnumAccess<'de> for EnumDeserializer<E> {
+    type Error = E;
+    type Variant = VariantDeserializer<E>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantDeserializer<E>), E>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(ContentDeserializer::<E>::new(self.variant))?;
+        let value = self.value.map(|value| ContentDeserializer {
+            content: value,
+            lenient: self.lenient,
+            marker: PhantomData,
+        });
+        Ok((variant, VariantDeserializer { value }))
+    }
+}
+
+struct VariantDeserializer<E> {
+    value: Option<ContentDeserializer<E>>,
+}
+
+impl<'de, E: de::Error> VariantAccess<'de> for VariantDeserializer<E> {
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), E> {
+        match self.value.map(|value| value.content) {
+            None | Some(Content::Unit) => Ok(()),
+            Some(_) => Err(de::Error::invalid_type(
+                de::Unexpected::Map,
+                &"unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, E>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, E> {
+        match self.value {
+            Some(value) => value.deserialize_seq(visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        match self.value {
+            Some(value) => value.deserialize_any(visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
+
+/// Captures any `Serialize` value as a [Content], with errors of type `E`.
+pub(crate) struct ContentSerializer<E> {
+    case: Option<Case>,
+    marker: PhantomData<E>,
+}
+
+impl<E> ContentSerializer<E> {
+    pub(crate) fn new() -> Self {
+        ContentSerializer {
+            case: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Also convert field and variant names to `case`, all the way down.
+    pub(crate) fn renaming(case: Case) -> Self {
+        ContentSerializer {
+            case: Some(case),
+            marker: PhantomData,
+        }
+    }
+
+    fn rename(&self, name: &str) -> String {
+        match self.case {
+            Some(case) => case.apply(name),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// A sequence, map or struct being captured, and the variant it belongs to.
+pub(crate) struct Compound<E> {
+    case: Option<Case>,
+    variant: Option<String>,
+    items: Vec<Content>,
+    entries: Vec<(Content, Content)>,
+    key: Option<Content>,
+    marker: PhantomData<E>,
+}
+
+impl<E> Compound<E> {
+    fn new(serializer: ContentSerializer<E>, variant: Option<&str>, len: Option<usize>) -> Self {
+        Compound {
+            variant: variant.map(|variant| serializer.rename(variant)),
+            case: serializer.case,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+            marker: PhantomData,
+        }
+    }
+
+    fn wrap(variant: Option<String>, content: Content) -> Content {
+        match variant {
+            Some(variant) => Content::Map(vec![(Content::String(variant), content)]),
+            None => content,
+        }
+    }
+
+    fn child(&self) -> ContentSerializer<E> {
+        ContentSerializer {
+            case: self.case,
+            marker: PhantomData,
+        }
+    }
+
+    fn end_seq(self) -> Content {
+        Self::wrap(self.variant, Content::Seq(self.items))
+    }
+
+    fn end_map(self) -> Content {
+        Self::wrap(self.variant, Content::Map(self.entries))
+    }
+}
+
+impl<E: ser::Error> Serializer for ContentSerializer<E> {
+    type Ok = Content;
+    type Error = E;
+    type SerializeSeq = Compound<E>;
+    type SerializeTuple = Compound<E>;
+    type SerializeTupleStruct = Compound<E>;
+    type SerializeTupleVariant = Compound<E>;
+    type SerializeMap = Compound<E>;
+    type SerializeStruct = Compound<E>;
+    type SerializeStructVariant = Compound<E>;
+
+    fn serialize_bool(self, v: bool) -> Result<Content, E> {
+        Ok(Content::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Content, E> {
+        Ok(Content::I64(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Content, E> {
+        Ok(Content::I64(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Content, E> {
+        Ok(Content::I64(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Content, E> {
+        Ok(Content::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Content, E> {
+        match (i64::try_from(v), u64::try_from(v)) {
+            (Ok(v), _) => Ok(Content::I64(v)),
+            (_, Ok(v)) => Ok(Content::U64(v)),
+            _ => Err(ser::Error::custom(format!("integer {} out of range", v))),
+        }
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Content, E> {
+        Ok(Content::U64(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Content, E> {
+        Ok(Content::U64(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Content, E> {
+        Ok(Content::U64(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Content, E> {
+        Ok(Content::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Content, E> {
+        u64::try_from(v)
+            .map(Content::U64)
+            .map_err(|_| ser::Error::custom(format!("integer {} out of range", v)))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Content, E> {
+        Ok(Content::F64(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Content, E> {
+        Ok(Content::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Content, E> {
+        Ok(Content::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Content, E> {
+        Ok(Content::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Content, E> {
+        Ok(Content::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Content, E> {
+        Ok(Content::Unit)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Content, E> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Content, E> {
+        Ok(Content::Unit)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Content, E> {
+        Ok(Content::Unit)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Content, E> {
+        Ok(Content::String(self.rename(variant)))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Content, E> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Content, E> {
+        let variant = self.rename(variant);
+        Ok(Compound::<E>::wrap(Some(variant), value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<E>, E> {
+        Ok(Compound::new(self, None, len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<E>, E> {
+        Ok(Compound::new(self, None, Some(len)))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Compound<E>, E> {
+        Ok(Compound::new(self, None, Some(len)))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<E>, E> {
+        Ok(Compound::new(self, Some(variant), Some(len)))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<E>, E> {
+        Ok(Compound::new(self, None, len))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Compound<E>, E> {
+        Ok(Compound::new(self, None, Some(len)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<E>, E> {
+        Ok(Compound::new(self, Some(variant), Some(len)))
+    }
+}
+
+impl<E: ser::Error> ser::SerializeSeq for Compound<E> {
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), E> {
+        self.items.push(value.serialize(self.child())?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, E> {
+        Ok(self.end_seq())
+    }
+}
+
+impl<E: ser::Error> ser::SerializeTuple for Compound<E> {
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), E> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        Ok(self.end_seq())
+    }
+}
+
+impl<E: ser::Error> ser::SerializeTupleStruct for Compound<E> {
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), E> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        Ok(self.end_seq())
+    }
+}
+
+impl<E: ser::Error> ser::SerializeTupleVariant for Compound<E> {
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), E> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        Ok(self.end_seq())
+    }
+}
+
+impl<E: ser::Error> ser::SerializeMap for Compound<E> {
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), E> {
+        self.key = Some(key.serialize(self.child())?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), E> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| ser::Error::custom("serialize_value called before serialize_key"))?;
+        self.entries.push((key, value.serialize(self.child())?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, E> {
+        Ok(self.end_map())
+    }
+}
+
+impl<E: ser::Error> ser::SerializeStruct for Compound<E> {
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), E> {
+        let value = value.serialize(self.child())?;
+        let key = self.child().rename(key);
+        self.entries.push((Content::String(key), value));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, E> {
+        Ok(self.end_map())
+    }
+}
+
+impl<E: ser::Error> ser::SerializeStructVariant for Compound<E> {
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), E> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        Ok(self.end_map())
+    }
+}
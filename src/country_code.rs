@@ -0,0 +1,92 @@
+//! Deserialize an ISO 3166-1 alpha-2 country code, such as `"US"`
+//!
+//! Codes are trimmed and upper cased, then checked against the officially
+//! assigned ISO 3166-1 alpha-2 codes.  User assigned codes, such as `"XK"`,
+//! and exceptionally reserved codes, such as `"UK"` or `"EU"`, are rejected.
+//! The code is stored as an upper case `String`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::country_code")]
+//!     pub country: String,
+//! }
+//! ```
+//! The following will deserialize to `Outer { country: "DE".to_string() }`
+//! ```json
+//! {
+//!     "country": "de"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+/// The officially assigned ISO 3166-1 alpha-2 codes, in order.
+pub const COUNTRY_CODES: [&str; 249] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+pub(crate) fn validate(value: &str) -> Result<String, String> {
+    let code = value.trim().to_ascii_uppercase();
+    if COUNTRY_CODES.binary_search(&code.as_str()).is_err() {
+        return Err(format!("unknown ISO 3166 country code \"{}\"", value));
+    }
+    Ok(code)
+}
+
+/// Country codes are serialized unchanged.
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    str::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let value = String::deserialize(d)?;
+    validate(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::country_code")]
+        pub country: String,
+    }
+
+    #[test]
+    fn sorted() {
+        assert!(super::COUNTRY_CODES.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn deserialize() {
+        let result: Outer = serde_json::from_str(r#"{"country": " de "}"#).expect("Oops!");
+        assert_eq!(result.country, "DE");
+        for value in ["UK", "XK", "USA", "", "1"] {
+            let json = format!(r#"{{"country": "{}"}}"#, value);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", value);
+        }
+    }
+}
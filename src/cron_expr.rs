@@ -0,0 +1,300 @@
+//! Deserialize a crontab expression, such as `"*/15 9-17 * * mon-fri"`, validated
+//!
+//! Scheduler configs usually hold their schedules as strings, and a typo
+//! only shows up when the job never runs.  This module checks the expression
+//! when the config is read, and the error quotes the bad expression and
+//! names the bad field.
+//!
+//! The classic five field crontab syntax is accepted:
+//!
+//! | Field        | Values              |
+//! |--------------|---------------------|
+//! | minute       | `0-59`              |
+//! | hour         | `0-23`              |
+//! | day of month | `1-31`              |
+//! | month        | `1-12` or `jan-dec` |
+//! | day of week  | `0-7` or `sun-sat`, where `0` and `7` are Sunday |
+//!
+//! Each field is a comma separated list of `*`, a value, or a range `a-b`.
+//! A `*` or a range may take a step, as in `*/15` or `9-17/2`.  Names are
+//! not case sensitive.  The `@yearly`, `@annually`, `@monthly`, `@weekly`,
+//! `@daily`, `@midnight`, `@hourly` and `@reboot` shorthands are accepted
+//! too.  The expression is stored as given.
+//!
+//! With the `cron` feature, the [schedule] module deserializes into a
+//! `cron::Schedule` instead, using that crate's syntax.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::cron_expr")]
+//!     pub backup: String,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "backup": "30 2 * * sun"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+const MACROS: [&str; 8] = [
+    "@yearly",
+    "@annually",
+    "@monthly",
+    "@weekly",
+    "@daily",
+    "@midnight",
+    "@hourly",
+    "@reboot",
+];
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const FIELDS: [Field; 5] = [
+    Field {
+        name: "minute",
+        min: 0,
+        max: 59,
+        names: &[],
+    },
+    Field {
+        name: "hour",
+        min: 0,
+        max: 23,
+        names: &[],
+    },
+    Field {
+        name: "day of month",
+        min: 1,
+        max: 31,
+        names: &[],
+    },
+    Field {
+        name: "month",
+        min: 1,
+        max: 12,
+        names: &MONTHS,
+    },
+    Field {
+        name: "day of week",
+        min: 0,
+        max: 7,
+        names: &WEEKDAYS,
+    },
+];
+
+impl Field {
+    fn value(&self, s: &str) -> Result<u32, String> {
+        let lower = s.to_ascii_lowercase();
+        if let Some(i) = self.names.iter().position(|n| *n == lower) {
+            return Ok(i as u32 + self.min);
+        }
+        match s.parse::<u32>() {
+            Ok(v) if s.bytes().all(|b| b.is_ascii_digit()) => {
+                if (self.min..=self.max).contains(&v) {
+                    Ok(v)
+                } else {
+                    Err(format!(
+                        "{} {} is out of range {}-{}",
+                        self.name, v, self.min, self.max
+                    ))
+                }
+            }
+            _ => Err(format!("invalid {} \"{}\"", self.name, s)),
+        }
+    }
+
+    fn check(&self, field: &str) -> Result<(), String> {
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            if let Some(step) = step {
+                match step.parse::<u32>() {
+                    Ok(n) if n > 0 && step.bytes().all(|b| b.is_ascii_digit()) => {}
+                    _ => return Err(format!("invalid {} step \"{}\"", self.name, step)),
+                }
+            }
+            if range == "*" {
+                continue;
+            }
+            match range.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (self.value(start)?, self.value(end)?);
+                    if start > end {
+                        return Err(format!("{} range \"{}\" is backwards", self.name, range));
+                    }
+                }
+                None if step.is_some() => {
+                    return Err(format!(
+                        "{} step needs \"*\" or a range, not \"{}\"",
+                        self.name, range
+                    ))
+                }
+                None => {
+                    self.value(range)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check that `value` is a five field crontab expression, or a shorthand.
+pub(crate) fn validate(value: &str) -> Result<(), String> {
+    let bad = |why: String| format!("invalid cron expression \"{}\": {}", value, why);
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    if let [shorthand] = fields[..] {
+        if shorthand.starts_with('@') {
+            return match MACROS.contains(&shorthand.to_ascii_lowercase().as_str()) {
+                true => Ok(()),
+                false => Err(bad(format!("unknown shorthand \"{}\"", shorthand))),
+            };
+        }
+    }
+    if fields.len() != FIELDS.len() {
+        return Err(bad(format!("expected 5 fields, found {}", fields.len())));
+    }
+    for (field, spec) in fields.iter().zip(FIELDS.iter()) {
+        spec.check(field).map_err(bad)?;
+    }
+    Ok(())
+}
+
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    str::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let value = String::deserialize(d)?;
+    validate(&value).map_err(serde::de::Error::custom)?;
+    Ok(value)
+}
+
+/// Serialize and Deserialize a `cron::Schedule`
+///
+/// Uses the `cron` crate's syntax, which starts with a seconds field and
+/// may end with a year field, as in `"0 30 2 * * Sun"`.  Note that its
+/// numeric days of the week run from `1` (Sunday) to `7`.  The schedule is
+/// serialized as the expression it was parsed from.
+///
+/// Requires the `cron` feature.
+#[cfg(feature = "cron")]
+pub mod schedule {
+    use std::str::FromStr;
+
+    use cron::Schedule;
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &Schedule, s: S) -> Result<S::Ok, S::Error> {
+        str::serialize(v.source(), s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Schedule, D::Error> {
+        let value = String::deserialize(d)?;
+        Schedule::from_str(&value).map_err(|e| {
+            serde::de::Error::custom(format!("invalid cron expression \"{}\": {}", value, e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::cron_expr")]
+        pub schedule: String,
+    }
+
+    #[test]
+    fn deserialize() {
+        for expr in [
+            "* * * * *",
+            "*/15 9-17 * * mon-fri",
+            "0 0 1,15 * 0",
+            "30 2 * JAN-Mar/2 7",
+            "0 22 * * 1-5",
+            "5,10-20/5 */2 31 12 sat",
+            "@daily",
+            "@REBOOT",
+        ] {
+            let json = format!(r#"{{"schedule":"{}"}}"#, expr);
+            let result: Outer = serde_json::from_str(&json).expect("Oops!");
+            assert_eq!(result.schedule, expr);
+            assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (expr, why) in [
+            ("* * * *", "expected 5 fields, found 4"),
+            ("0 0 * * * *", "expected 5 fields, found 6"),
+            ("60 * * * *", "minute 60 is out of range 0-59"),
+            ("* 24 * * *", "hour 24 is out of range 0-23"),
+            ("* * 0 * *", "day of month 0 is out of range 1-31"),
+            ("* * * foo *", "invalid month \"foo\""),
+            ("* * * * 8", "day of week 8 is out of range 0-7"),
+            ("*/0 * * * *", "invalid minute step \"0\""),
+            ("5/10 * * * *", "minute step needs \"*\" or a range"),
+            ("* 17-9 * * *", "hour range \"17-9\" is backwards"),
+            ("@fortnightly", "unknown shorthand \"@fortnightly\""),
+        ] {
+            let json = format!(r#"{{"schedule":"{}"}}"#, expr);
+            let msg = serde_json::from_str::<Outer>(&json)
+                .unwrap_err()
+                .to_string();
+            let quoted = format!("invalid cron expression \"{}\"", expr);
+            assert!(msg.contains(&quoted), "{}", msg);
+            assert!(msg.contains(why), "{}", msg);
+        }
+    }
+
+    #[cfg(feature = "cron")]
+    #[test]
+    fn schedule() {
+        use cron::Schedule;
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::cron_expr::schedule")]
+            pub schedule: Schedule,
+        }
+
+        let json = r#"{"schedule":"0 30 2 * * Sun"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+
+        let json = r#"{"schedule":"0 61 2 * * Sun"}"#;
+        let msg = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(
+            msg.contains("invalid cron expression \"0 61 2 * * Sun\""),
+            "{}",
+            msg
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Deserialize an ISO 4217 currency code, such as `"EUR"`
+//!
+//! Codes are trimmed and upper cased, then checked against the active ISO
+//! 4217 currency codes, including the fund and precious metal codes, such as
+//! `"XAU"`.  Historic codes, such as `"DEM"`, are rejected.  The code is
+//! stored as an upper case `String`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::currency_code")]
+//!     pub currency: String,
+//! }
+//! ```
+//! The following will deserialize to `Outer { currency: "EUR".to_string() }`
+//! ```json
+//! {
+//!     "currency": "eur"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+/// The active ISO 4217 currency codes, in order.
+pub const CURRENCY_CODES: [&str; 177] = [
+    "AED", "AFN", "ALL", "AMD", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT", "BHD",
+    "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD", "CDF",
+    "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUP", "CVE", "CZK", "DJF",
+    "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS", "GIP",
+    "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR",
+    "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT",
+    "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP",
+    "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR",
+    "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB",
+    "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN",
+    "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH",
+    "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES", "VND", "VUV", "WST", "XAF",
+    "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR", "XOF", "XPD", "XPF", "XPT",
+    "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWG",
+];
+
+pub(crate) fn validate(value: &str) -> Result<String, String> {
+    let code = value.trim().to_ascii_uppercase();
+    if CURRENCY_CODES.binary_search(&code.as_str()).is_err() {
+        return Err(format!("unknown ISO 4217 currency code \"{}\"", value));
+    }
+    Ok(code)
+}
+
+/// Currency codes are serialized unchanged.
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    str::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let value = String::deserialize(d)?;
+    validate(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::currency_code")]
+        pub currency: String,
+    }
+
+    #[test]
+    fn sorted() {
+        assert!(super::CURRENCY_CODES.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn deserialize() {
+        let result: Outer = serde_json::from_str(r#"{"currency": "eur"}"#).expect("Oops!");
+        assert_eq!(result.currency, "EUR");
+        let result: Outer = serde_json::from_str(r#"{"currency": "XCG"}"#).expect("Oops!");
+        assert_eq!(result.currency, "XCG");
+        for value in ["DEM", "EU", "EURO", ""] {
+            let json = format!(r#"{{"currency": "{}"}}"#, value);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", value);
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! Deserialize a date-time by trying an ordered list of formats
+//!
+//! Aggregated vendor data never agrees on one format.  This module tries, in
+//! order:
+//!
+//! 1. RFC 3339, such as `"2023-11-14T22:13:20Z"`
+//! 2. RFC 2822, such as `"Tue, 14 Nov 2023 22:13:20 +0000"`
+//! 3. `%Y-%m-%d %H:%M:%S`, such as `"2023-11-14 22:13:20"`, read as UTC
+//! 4. an epoch number, or numeric string, read as seconds or milliseconds by
+//!    magnitude as in [timestamp_or_string](crate::timestamp_or_string)
+//!
+//! If none match, the error lists every format that was attempted.  Values
+//! are serialized as RFC 3339.
+//!
+//! To try a different list or order, call [deserialize_formats] from your own
+//! `deserialize_with` function.
+//!
+//! Any [Rfc3339Time] is supported, including `chrono` and `time` types behind
+//! their features.  Types with an offset keep the offset that was parsed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::datetime_fallback")]
+//!     pub created: SystemTime,
+//! }
+//! ```
+//! The following fails with `no date format matched "yesterday"; tried RFC 3339, RFC 2822, %Y-%m-%d %H:%M:%S, epoch`
+//! ```json
+//! {
+//!     "created": "yesterday"
+//! }
+//! ```
+
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::civil;
+use super::rfc3339::{self, Rfc3339Time};
+use super::timestamp_or_string::{self, DEFAULT_MILLIS_THRESHOLD};
+
+/// A format to attempt, in [deserialize_formats].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateFormat {
+    Rfc3339,
+    Rfc2822,
+    /// `%Y-%m-%d %H:%M:%S`, with optional fractional seconds, read as UTC.
+    Naive,
+    /// Epoch seconds or milliseconds, as a number or a numeric string.
+    Epoch,
+}
+
+impl DateFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DateFormat::Rfc3339 => "RFC 3339",
+            DateFormat::Rfc2822 => "RFC 2822",
+            DateFormat::Naive => "%Y-%m-%d %H:%M:%S",
+            DateFormat::Epoch => "epoch",
+        }
+    }
+
+    /// Parse to nanoseconds since the Unix epoch, and an offset in seconds.
+    fn parse(&self, value: &str) -> Option<(i128, i32)> {
+        let parsed = match self {
+            DateFormat::Rfc3339 => civil::parse_rfc3339(value),
+            DateFormat::Rfc2822 => civil::parse_rfc2822(value),
+            DateFormat::Naive => civil::parse_naive(value),
+            DateFormat::Epoch => {
+                let value = value.trim();
+                let nanos = match value.parse::<i64>() {
+                    Ok(n) => timestamp_or_string::int_nanos(n as i128, DEFAULT_MILLIS_THRESHOLD),
+                    Err(_) => value.parse::<f64>().ok().and_then(|n| {
+                        timestamp_or_string::float_nanos(n, DEFAULT_MILLIS_THRESHOLD)
+                    }),
+                };
+                return nanos.map(|n| (n, 0));
+            }
+        };
+        parsed.ok().map(|p| (p.epoch_nanos(), p.offset))
+    }
+}
+
+/// The formats tried by [deserialize], in order.
+pub const DEFAULT_FORMATS: &[DateFormat] = &[
+    DateFormat::Rfc3339,
+    DateFormat::Rfc2822,
+    DateFormat::Naive,
+    DateFormat::Epoch,
+];
+
+fn attempted(formats: &[DateFormat]) -> String {
+    formats
+        .iter()
+        .map(DateFormat::name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Deserialize with the first of `formats` that matches.
+pub fn deserialize_formats<'de, D, T>(d: D, formats: &'static [DateFormat]) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Rfc3339Time,
+{
+    struct Fallback<T>(&'static [DateFormat], PhantomData<T>);
+
+    impl<T: Rfc3339Time> Fallback<T> {
+        fn to_time<E: de::Error>(&self, parts: Option<(i128, i32)>) -> Result<T, E> {
+            parts
+                .and_then(|(nanos, offset)| T::from_rfc3339_parts(nanos, offset))
+                .ok_or_else(|| E::custom("date out of range"))
+        }
+
+        fn epoch<E: de::Error>(&self, nanos: Option<i128>, unexpected: String) -> Result<T, E> {
+            if !self.0.contains(&DateFormat::Epoch) {
+                return Err(E::custom(format!(
+                    "no date format matched {}; tried {}",
+                    unexpected,
+                    attempted(self.0)
+                )));
+            }
+            self.to_time(nanos.map(|n| (n, 0)))
+        }
+    }
+
+    impl<'de, T: Rfc3339Time> Visitor<'de> for Fallback<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a date in one of: {}", attempted(self.0))
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            let nanos = timestamp_or_string::int_nanos(value as i128, DEFAULT_MILLIS_THRESHOLD);
+            self.epoch(nanos, value.to_string())
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            let nanos = timestamp_or_string::int_nanos(value as i128, DEFAULT_MILLIS_THRESHOLD);
+            self.epoch(nanos, value.to_string())
+        }
+
+        fn visit_f64<E>(self, value: f64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            let nanos = timestamp_or_string::float_nanos(value, DEFAULT_MILLIS_THRESHOLD);
+            self.epoch(nanos, value.to_string())
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            match self.0.iter().find_map(|f| f.parse(value)) {
+                Some(parts) => self.to_time(Some(parts)),
+                None => Err(E::custom(format!(
+                    "no date format matched \"{}\"; tried {}",
+                    value,
+                    attempted(self.0)
+                ))),
+            }
+        }
+    }
+
+    d.deserialize_any(Fallback(formats, PhantomData))
+}
+
+pub fn serialize<S: Serializer, T: Rfc3339Time>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    rfc3339::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: Rfc3339Time>(d: D) -> Result<T, D::Error> {
+    deserialize_formats(d, DEFAULT_FORMATS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DateFormat;
+    use serde::{Deserialize, Deserializer, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::datetime_fallback")]
+        pub created: SystemTime,
+    }
+
+    fn strings_only<'de, D: Deserializer<'de>>(d: D) -> Result<SystemTime, D::Error> {
+        super::deserialize_formats(d, &[DateFormat::Rfc2822, DateFormat::Rfc3339])
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    pub struct Custom {
+        #[serde(deserialize_with = "strings_only")]
+        pub created: SystemTime,
+    }
+
+    #[test]
+    fn deserialize() {
+        let expected = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        for value in [
+            r#""2023-11-14T22:13:20Z""#,
+            r#""Tue, 14 Nov 2023 23:13:20 +0100""#,
+            r#""14 Nov 2023 17:13:20 EST""#,
+            r#""2023-11-14 22:13:20""#,
+            r#""1700000000""#,
+            "1700000000",
+            "1700000000000",
+        ] {
+            let json = format!(r#"{{"created": {}}}"#, value);
+            let result: Outer = serde_json::from_str(&json).expect(value);
+            assert_eq!(result.created, expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn deserialize_failure_lists_formats() {
+        let result = serde_json::from_str::<Outer>(r#"{"created": "yesterday"}"#);
+        let msg = result.unwrap_err().to_string();
+        assert!(
+            msg.contains(r#"tried RFC 3339, RFC 2822, %Y-%m-%d %H:%M:%S, epoch"#),
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn deserialize_custom_formats() {
+        let result: Custom =
+            serde_json::from_str(r#"{"created": "Tue, 14 Nov 2023 22:13:20 GMT"}"#).expect("Oops!");
+        assert_eq!(
+            result.created,
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+        );
+        let result = serde_json::from_str::<Custom>(r#"{"created": 1700000000}"#);
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("tried RFC 2822, RFC 3339"), "{}", msg);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"created":"2023-11-14T22:13:20Z"}"#);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{DateTime, FixedOffset};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Chrono {
+            #[serde(with = "crate::datetime_fallback")]
+            pub created: DateTime<FixedOffset>,
+        }
+
+        let json = r#"{"created": "Tue, 14 Nov 2023 23:13:20 +0100"}"#;
+        let result: Chrono = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.created.offset().local_minus_utc(), 3600);
+        let result = serde_json::to_string(&result).expect("Oops!");
+        assert_eq!(result, r#"{"created":"2023-11-14T23:13:20+01:00"}"#);
+    }
+}
@@ -0,0 +1,229 @@
+//! Serialize the same value to the same output, every time
+//!
+//! Content addressing and snapshot tests need a value to serialize to the
+//! same bytes on every run, but a `HashMap` iterates in a different order
+//! each time, and bytes and floats can be written more than one way.
+//! [Deterministic] and [serialize] capture the value as [Content], and then:
+//! - sort the keys of every map, all the way down, by string, or by number
+//!   for numeric keys.  Struct fields are sorted too.
+//! - write `-0.0` as `0.0`, and every `NaN` as the same `NaN`.  Floats are
+//!   written as `f64`.
+//! - write bytes as a string, as the [base64](crate::base64) module does.
+//!
+//! The format still decides how each value is written, so use a format
+//! that is deterministic itself, such as `serde_json`.  A `HashSet` is a
+//! sequence, which can't be told from a `Vec`, so it isn't sorted.  Use a
+//! `BTreeSet` instead.  For JSON to sign, see
+//! [canonical_json](crate::canonical_json).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::Serialize;
+//! use serde_stuff::deterministic::Deterministic;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Serialize)]
+//! pub struct Outer {
+//!     pub name: String,
+//!     pub labels: HashMap<String, String>,
+//! }
+//!
+//! let outer = Outer {
+//!     name: "web".to_string(),
+//!     labels: [("tier", "front"), ("app", "shop")]
+//!         .into_iter()
+//!         .map(|(k, v)| (k.to_string(), v.to_string()))
+//!         .collect(),
+//! };
+//! assert_eq!(
+//!     serde_json::to_string(&Deterministic(&outer)).unwrap(),
+//!     r#"{"labels":{"app":"shop","tier":"front"},"name":"web"}"#
+//! );
+//! ```
+//!
+//! As a field's `serialize_with`, only that field is made deterministic:
+//!
+//! ```rust
+//! use serde::Serialize;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Serialize)]
+//! pub struct Outer {
+//!     #[serde(serialize_with = "serde_stuff::deterministic::serialize")]
+//!     pub labels: HashMap<String, String>,
+//! }
+//! ```
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use serde::ser::{Error, Serialize, Serializer};
+
+use super::content::Content;
+
+/// Serializes `T` deterministically.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Deterministic<T>(pub T);
+
+impl<T: Serialize> Serialize for Deterministic<T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, s)
+    }
+}
+
+pub fn serialize<S: Serializer, T: ?Sized + Serialize>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let content = Content::from_value(v).map_err(S::Error::custom)?;
+    Normalized(&normalize(content)).serialize(s)
+}
+
+fn normalize(content: Content) -> Content {
+    match content {
+        // Matches `-0.0` too.
+        Content::F64(0.0) => Content::F64(0.0),
+        Content::F64(v) if v.is_nan() => Content::F64(f64::NAN),
+        Content::Seq(items) => Content::Seq(items.into_iter().map(normalize).collect()),
+        Content::Map(entries) => {
+            let mut entries: Vec<(Content, Content)> = entries
+                .into_iter()
+                .map(|(k, v)| (normalize(k), normalize(v)))
+                .collect();
+            entries.sort_by(|(a, _), (b, _)| compare(a, b));
+            Content::Map(entries)
+        }
+        content => content,
+    }
+}
+
+/// Order map keys: numbers by value, strings and bytes lexically, and
+/// anything else by kind.
+fn compare(a: &Content, b: &Content) -> Ordering {
+    match (a, b) {
+        (Content::I64(a), Content::I64(b)) => a.cmp(b),
+        (Content::U64(a), Content::U64(b)) => a.cmp(b),
+        (Content::I64(a), Content::U64(b)) => i128::from(*a).cmp(&i128::from(*b)),
+        (Content::U64(a), Content::I64(b)) => i128::from(*a).cmp(&i128::from(*b)),
+        (Content::F64(a), Content::F64(b)) => a.total_cmp(b),
+        (Content::String(a), Content::String(b)) => a.cmp(b),
+        (Content::Bytes(a), Content::Bytes(b)) => a.cmp(b),
+        (Content::Bool(a), Content::Bool(b)) => a.cmp(b),
+        (Content::Seq(a), Content::Seq(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| compare(a, b))
+            .find(|order| order.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Content::Map(a), Content::Map(b)) => a
+            .iter()
+            .zip(b)
+            .map(|((ak, av), (bk, bv))| compare(ak, bk).then_with(|| compare(av, bv)))
+            .find(|order| order.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (a, b) => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn rank(content: &Content) -> u8 {
+    match content {
+        Content::Unit => 0,
+        Content::Bool(_) => 1,
+        Content::I64(_) | Content::U64(_) => 2,
+        Content::F64(_) => 3,
+        Content::String(_) => 4,
+        Content::Bytes(_) => 5,
+        Content::Seq(_) => 6,
+        Content::Map(_) => 7,
+    }
+}
+
+/// Serializes a normalized [Content], with bytes as base64.
+struct Normalized<'a>(&'a Content);
+
+impl Serialize for Normalized<'_> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Content::Bytes(v) => super::base64::serialize(v, s),
+            Content::Seq(items) => s.collect_seq(items.iter().map(Normalized)),
+            Content::Map(entries) => {
+                s.collect_map(entries.iter().map(|(k, v)| (Normalized(k), Normalized(v))))
+            }
+            content => content.serialize(s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Serialize, Serializer};
+    use std::collections::HashMap;
+
+    use super::Deterministic;
+
+    /// Serializes with `serialize_bytes`, as `serde_bytes` does.
+    pub struct Bytes<'a>(pub &'a [u8]);
+
+    impl Serialize for Bytes<'_> {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_bytes(self.0)
+        }
+    }
+
+    #[derive(Serialize)]
+    pub struct Outer {
+        pub name: String,
+        pub labels: HashMap<String, f64>,
+        pub ports: HashMap<i32, String>,
+    }
+
+    #[test]
+    fn sorted() {
+        let outer = Outer {
+            name: "web".to_string(),
+            labels: [("b", -0.0), ("a", 1.5), ("c", f64::NAN)]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+            ports: [(443, "https"), (-1, "none"), (80, "http")]
+                .into_iter()
+                .map(|(k, v)| (k, v.to_string()))
+                .collect(),
+        };
+        let json = serde_json::to_string(&Deterministic(&outer)).expect("Oops!");
+        assert_eq!(
+            json,
+            r#"{"labels":{"a":1.5,"b":0.0,"c":null},"name":"web","ports":{"-1":"none","80":"http","443":"https"}}"#
+        );
+    }
+
+    #[test]
+    fn repeatable() {
+        let map: HashMap<String, Vec<u32>> = (0..100).map(|i| (i.to_string(), vec![i])).collect();
+        let first = serde_json::to_string(&Deterministic(&map)).expect("Oops!");
+        let copy: HashMap<String, Vec<u32>> = map.clone().into_iter().collect();
+        let second = serde_json::to_string(&Deterministic(&copy)).expect("Oops!");
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn bytes() {
+        let json =
+            serde_json::to_string(&Deterministic(Bytes(&[0xde, 0xad, 0xbe, 0xef]))).expect("Oops!");
+        assert_eq!(json, r#""3q2-7w==""#);
+    }
+
+    #[test]
+    fn serialize_with() {
+        #[derive(Serialize)]
+        pub struct Outer {
+            pub z: u8,
+            #[serde(serialize_with = "crate::deterministic::serialize")]
+            pub labels: HashMap<&'static str, u8>,
+        }
+
+        let outer = Outer {
+            z: 1,
+            labels: [("y", 2), ("x", 3)].into_iter().collect(),
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(json, r#"{"z":1,"labels":{"x":3,"y":2}}"#);
+    }
+}
@@ -0,0 +1,153 @@
+//! Serialize and Deserialize a `Duration` as seconds
+//!
+//! On deserialize, both integer and float seconds are accepted, so `30` and
+//! `0.25` are both valid.  Negative values are rejected.
+//!
+//! - `duration_secs` serializes whole seconds, dropping any sub-second part.
+//! - `duration_secs::float` serializes float seconds, keeping the fraction.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::duration_secs")]
+//!     pub timeout: Duration,
+//!     #[serde(with = "serde_stuff::duration_secs::float")]
+//!     pub backoff: Duration,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "timeout": 30,
+//!     "backoff": 0.25
+//! }
+//! ```
+
+use serde::de::{self, Unexpected, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::time::Duration;
+
+pub(crate) struct DurationSecs;
+
+impl<'de> Visitor<'de> for DurationSecs {
+    type Value = Duration;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a non-negative number of seconds")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Duration, E>
+    where
+        E: de::Error,
+    {
+        Ok(Duration::from_secs(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Duration, E>
+    where
+        E: de::Error,
+    {
+        u64::try_from(value)
+            .map(Duration::from_secs)
+            .map_err(|_| E::invalid_value(Unexpected::Signed(value), &self))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Duration, E>
+    where
+        E: de::Error,
+    {
+        Duration::try_from_secs_f64(value)
+            .map_err(|_| E::invalid_value(Unexpected::Float(value), &self))
+    }
+}
+
+pub fn serialize<S: Serializer>(v: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_u64(v.as_secs())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+    // Binary formats can't be asked what they hold, but only ever hold the
+    // u64 that `serialize` writes.
+    if d.is_human_readable() {
+        d.deserialize_any(DurationSecs)
+    } else {
+        d.deserialize_u64(DurationSecs)
+    }
+}
+
+/// Serialize as float seconds.
+pub mod float {
+    use serde::{Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(v: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_f64(v.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        if d.is_human_readable() {
+            d.deserialize_any(super::DurationSecs)
+        } else {
+            d.deserialize_f64(super::DurationSecs)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::duration_secs")]
+        pub timeout: Duration,
+        #[serde(with = "crate::duration_secs::float")]
+        pub backoff: Duration,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            timeout: Duration::from_millis(30_500),
+            backoff: Duration::from_millis(1_250),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"timeout":30,"backoff":1.25}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            timeout: Duration::from_millis(500),
+            backoff: Duration::from_secs(2),
+        };
+        let result: Outer =
+            serde_json::from_str(r#"{"timeout": 0.5, "backoff": 2}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_negative() {
+        assert!(serde_json::from_str::<Outer>(r#"{"timeout": -1, "backoff": 2}"#).is_err());
+        assert!(serde_json::from_str::<Outer>(r#"{"timeout": 1, "backoff": -0.5}"#).is_err());
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let outer = Outer {
+            timeout: Duration::from_secs(30),
+            backoff: Duration::from_millis(1_250),
+        };
+        let bytes = bincode::serialize(&outer).expect("Oops!");
+        let result: Outer = bincode::deserialize(&bytes).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
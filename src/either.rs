@@ -0,0 +1,205 @@
+//! Deserialize either of two, or three, types
+//!
+//! [string_or_struct](crate::string_or_struct) and
+//! [vec_or_one](crate::vec_or_one) cover the common cases of a field that
+//! takes more than one shape.  For the rest, [Either] tries `L`, then `R`,
+//! and keeps whichever matched, so the caller can tell them apart.  It
+//! serializes whichever side it holds.  [Either3] does the same for three
+//! types.
+//!
+//! Put the stricter type first, since the first that matches wins.  As for
+//! any untagged enum, the format must be self describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::either::Either;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Range {
+//!     pub from: u16,
+//!     pub to: u16,
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     pub port: Either<u16, Range>,
+//! }
+//!
+//! let outer: Outer = serde_json::from_str(r#"{"port": {"from": 80, "to": 90}}"#).unwrap();
+//! assert_eq!(outer.port.as_right().map(|range| range.to), Some(90));
+//! ```
+//! The following will also deserialize to `Outer`
+//! ```json
+//! {
+//!     "port": 443
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<L, R> Either<L, R> {
+    pub fn is_left(&self) -> bool {
+        matches!(self, Either::Left(_))
+    }
+
+    pub fn is_right(&self) -> bool {
+        matches!(self, Either::Right(_))
+    }
+
+    pub fn as_left(&self) -> Option<&L> {
+        match self {
+            Either::Left(l) => Some(l),
+            Either::Right(_) => None,
+        }
+    }
+
+    pub fn as_right(&self) -> Option<&R> {
+        match self {
+            Either::Left(_) => None,
+            Either::Right(r) => Some(r),
+        }
+    }
+
+    pub fn into_left(self) -> Option<L> {
+        match self {
+            Either::Left(l) => Some(l),
+            Either::Right(_) => None,
+        }
+    }
+
+    pub fn into_right(self) -> Option<R> {
+        match self {
+            Either::Left(_) => None,
+            Either::Right(r) => Some(r),
+        }
+    }
+
+    /// Convert both sides to a common type.
+    pub fn either<T>(self, left: impl FnOnce(L) -> T, right: impl FnOnce(R) -> T) -> T {
+        match self {
+            Either::Left(l) => left(l),
+            Either::Right(r) => right(r),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Either3<A, B, C> {
+    First(A),
+    Second(B),
+    Third(C),
+}
+
+impl<A, B, C> Either3<A, B, C> {
+    pub fn as_first(&self) -> Option<&A> {
+        match self {
+            Either3::First(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    pub fn as_second(&self) -> Option<&B> {
+        match self {
+            Either3::Second(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_third(&self) -> Option<&C> {
+        match self {
+            Either3::Third(c) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{Either, Either3};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Range {
+        pub from: u16,
+        pub to: u16,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        pub port: Either<u16, Range>,
+    }
+
+    #[test]
+    fn deserialize_left() {
+        let outer: Outer = serde_json::from_str(r#"{"port": 443}"#).expect("Oops!");
+        assert!(outer.port.is_left());
+        assert_eq!(outer.port.as_left(), Some(&443));
+        assert_eq!(outer.port.as_right(), None);
+    }
+
+    #[test]
+    fn deserialize_right() {
+        let outer: Outer =
+            serde_json::from_str(r#"{"port": {"from": 80, "to": 90}}"#).expect("Oops!");
+        assert!(outer.port.is_right());
+        assert_eq!(outer.port.into_right(), Some(Range { from: 80, to: 90 }));
+    }
+
+    #[test]
+    fn deserialize_neither() {
+        let err = serde_json::from_str::<Outer>(r#"{"port": "http"}"#)
+            .expect_err("Oops!")
+            .to_string();
+        let msg = "data did not match any variant of untagged enum Either";
+        assert!(err.contains(msg), "{}", err);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            port: Either::Right(Range { from: 80, to: 90 }),
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(json, r#"{"port":{"from":80,"to":90}}"#);
+
+        let outer = Outer {
+            port: Either::Left(443),
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(json, r#"{"port":443}"#);
+    }
+
+    #[test]
+    fn either() {
+        let ports: Vec<Either<u16, Range>> =
+            serde_json::from_str(r#"[443, {"from": 80, "to": 90}]"#).expect("Oops!");
+        let ports: Vec<u16> = ports
+            .into_iter()
+            .map(|port| port.either(|port| port, |range| range.to - range.from))
+            .collect();
+        assert_eq!(ports, vec![443, 10]);
+    }
+
+    #[test]
+    fn either3() {
+        let values: Vec<Either3<bool, u64, String>> =
+            serde_json::from_str(r#"[true, 7, "seven"]"#).expect("Oops!");
+        assert_eq!(values[0].as_first(), Some(&true));
+        assert_eq!(values[1].as_second(), Some(&7));
+        assert_eq!(values[2].as_third().map(|s| s.as_str()), Some("seven"));
+        assert_eq!(values[2].as_first(), None);
+        let json = serde_json::to_string(&values).expect("Oops!");
+        assert_eq!(json, r#"[true,7,"seven"]"#);
+    }
+}
@@ -0,0 +1,167 @@
+//! Deserialize an email address, with pragmatic validation
+//!
+//! Addresses are checked with the same rules as an HTML5
+//! `<input type="email">`: a local part of letters, digits and
+//! ``.!#$%&'*+/=?^_`{|}~-``, an `@`, and a domain of dot separated labels of
+//! letters, digits and inner hyphens, each at most 63 characters.  Quoted
+//! local parts, comments and IP address literals aren't accepted.
+//!
+//! Surrounding whitespace is trimmed, and the domain is lower cased, since
+//! domains are case insensitive.  The local part is left unchanged.  The
+//! address can be stored as a `String`, or as the provided [Email] newtype,
+//! which validates itself wherever it is deserialized.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::email::Email;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::email")]
+//!     pub owner: String,
+//!     pub contacts: Vec<Email>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`, with an owner of
+//! `"Jane.Doe@example.com"`
+//! ```json
+//! {
+//!     "owner": "Jane.Doe@Example.COM",
+//!     "contacts": ["ops@example.com"]
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use super::hostname::valid_label;
+
+const LOCAL_SPECIALS: &str = ".!#$%&'*+/=?^_`{|}~-";
+
+/// Validate `value`, and return it trimmed with a lower case domain.
+pub(crate) fn normalize(value: &str) -> Result<String, String> {
+    let trimmed = value.trim();
+    let bad = || format!("invalid email address \"{}\"", value);
+    let (local, domain) = trimmed.rsplit_once('@').ok_or_else(bad)?;
+    let local_ok = !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || LOCAL_SPECIALS.contains(c));
+    if !local_ok || !domain.split('.').all(valid_label) {
+        return Err(bad());
+    }
+    Ok(format!("{}@{}", local, domain.to_ascii_lowercase()))
+}
+
+/// A validated email address.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Email(String);
+
+impl Email {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl FromStr for Email {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        normalize(s).map(Email)
+    }
+}
+
+impl fmt::Display for Email {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Email {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Serialize for Email {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        str::serialize(&self.0, s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Email {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        deserialize(d).map(Email)
+    }
+}
+
+/// Email addresses are serialized unchanged.
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    str::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let value = String::deserialize(d)?;
+    normalize(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    use super::Email;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::email")]
+        pub owner: String,
+        pub contacts: Vec<Email>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "owner": " Jane.Doe+tag@Example.COM ",
+            "contacts": ["ops@mail.example.com"]
+        }"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.owner, "Jane.Doe+tag@example.com");
+        assert_eq!(result.contacts[0].as_str(), "ops@mail.example.com");
+        assert_eq!(
+            serde_json::to_string(&result).expect("Oops!"),
+            r#"{"owner":"Jane.Doe+tag@example.com","contacts":["ops@mail.example.com"]}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for value in [
+            "",
+            "jane",
+            "@example.com",
+            "jane@",
+            "jane doe@example.com",
+            "jane@exa mple.com",
+            "jane@-example.com",
+            "jane@example..com",
+            "jane@example.com.",
+            "\"jane\"@example.com",
+        ] {
+            let json = format!(
+                r#"{{"owner": "{}", "contacts": []}}"#,
+                value.replace('"', "\\\"")
+            );
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", value);
+            assert!(value.parse::<Email>().is_err(), "{}", value);
+        }
+    }
+}
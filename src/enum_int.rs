@@ -0,0 +1,193 @@
+//! Serialize and Deserialize a C-like enum as its integer discriminant
+//!
+//! APIs often send enums as numbers, `"level": 2`.  This module writes an
+//! enum as its discriminant and reads it back from any integer, naming the
+//! valid discriminants when the number is out of range, without needing the
+//! `serde_repr` derive.
+//!
+//! The enum implements [IntEnum], usually with the [int_enum!](crate::int_enum)
+//! macro.  See [option_enum_int](crate::option_enum_int) for `Option`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug)]
+//! pub enum Level {
+//!     Debug = 1,
+//!     Info = 2,
+//!     Warn = 4,
+//! }
+//! serde_stuff::int_enum!(Level { Debug, Info, Warn });
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::enum_int")]
+//!     pub level: Level,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "level": 2
+//! }
+//! ```
+
+use serde::de::{self, Unexpected, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Enums with integer discriminants.
+pub trait IntEnum: Sized {
+    /// The enum name, for error messages.
+    const NAME: &'static str;
+    /// Every discriminant, in declaration order.
+    const DISCRIMINANTS: &'static [i64];
+
+    fn to_int(&self) -> i64;
+    fn from_int(value: i64) -> Option<Self>;
+}
+
+/// Implement [IntEnum](crate::enum_int::IntEnum) for a C-like enum by
+/// listing its variants.
+#[macro_export]
+macro_rules! int_enum {
+    ($ty:ident { $($variant:ident),+ $(,)? }) => {
+        impl $crate::enum_int::IntEnum for $ty {
+            const NAME: &'static str = stringify!($ty);
+            const DISCRIMINANTS: &'static [i64] = &[$($ty::$variant as i64),+];
+
+            fn to_int(&self) -> i64 {
+                match self {
+                    $($ty::$variant => $ty::$variant as i64,)+
+                }
+            }
+
+            fn from_int(value: i64) -> Option<Self> {
+                $(
+                    if value == $ty::$variant as i64 {
+                        return Some($ty::$variant);
+                    }
+                )+
+                None
+            }
+        }
+    };
+}
+
+pub(crate) struct IntEnumVisitor<T>(pub(crate) PhantomData<T>);
+
+impl<T: IntEnum> IntEnumVisitor<T> {
+    fn expected(&self) -> String {
+        let discriminants: Vec<String> = T::DISCRIMINANTS.iter().map(i64::to_string).collect();
+        format!(
+            "a {} discriminant, one of {}",
+            T::NAME,
+            discriminants.join(", ")
+        )
+    }
+}
+
+impl<'de, T: IntEnum> Visitor<'de> for IntEnumVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.expected())
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        T::from_int(value)
+            .ok_or_else(|| E::invalid_value(Unexpected::Signed(value), &self.expected().as_str()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        i64::try_from(value)
+            .ok()
+            .and_then(T::from_int)
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(value), &self.expected().as_str()))
+    }
+}
+
+pub fn serialize<S: Serializer, T: IntEnum>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_i64(v.to_int())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: IntEnum>(d: D) -> Result<T, D::Error> {
+    d.deserialize_i64(IntEnumVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(PartialEq, Debug)]
+    pub enum Level {
+        Debug = 1,
+        Info = 2,
+        Warn = 4,
+        Error = -1,
+    }
+    crate::int_enum!(Level {
+        Debug,
+        Info,
+        Warn,
+        Error,
+    });
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::enum_int")]
+        pub level: Level,
+    }
+
+    #[test]
+    fn deserialize() {
+        for (json, level) in [
+            (r#"{"level": 1}"#, Level::Debug),
+            (r#"{"level": 4}"#, Level::Warn),
+            (r#"{"level": -1}"#, Level::Error),
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(Outer { level }, result);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer { level: Level::Info };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"level":2}"#);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let err = serde_json::from_str::<Outer>(r#"{"level": 3}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(
+                "invalid value: integer `3`, expected a Level discriminant, one of 1, 2, 4, -1"
+            ),
+            "{}",
+            err
+        );
+        assert!(serde_json::from_str::<Outer>(r#"{"level": "Info"}"#).is_err());
+    }
+
+    #[test]
+    fn bincode() {
+        let outer = Outer { level: Level::Warn };
+        let bytes = bincode::serialize(&outer).expect("Oops!");
+        let result: Outer = bincode::deserialize(&bytes).expect("Oops!");
+        assert_eq!(outer, result);
+    }
+}
@@ -0,0 +1,193 @@
+//! Unwrap single key envelope objects, such as `{"value": 42}`
+//!
+//! Some APIs wrap every scalar in an object with a single key.  This
+//! module reads `{"value": x}` as `x`, and writes `x` back wrapped.  The
+//! object must have the key, and no others.
+//!
+//! The [envelope!](crate::envelope!) macro generates `serialize` and
+//! `deserialize` functions for another key.  It can be invoked inside a
+//! module you declare, or given a module name to declare:
+//!
+//! ```rust
+//! mod data {
+//!     serde_stuff::envelope!("data");
+//! }
+//!
+//! serde_stuff::envelope!(pub wrapped, "Wrapped");
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::envelope")]
+//!     pub count: u32,
+//!     #[serde(with = "serde_stuff::envelope")]
+//!     pub name: String,
+//! }
+//! ```
+//! The following will deserialize to `Outer { count: 42, name: "ann".to_string() }`
+//! ```json
+//! {
+//!     "count": {"value": 42},
+//!     "name": {"value": "ann"}
+//! }
+//! ```
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Serialize `v` wrapped in an object with the single `key`.
+pub fn serialize_with<S, T>(v: &T, s: S, key: &'static str) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let mut map = s.serialize_map(Some(1))?;
+    map.serialize_entry(key, v)?;
+    map.end()
+}
+
+/// Deserialize the value of the single `key` of an object.
+pub fn deserialize_with<'de, D, T>(d: D, key: &'static str) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    struct EnvelopeVisitor<T>(&'static str, PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for EnvelopeVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "an object with the single key \"{}\"", self.0)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+            let mut value = None;
+            while let Some(name) = map.next_key::<String>()? {
+                if name != self.0 {
+                    return Err(de::Error::custom(format!(
+                        "unknown field `{}`, expected `{}`",
+                        name, self.0
+                    )));
+                }
+                if value.is_some() {
+                    return Err(de::Error::duplicate_field(self.0));
+                }
+                value = Some(map.next_value()?);
+            }
+            value.ok_or_else(|| de::Error::missing_field(self.0))
+        }
+    }
+
+    d.deserialize_map(EnvelopeVisitor(key, PhantomData))
+}
+
+pub fn serialize<S: Serializer, T: Serialize>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    serialize_with(v, s, "value")
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    deserialize_with(d, "value")
+}
+
+/// Generate a serde `with` module for an envelope with another key.
+///
+/// With only the key, the functions are generated in place.  With a
+/// visibility and name first, a module of that name is declared.
+#[macro_export]
+macro_rules! envelope {
+    ($vis:vis $name:ident, $key:literal) => {
+        $vis mod $name {
+            $crate::envelope!($key);
+        }
+    };
+    ($key:literal) => {
+        #[allow(dead_code)]
+        pub fn serialize<S, T>(v: &T, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+            T: serde::Serialize,
+        {
+            $crate::envelope::serialize_with(v, s, $key)
+        }
+
+        #[allow(dead_code)]
+        pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+            T: serde::Deserialize<'de>,
+        {
+            $crate::envelope::deserialize_with(d, $key)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    mod data {
+        crate::envelope!("data");
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::envelope")]
+        pub count: u32,
+        #[serde(with = "data")]
+        pub tags: Vec<String>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"count": {"value": 42}, "tags": {"data": ["a", "b"]}}"#;
+        let outer = Outer {
+            count: 42,
+            tags: vec!["a".to_string(), "b".to_string()],
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            count: 7,
+            tags: vec![],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"count":{"value":7},"tags":{"data":[]}}"#);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (count, msg) in [
+            ("42", "an object with the single key \"value\""),
+            ("{}", "missing field `value`"),
+            (
+                r#"{"value": 1, "unit": "s"}"#,
+                "unknown field `unit`, expected `value`",
+            ),
+            (r#"{"value": 1, "value": 2}"#, "duplicate field `value`"),
+        ] {
+            let json = format!(r#"{{"count": {}, "tags": {{"data": []}}}}"#, count);
+            let err = serde_json::from_str::<Outer>(&json)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Time types that can be converted to and from a Unix epoch timestamp.
+//!
+//! The `timestamp_*` modules work with any type that implements
+//! [EpochTime].  `SystemTime` is always supported.  With the `chrono` feature
+//! `chrono::DateTime<Utc>` is supported, and with the `time` feature
+//! `time::OffsetDateTime` is supported.  With the `jiff` feature,
+//! `jiff::Timestamp` and `jiff::Zoned` are supported.  A `Zoned` read from
+//! an epoch timestamp is in UTC.
+//!
+//! Times are converted through a signed count of nanoseconds since the Unix
+//! epoch, which covers values both before and after the epoch.
+
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub(crate) const NANOS_PER_SEC: i128 = 1_000_000_000;
+pub(crate) const NANOS_PER_MILLI: i128 = 1_000_000;
+pub(crate) const NANOS_PER_MICRO: i128 = 1_000;
+pub(crate) const NANOS_PER_NANO: i128 = 1;
+
+/// A point in time that can be expressed as nanoseconds since the Unix epoch.
+pub trait EpochTime: Sized {
+    /// Nanoseconds since the Unix epoch.  Negative values are before the epoch.
+    fn to_epoch_nanos(&self) -> i128;
+
+    /// Build from nanoseconds since the Unix epoch, or `None` if the value is
+    /// out of range for the type.
+    fn from_epoch_nanos(nanos: i128) -> Option<Self>;
+}
+
+impl EpochTime for SystemTime {
+    fn to_epoch_nanos(&self) -> i128 {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_nanos() as i128,
+            Err(e) => -(e.duration().as_nanos() as i128),
+        }
+    }
+
+    fn from_epoch_nanos(nanos: i128) -> Option<Self> {
+        let abs = nanos.unsigned_abs();
+        let secs = u64::try_from(abs / NANOS_PER_SEC as u128).ok()?;
+        let d = Duration::new(secs, (abs % NANOS_PER_SEC as u128) as u32);
+        if nanos >= 0 {
+            UNIX_EPOCH.checked_add(d)
+        } else {
+            UNIX_EPOCH.checked_sub(d)
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl EpochTime for chrono::DateTime<chrono::Utc> {
+    fn to_epoch_nanos(&self) -> i128 {
+        self.timestamp() as i128 * NANOS_PER_SEC + self.timestamp_subsec_nanos() as i128
+    }
+
+    fn from_epoch_nanos(nanos: i128) -> Option<Self> {
+        let secs = i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+        chrono::DateTime::from_timestamp(secs, nanos.rem_euclid(NANOS_PER_SEC) as u32)
+    }
+}
+
+#[cfg(feature = "time")]
+impl EpochTime for time::OffsetDateTime {
+    fn to_epoch_nanos(&self) -> i128 {
+        self.unix_timestamp_nanos()
+    }
+
+    fn from_epoch_nanos(nanos: i128) -> Option<Self> {
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl EpochTime for jiff::Timestamp {
+    fn to_epoch_nanos(&self) -> i128 {
+        self.as_nanosecond()
+    }
+
+    fn from_epoch_nanos(nanos: i128) -> Option<Self> {
+        jiff::Timestamp::from_nanosecond(nanos).ok()
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl EpochTime for jiff::Zoned {
+    fn to_epoch_nanos(&self) -> i128 {
+        self.timestamp().as_nanosecond()
+    }
+
+    fn from_epoch_nanos(nanos: i128) -> Option<Self> {
+        let timestamp = jiff::Timestamp::from_nanosecond(nanos).ok()?;
+        Some(timestamp.to_zoned(jiff::tz::TimeZone::UTC))
+    }
+}
+
+/// Serialize as a whole number of `unit` nanoseconds, rounding towards
+/// negative infinity so that pre-epoch times stay ordered.
+pub(crate) fn serialize<S: Serializer, T: EpochTime>(
+    v: &T,
+    s: S,
+    unit: i128,
+) -> Result<S::Ok, S::Error> {
+    let value = v.to_epoch_nanos().div_euclid(unit);
+    match i64::try_from(value) {
+        Ok(value) => s.serialize_i64(value),
+        Err(_) => Err(serde::ser::Error::custom("timestamp out of range")),
+    }
+}
+
+/// Deserialize from an integer or float count of `unit` nanoseconds.
+pub(crate) fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(
+    d: D,
+    unit: i128,
+) -> Result<T, D::Error> {
+    struct Epoch<T>(i128, PhantomData<T>);
+
+    impl<T: EpochTime> Epoch<T> {
+        fn to_time<E: de::Error>(&self, nanos: Option<i128>) -> Result<T, E> {
+            nanos
+                .and_then(T::from_epoch_nanos)
+                .ok_or_else(|| E::custom("timestamp out of range"))
+        }
+    }
+
+    impl<'de, T: EpochTime> Visitor<'de> for Epoch<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an integer or float timestamp")
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            self.to_time((value as i128).checked_mul(self.0))
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            self.to_time((value as i128).checked_mul(self.0))
+        }
+
+        fn visit_f64<E>(self, value: f64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            if !value.is_finite() {
+                return Err(E::invalid_value(de::Unexpected::Float(value), &self));
+            }
+            // Scale the whole and fractional parts separately, to keep the
+            // precision of large timestamps.
+            let whole = value.trunc();
+            if whole.abs() >= i64::MAX as f64 {
+                return self.to_time(None);
+            }
+            let fraction = ((value - whole) * self.0 as f64).round() as i128;
+            self.to_time((whole as i128).checked_mul(self.0).map(|n| n + fraction))
+        }
+    }
+
+    // Binary formats can't be asked what they hold, but only ever hold the
+    // i64 that `serialize` writes.
+    if d.is_human_readable() {
+        d.deserialize_any(Epoch(unit, PhantomData))
+    } else {
+        d.deserialize_i64(Epoch(unit, PhantomData))
+    }
+}
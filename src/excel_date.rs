@@ -0,0 +1,180 @@
+//! Serialize and Deserialize a time as an Excel serial date number
+//!
+//! Spreadsheets store dates as a count of days, with the time of day as the
+//! fraction, so `45123.5` is noon on 2023-07-16.  Excel (and Google Sheets,
+//! for compatibility) count from 1900-01-00, and keep the Lotus 1-2-3 bug
+//! that treats 1900 as a leap year:
+//!
+//! - serials below `60` are 1900-01-00 through 1900-02-28, as Excel shows them.
+//! - serial `60` is the nonexistent 1900-02-29, and is rejected.
+//! - serials from `61` (1900-03-01) on are days since 1899-12-30.
+//!
+//! The fraction is rounded to the nearest millisecond, which is all the
+//! precision a spreadsheet keeps.  Whole days are serialized as an integer,
+//! and other times as a float.  Negative serials, and times before
+//! 1900-01-00, are rejected.
+//!
+//! Any [EpochTime](crate::epoch::EpochTime) is supported.  The time is taken
+//! to be in UTC, since spreadsheets have no time zones.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::excel_date")]
+//!     pub shipped: SystemTime,
+//! }
+//! ```
+//! The following will deserialize to `Outer`, shipped at 2023-07-16T12:00:00Z
+//! ```json
+//! {
+//!     "shipped": 45123.5
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+use super::civil;
+use super::epoch::{EpochTime, NANOS_PER_MILLI, NANOS_PER_SEC};
+
+const NANOS_PER_DAY: i128 = 86_400 * NANOS_PER_SEC;
+
+/// The serial of the nonexistent 1900-02-29.
+const LEAP_BUG_SERIAL: i64 = 60;
+
+/// Days from the Unix epoch to serial `0` for serials from `61` on.
+fn epoch_serial() -> i64 {
+    -civil::days_from_civil(1899, 12, 30)
+}
+
+fn to_nanos(serial: f64) -> Result<i128, String> {
+    if !serial.is_finite() || serial < 0.0 || serial >= i32::MAX as f64 {
+        return Err(format!("invalid Excel serial date {}", serial));
+    }
+    let whole = serial.trunc() as i64;
+    if whole == LEAP_BUG_SERIAL {
+        return Err("Excel serial date 60 is the nonexistent 1900-02-29".to_string());
+    }
+    let days = if whole < LEAP_BUG_SERIAL {
+        whole + 1
+    } else {
+        whole
+    } - epoch_serial();
+    let millis = ((serial - serial.trunc()) * 86_400_000.0).round() as i128;
+    Ok(days as i128 * NANOS_PER_DAY + millis * NANOS_PER_MILLI)
+}
+
+fn to_serial(nanos: i128) -> Result<(i64, i128), String> {
+    let days = i64::try_from(nanos.div_euclid(NANOS_PER_DAY))
+        .map_err(|_| "timestamp out of range".to_string())?;
+    let mut serial = days + epoch_serial();
+    if serial <= LEAP_BUG_SERIAL {
+        serial -= 1;
+    }
+    if serial < 0 {
+        return Err("times before 1900-01-00 can not be an Excel serial date".to_string());
+    }
+    Ok((serial, nanos.rem_euclid(NANOS_PER_DAY)))
+}
+
+pub fn serialize<S: Serializer, T: EpochTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let (serial, fraction) = to_serial(v.to_epoch_nanos()).map_err(serde::ser::Error::custom)?;
+    if fraction == 0 {
+        s.serialize_i64(serial)
+    } else {
+        s.serialize_f64(serial as f64 + fraction as f64 / NANOS_PER_DAY as f64)
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(d: D) -> Result<T, D::Error> {
+    let serial = f64::deserialize(d)?;
+    let nanos = to_nanos(serial).map_err(serde::de::Error::custom)?;
+    T::from_epoch_nanos(nanos).ok_or_else(|| serde::de::Error::custom("timestamp out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::excel_date")]
+        pub date: SystemTime,
+    }
+
+    fn time(secs: i64) -> SystemTime {
+        if secs >= 0 {
+            UNIX_EPOCH + Duration::from_secs(secs as u64)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            date: time(1_689_508_800),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"date":45123.5}"#);
+
+        let outer = Outer { date: UNIX_EPOCH };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"date":25569}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let result: Outer = serde_json::from_str(r#"{"date": 45123.5}"#).expect("Oops!");
+        assert_eq!(result.date, time(1_689_508_800));
+        let result: Outer = serde_json::from_str(r#"{"date": 25569}"#).expect("Oops!");
+        assert_eq!(result.date, UNIX_EPOCH);
+        let result: Outer = serde_json::from_str(r#"{"date": 0.25}"#).expect("Oops!");
+        assert_eq!(result.date, time(-2_209_075_200 + 6 * 3600));
+    }
+
+    #[test]
+    fn leap_year_bug() {
+        // 1900-02-28 is serial 59 and 1900-03-01 is serial 61
+        let feb_28 = time(-2_203_977_600);
+        let mar_1 = time(-2_203_891_200);
+        let result: Outer = serde_json::from_str(r#"{"date": 59}"#).expect("Oops!");
+        assert_eq!(result.date, feb_28);
+        let result: Outer = serde_json::from_str(r#"{"date": 61}"#).expect("Oops!");
+        assert_eq!(result.date, mar_1);
+        assert!(serde_json::from_str::<Outer>(r#"{"date": 60}"#).is_err());
+
+        let result = serde_json::to_string(&Outer { date: feb_28 }).expect("Oops!");
+        assert_eq!(result, r#"{"date":59}"#);
+        let result = serde_json::to_string(&Outer { date: mar_1 }).expect("Oops!");
+        assert_eq!(result, r#"{"date":61}"#);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        assert!(serde_json::from_str::<Outer>(r#"{"date": -1}"#).is_err());
+        assert!(serde_json::from_str::<Outer>(r#"{"date": "45123"}"#).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_crate() {
+        use time::macros::datetime;
+        use time::OffsetDateTime;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Time {
+            #[serde(with = "crate::excel_date")]
+            pub date: OffsetDateTime,
+        }
+
+        let result: Time = serde_json::from_str(r#"{"date": 45123.75}"#).expect("Oops!");
+        assert_eq!(result.date, datetime!(2023-07-16 18:00 UTC));
+    }
+}
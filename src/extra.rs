@@ -0,0 +1,166 @@
+//! Capture unknown fields with `#[serde(flatten)]`, and write them back
+//!
+//! Forward compatible models need to keep the fields they don't know
+//! about, such as vendor extensions, and write them back unchanged.
+//! [Extra] collects every field that the struct doesn't declare, in order,
+//! when used with `#[serde(flatten)]`:
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::extra::Extra;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Operation {
+//!     pub id: String,
+//!     #[serde(flatten)]
+//!     pub extra: Extra,
+//! }
+//!
+//! let json = r#"{"id":"list","x-rate-limit":100,"x-owner":{"team":"core"}}"#;
+//! let op: Operation = serde_json::from_str(json).unwrap();
+//! let limit: u32 = op.extra.get_as("x-rate-limit").unwrap().unwrap();
+//! assert_eq!(limit, 100);
+//! assert_eq!(serde_json::to_string(&op).unwrap(), json);
+//! ```
+//!
+//! Values are held as [Content], so `Extra` works with any self describing
+//! format.  Since `flatten` buffers the whole struct, it doesn't work with
+//! formats that aren't self describing, such as bincode.
+
+use serde::de::{self, DeserializeOwned, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+pub use super::content::Content;
+
+/// The fields of a struct that it doesn't declare, in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Extra(Vec<(String, Content)>);
+
+impl Extra {
+    pub fn new() -> Self {
+        Extra::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Content> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Deserialize the field `key` as a `T`, if it is present.
+    pub fn get_as<T: DeserializeOwned>(&self, key: &str) -> Option<Result<T, de::value::Error>> {
+        self.get(key).map(|v| v.clone().deserialize_into())
+    }
+
+    /// Set the field `key`, replacing and returning any previous value.
+    pub fn insert(&mut self, key: impl Into<String>, value: Content) -> Option<Content> {
+        let key = key.into();
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => Some(std::mem::replace(v, value)),
+            None => {
+                self.0.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Content> {
+        let i = self.0.iter().position(|(k, _)| k == key)?;
+        Some(self.0.remove(i).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Content)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+impl Serialize for Extra {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+impl<'de> Deserialize<'de> for Extra {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        struct ExtraVisitor;
+
+        impl<'de> Visitor<'de> for ExtraVisitor {
+            type Value = Extra;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map with string keys")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Extra, A::Error> {
+                let mut extra = Extra::new();
+                while let Some((key, value)) = map.next_entry::<String, Content>()? {
+                    extra.insert(key, value);
+                }
+                Ok(extra)
+            }
+        }
+
+        d.deserialize_map(ExtraVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    use super::{Content, Extra};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Operation {
+        pub id: String,
+        pub deprecated: Option<bool>,
+        #[serde(flatten)]
+        pub extra: Extra,
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{"id":"list","deprecated":true,"x-b":[1,-2,3.5],"x-a":{"n":null,"s":"t"}}"#;
+        let result: Operation = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.id, "list");
+        assert_eq!(result.extra.len(), 2);
+        assert_eq!(
+            result.extra.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            ["x-b", "x-a"]
+        );
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+
+    #[test]
+    fn access() {
+        let json = r#"{"id":"list","x-limit":100}"#;
+        let mut result: Operation = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.extra.get("x-limit"), Some(&Content::U64(100)));
+        let limit: u32 = result.extra.get_as("x-limit").unwrap().expect("Oops!");
+        assert_eq!(limit, 100);
+        assert!(result.extra.get_as::<bool>("x-limit").unwrap().is_err());
+        assert!(result.extra.get_as::<u32>("x-missing").is_none());
+
+        let owner = Content::from_value(&["core", "infra"]).expect("Oops!");
+        assert_eq!(result.extra.insert("x-owner", owner), None);
+        assert_eq!(result.extra.remove("x-limit"), Some(Content::U64(100)));
+        assert_eq!(
+            serde_json::to_string(&result).expect("Oops!"),
+            r#"{"id":"list","deprecated":null,"x-owner":["core","infra"]}"#
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let result: Operation = serde_json::from_str(r#"{"id":"x"}"#).expect("Oops!");
+        assert!(result.extra.is_empty());
+    }
+}
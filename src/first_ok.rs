@@ -0,0 +1,195 @@
+//! Try several modules in turn, and keep the first that deserializes
+//!
+//! Feeds mix formats for the same field, such as RFC 3339 from one source
+//! and epoch seconds from another.  The [first_ok!](crate::first_ok!) macro
+//! generates a module that tries each given module in order, and keeps the
+//! first result.  If none match, the error names every module with its
+//! error:
+//!
+//! ```text
+//! no interpretation matched; crate::rfc3339: ...; crate::http_date: ...
+//! ```
+//!
+//! The arguments are the visibility and name of the module to declare, the
+//! field type, and the paths of the modules to try.  Serializing uses the
+//! first module, so it sets the written form:
+//!
+//! ```rust
+//! serde_stuff::first_ok!(
+//!     pub any_time,
+//!     std::time::SystemTime,
+//!     serde_stuff::rfc3339,
+//!     serde_stuff::http_date,
+//!     serde_stuff::timestamp_secs
+//! );
+//! ```
+//!
+//! Since each module replays the value from [Content], the format must be
+//! self describing.  [deserialize_with] is the same thing as a function,
+//! for lists built at runtime.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! serde_stuff::first_ok!(
+//!     any_time,
+//!     std::time::SystemTime,
+//!     serde_stuff::rfc3339,
+//!     serde_stuff::http_date,
+//!     serde_stuff::timestamp_secs
+//! );
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "any_time")]
+//!     pub created: SystemTime,
+//!     #[serde(with = "any_time")]
+//!     pub modified: SystemTime,
+//!     #[serde(with = "any_time")]
+//!     pub expires: SystemTime,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "created": "2023-11-14T22:13:20Z",
+//!     "modified": "Tue, 14 Nov 2023 22:13:20 GMT",
+//!     "expires": 1700000000
+//! }
+//! ```
+
+use serde::de::{self, value};
+use serde::{Deserialize, Deserializer};
+
+use super::content::{Content, ContentDeserializer};
+
+/// One way to read a value, from a replay of the buffered input.
+pub type Attempt<T> = fn(ContentDeserializer<value::Error>) -> Result<T, value::Error>;
+
+/// Try each named attempt in order, returning the first success, or an
+/// error naming every attempt.
+pub fn deserialize_with<'de, D, T>(d: D, attempts: &[(&str, Attempt<T>)]) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let content = Content::deserialize(d)?;
+    let mut errors = Vec::with_capacity(attempts.len());
+    for (name, attempt) in attempts {
+        match attempt(ContentDeserializer::new(content.clone())) {
+            Ok(value) => return Ok(value),
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+        }
+    }
+    Err(de::Error::custom(format!(
+        "no interpretation matched; {}",
+        errors.join("; ")
+    )))
+}
+
+/// Generate a serde `with` module that tries modules in order.
+#[macro_export]
+macro_rules! first_ok {
+    (
+        $vis:vis $name:ident,
+        $ty:ty,
+        $($first:ident)::+
+        $(, $($rest:ident)::+)* $(,)?
+    ) => {
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[allow(dead_code)]
+            pub fn serialize<S: serde::Serializer>(v: &$ty, s: S) -> Result<S::Ok, S::Error> {
+                $($first)::+::serialize(v, s)
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D: serde::Deserializer<'de>>(d: D) -> Result<$ty, D::Error> {
+                $crate::first_ok::deserialize_with::<D, $ty>(
+                    d,
+                    &[
+                        (
+                            [$(stringify!($first)),+].join("::").as_str(),
+                            |d| $($first)::+::deserialize(d),
+                        ),
+                        $((
+                            [$(stringify!($rest)),+].join("::").as_str(),
+                            |d| $($rest)::+::deserialize(d),
+                        ),)*
+                    ],
+                )
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    crate::first_ok!(
+        any_time,
+        SystemTime,
+        crate::rfc3339,
+        crate::http_date,
+        crate::timestamp_secs,
+    );
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "any_time")]
+        pub created: SystemTime,
+        #[serde(with = "any_time")]
+        pub modified: SystemTime,
+        #[serde(with = "any_time")]
+        pub expires: SystemTime,
+    }
+
+    fn outer() -> Outer {
+        let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        Outer {
+            created: time,
+            modified: time,
+            expires: time,
+        }
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "created": "2023-11-14T22:13:20Z",
+            "modified": "Tue, 14 Nov 2023 22:13:20 GMT",
+            "expires": 1700000000
+        }"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer(), &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let result = serde_json::to_string(&outer()).expect("Oops!");
+        let time = "2023-11-14T22:13:20Z";
+        assert_eq!(
+            result,
+            format!(r#"{{"created":"{time}","modified":"{time}","expires":"{time}"}}"#)
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"created": "yesterday", "modified": 0, "expires": 0}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        for msg in [
+            "no interpretation matched; crate::rfc3339: ",
+            "; crate::http_date: ",
+            "; crate::timestamp_secs: ",
+        ] {
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Serialize and Deserialize `bitflags` values by flag name
+//!
+//! Permission masks in configs read better as `["READ", "WRITE"]` or
+//! `"READ|WRITE"` than as `3`.  This module writes the names of the set
+//! flags as a list, or joined with `|` using `flag_names::pipe`.  Either
+//! form is read back, and the string form may also separate names with `,`.
+//! An unknown name is an error, as are set bits without a name when
+//! serializing.
+//!
+//! Requires the `bitflags` feature, and works with any type implementing
+//! `bitflags::Flags`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! bitflags::bitflags! {
+//!     #[derive(Debug)]
+//!     pub struct Perms: u8 {
+//!         const READ = 1;
+//!         const WRITE = 2;
+//!         const EXEC = 4;
+//!     }
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::flag_names")]
+//!     pub owner: Perms,
+//!     #[serde(with = "serde_stuff::flag_names::pipe")]
+//!     pub group: Perms,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "owner": ["READ", "WRITE"],
+//!     "group": "READ|EXEC"
+//! }
+//! ```
+
+use bitflags::Flags;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// The names of the flags set in `v`.
+fn names<T: Flags>(v: &T) -> Result<Vec<&'static str>, String> {
+    let mut iter = v.iter_names();
+    let names = iter.by_ref().map(|(name, _)| name).collect();
+    match iter.remaining().is_empty() {
+        true => Ok(names),
+        false => Err("flags value has bits without a name".to_string()),
+    }
+}
+
+fn insert<T: Flags>(flags: &mut T, name: &str) -> Result<(), String> {
+    let flag = T::from_name(name).ok_or_else(|| format!("unknown flag \"{}\"", name))?;
+    flags.insert(flag);
+    Ok(())
+}
+
+struct FlagNames<T>(PhantomData<T>);
+
+impl<'de, T: Flags> Visitor<'de> for FlagNames<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of flag names, or a string of names separated by |")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        let mut flags = T::empty();
+        for name in value.split(['|', ',']).map(str::trim) {
+            if !name.is_empty() {
+                insert(&mut flags, name).map_err(E::custom)?;
+            }
+        }
+        Ok(flags)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<T, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut flags = T::empty();
+        while let Some(name) = seq.next_element::<String>()? {
+            insert(&mut flags, &name).map_err(de::Error::custom)?;
+        }
+        Ok(flags)
+    }
+}
+
+pub fn serialize<S: Serializer, T: Flags>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(names(v).map_err(serde::ser::Error::custom)?)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: Flags>(d: D) -> Result<T, D::Error> {
+    d.deserialize_any(FlagNames(PhantomData))
+}
+
+/// Serialize as a string of names separated by `|`.
+pub mod pipe {
+    use bitflags::Flags;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, T: Flags>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        let names = super::names(v).map_err(serde::ser::Error::custom)?;
+        s.serialize_str(&names.join("|"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Flags>(d: D) -> Result<T, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    bitflags::bitflags! {
+        #[derive(Debug, PartialEq)]
+        pub struct Perms: u8 {
+            const READ = 1;
+            const WRITE = 2;
+            const EXEC = 4;
+        }
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::flag_names")]
+        pub owner: Perms,
+        #[serde(with = "crate::flag_names::pipe")]
+        pub group: Perms,
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            owner: Perms::READ | Perms::WRITE,
+            group: Perms::READ | Perms::EXEC,
+        };
+        for json in [
+            r#"{"owner": ["READ", "WRITE"], "group": "READ|EXEC"}"#,
+            r#"{"owner": "WRITE, READ", "group": ["EXEC", "READ", "EXEC"]}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(&outer, &result);
+        }
+
+        let json = r#"{"owner": [], "group": ""}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert!(result.owner.is_empty() && result.group.is_empty());
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            owner: Perms::READ | Perms::WRITE,
+            group: Perms::READ | Perms::EXEC,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"owner":["READ","WRITE"],"group":"READ|EXEC"}"#);
+    }
+
+    #[test]
+    fn invalid() {
+        let json = r#"{"owner": ["READ", "SUDO"], "group": ""}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("unknown flag \"SUDO\""), "{}", err);
+
+        let outer = Outer {
+            owner: Perms::from_bits_retain(8),
+            group: Perms::empty(),
+        };
+        let err = serde_json::to_string(&outer).unwrap_err().to_string();
+        assert!(err.contains("bits without a name"), "{}", err);
+    }
+}
@@ -0,0 +1,230 @@
+//! Serialize and Deserialize hex color strings such as `"#ff8800"`
+//!
+//! The accepted forms depend on whether the target has an alpha channel:
+//!
+//! | Target                 | Accepted                             | Serialized as |
+//! |------------------------|--------------------------------------|---------------|
+//! | [Rgb], `[u8; 3]`       | `"#rgb"`, `"#rrggbb"`                | `"#rrggbb"`   |
+//! | [Rgba], `[u8; 4]`      | `"#rgb"`, `"#rgba"`, `"#rrggbb"`, `"#rrggbbaa"` | `"#rrggbbaa"` |
+//!
+//! Short forms repeat each digit, so `"#f80"` is `"#ff8800"`.  A color
+//! without alpha is opaque.  Digits may be in either case, and are always
+//! written in lower case.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::hex_color::Rgba;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::hex_color")]
+//!     pub accent: [u8; 3],
+//!     #[serde(with = "serde_stuff::hex_color")]
+//!     pub overlay: Rgba,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "accent": "#F80",
+//!     "overlay": "#00000080"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+/// An opaque color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// A color with an alpha channel, where `255` is opaque.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// Colors that can be built from, and split into, RGBA channels.
+pub trait HexColor: Sized {
+    /// Whether the alpha channel is kept, and written.
+    const HAS_ALPHA: bool;
+
+    fn from_rgba(rgba: [u8; 4]) -> Self;
+    fn to_rgba(&self) -> [u8; 4];
+}
+
+impl HexColor for Rgb {
+    const HAS_ALPHA: bool = false;
+
+    fn from_rgba([r, g, b, _]: [u8; 4]) -> Self {
+        Rgb { r, g, b }
+    }
+
+    fn to_rgba(&self) -> [u8; 4] {
+        [self.r, self.g, self.b, 255]
+    }
+}
+
+impl HexColor for Rgba {
+    const HAS_ALPHA: bool = true;
+
+    fn from_rgba([r, g, b, a]: [u8; 4]) -> Self {
+        Rgba { r, g, b, a }
+    }
+
+    fn to_rgba(&self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+impl HexColor for [u8; 3] {
+    const HAS_ALPHA: bool = false;
+
+    fn from_rgba([r, g, b, _]: [u8; 4]) -> Self {
+        [r, g, b]
+    }
+
+    fn to_rgba(&self) -> [u8; 4] {
+        [self[0], self[1], self[2], 255]
+    }
+}
+
+impl HexColor for [u8; 4] {
+    const HAS_ALPHA: bool = true;
+
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        rgba
+    }
+
+    fn to_rgba(&self) -> [u8; 4] {
+        *self
+    }
+}
+
+fn parse<T: HexColor>(value: &str) -> Result<T, String> {
+    let bad = || format!("invalid hex color \"{}\"", value);
+    let digits = value.strip_prefix('#').ok_or_else(bad)?;
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(bad());
+    }
+    let nibble = |i: usize| u8::from_str_radix(&digits[i..i + 1], 16).unwrap() * 0x11;
+    let byte = |i: usize| u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).unwrap();
+    let rgba = match (digits.len(), T::HAS_ALPHA) {
+        (3, _) => [nibble(0), nibble(1), nibble(2), 255],
+        (4, true) => [nibble(0), nibble(1), nibble(2), nibble(3)],
+        (6, _) => [byte(0), byte(1), byte(2), 255],
+        (8, true) => [byte(0), byte(1), byte(2), byte(3)],
+        _ => return Err(bad()),
+    };
+    Ok(T::from_rgba(rgba))
+}
+
+fn format<T: HexColor>(v: &T) -> String {
+    let [r, g, b, a] = v.to_rgba();
+    if T::HAS_ALPHA {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    } else {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+pub fn serialize<S: Serializer, T: HexColor>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    String::serialize(&format(v), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: HexColor>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    parse(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    use super::{Rgb, Rgba};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::hex_color")]
+        pub accent: Rgb,
+        #[serde(with = "crate::hex_color")]
+        pub overlay: Rgba,
+        #[serde(with = "crate::hex_color")]
+        pub raw: [u8; 3],
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r##"{"accent": "#F80", "overlay": "#0008", "raw": "#102030"}"##;
+        let outer = Outer {
+            accent: Rgb {
+                r: 0xff,
+                g: 0x88,
+                b: 0x00,
+            },
+            overlay: Rgba {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0x88,
+            },
+            raw: [0x10, 0x20, 0x30],
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+
+        let json = r##"{"accent": "#ff8800", "overlay": "#abc", "raw": "#102030"}"##;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.overlay.a, 255);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            accent: Rgb {
+                r: 0xff,
+                g: 0x88,
+                b: 0x00,
+            },
+            overlay: Rgba {
+                r: 0xAB,
+                g: 0,
+                b: 0,
+                a: 0x80,
+            },
+            raw: [1, 2, 3],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r##"{"accent":"#ff8800","overlay":"#ab000080","raw":"#010203"}"##
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (accent, overlay) in [
+            ("ff8800", "#000"),
+            ("#ff880080", "#000"),
+            ("#ff88", "#000"),
+            ("#ff88zz", "#000"),
+            ("#fff", "#00000"),
+        ] {
+            let json = format!(
+                r##"{{"accent": "{}", "overlay": "{}", "raw": "#000"}}"##,
+                accent, overlay
+            );
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", json);
+        }
+    }
+}
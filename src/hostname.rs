@@ -0,0 +1,154 @@
+//! Deserialize an RFC 1123 hostname, such as `"api.example.com"`
+//!
+//! Hostnames are at most 253 characters, made of dot separated labels of
+//! 1 to 63 letters, digits and inner hyphens.  A single trailing dot, as in
+//! a fully qualified name, is removed.  Hostnames are case insensitive, and
+//! are stored in lower case.
+//!
+//! - `hostname` accepts IP addresses, since `"10.0.0.1"` is also a valid
+//!   RFC 1123 name.
+//! - `hostname::not_ip` rejects IPv4 addresses, for fields that must name a
+//!   host rather than an address.
+//!
+//! With the `idna` feature, internationalized names such as `"bücher.de"`
+//! are converted to their ASCII (punycode) form, `"xn--bcher-kva.de"`, before
+//! they are validated.  Without it, they are rejected.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::hostname::not_ip")]
+//!     pub service: String,
+//! }
+//! ```
+//! The following will deserialize to `Outer { service: "api.example.com".to_string() }`
+//! ```json
+//! {
+//!     "service": "API.Example.com."
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::net::Ipv4Addr;
+
+/// Whether `label` is a valid RFC 1123 label.
+pub(crate) fn valid_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 63
+        && label
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+}
+
+#[cfg(feature = "idna")]
+fn to_ascii(value: &str) -> Result<String, String> {
+    idna::domain_to_ascii(value).map_err(|e| format!("invalid hostname \"{}\": {}", value, e))
+}
+
+#[cfg(not(feature = "idna"))]
+fn to_ascii(value: &str) -> Result<String, String> {
+    Ok(value.to_ascii_lowercase())
+}
+
+/// Validate `value`, and return it in lower case ASCII.
+pub(crate) fn normalize(value: &str, allow_ip: bool) -> Result<String, String> {
+    let trimmed = value.trim();
+    let name = trimmed.strip_suffix('.').unwrap_or(trimmed);
+    let name = to_ascii(name)?;
+    if name.is_empty() || name.len() > 253 || !name.split('.').all(valid_label) {
+        return Err(format!("invalid hostname \"{}\"", value));
+    }
+    if !allow_ip && name.parse::<Ipv4Addr>().is_ok() {
+        return Err(format!(
+            "expected a hostname, found IP address \"{}\"",
+            value
+        ));
+    }
+    Ok(name)
+}
+
+/// Hostnames are serialized unchanged.
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    str::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let value = String::deserialize(d)?;
+    normalize(&value, true).map_err(serde::de::Error::custom)
+}
+
+/// Reject IP addresses.
+pub mod not_ip {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+        super::serialize(v, s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+        let value = String::deserialize(d)?;
+        super::normalize(&value, false).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::hostname")]
+        pub host: String,
+        #[serde(with = "crate::hostname::not_ip")]
+        pub service: String,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"host": "10.0.0.1", "service": "API.Example.com."}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.host, "10.0.0.1");
+        assert_eq!(result.service, "api.example.com");
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let long = format!("{}.com", "a".repeat(64));
+        for (host, service) in [
+            ("-bad.example.com", "ok"),
+            ("under_score.com", "ok"),
+            ("a..b", "ok"),
+            ("", "ok"),
+            (long.as_str(), "ok"),
+            ("ok", "10.0.0.1"),
+            ("ok", "."),
+        ] {
+            let json = format!(r#"{{"host": "{}", "service": "{}"}}"#, host, service);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", json);
+        }
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn idna() {
+        let json = r#"{"host": "Bücher.de", "service": "münchen.example"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.host, "xn--bcher-kva.de");
+        assert_eq!(result.service, "xn--mnchen-3ya.example");
+    }
+
+    #[cfg(not(feature = "idna"))]
+    #[test]
+    fn unicode_rejected() {
+        let json = r#"{"host": "bücher.de", "service": "ok"}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Serialize and Deserialize a time as an HTTP-date string
+//!
+//! HTTP headers such as `Last-Modified` and `Expires`, and many cache
+//! manifests, use the IMF-fixdate form, `"Sun, 06 Nov 1994 08:49:37 GMT"`.
+//! That is the only form written on serialize.  On deserialize, the obsolete
+//! RFC 850 (`"Sunday, 06-Nov-94 08:49:37 GMT"`) and asctime
+//! (`"Sun Nov  6 08:49:37 1994"`) forms are also accepted, as RFC 9110
+//! requires of recipients.  HTTP dates are always in UTC and have no
+//! fractional seconds, so any sub-second part is dropped.
+//!
+//! Any [EpochTime](crate::epoch::EpochTime) is supported.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::http_date")]
+//!     pub last_modified: SystemTime,
+//! }
+//! ```
+//! The following will all deserialize to the same `Outer`
+//! ```json
+//! {
+//!     "last_modified": "Sun, 06 Nov 1994 08:49:37 GMT"
+//! }
+//! ```
+//! ```json
+//! {
+//!     "last_modified": "Sunday, 06-Nov-94 08:49:37 GMT"
+//! }
+//! ```
+//! ```json
+//! {
+//!     "last_modified": "Sun Nov  6 08:49:37 1994"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+use super::civil;
+use super::epoch::EpochTime;
+
+pub fn serialize<S: Serializer, T: EpochTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let value = civil::format_http_date(v.to_epoch_nanos()).map_err(serde::ser::Error::custom)?;
+    String::serialize(&value, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    let parsed = civil::parse_http_date(&value).map_err(serde::de::Error::custom)?;
+    T::from_epoch_nanos(parsed.epoch_nanos())
+        .ok_or_else(|| serde::de::Error::custom(format!("date \"{}\" is out of range", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::http_date")]
+        pub date: SystemTime,
+    }
+
+    fn time() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(784_111_777)
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            date: time() + Duration::from_millis(250),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"date":"Sun, 06 Nov 1994 08:49:37 GMT"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        for value in [
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+        ] {
+            let json = format!(r#"{{"date": "{}"}}"#, value);
+            let result: Outer = serde_json::from_str(&json).expect(value);
+            assert_eq!(result.date, time(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for value in [
+            "Sun, 06 Nov 1994 08:49:37 +0000",
+            "Sun, 06 Nov 1994 08:49 GMT",
+            "Sun, 31 Nov 1994 08:49:37 GMT",
+            "Someday, 06-Nov-94 08:49:37 GMT",
+            "1994-11-06T08:49:37Z",
+        ] {
+            let json = format!(r#"{{"date": "{}"}}"#, value);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", value);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{DateTime, Utc};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Chrono {
+            #[serde(with = "crate::http_date")]
+            pub date: DateTime<Utc>,
+        }
+
+        let json = r#"{"date":"Sun, 06 Nov 1994 08:49:37 GMT"}"#;
+        let result: Chrono = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.date.timestamp(), 784_111_777);
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+}
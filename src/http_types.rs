@@ -0,0 +1,301 @@
+//! Serialize and Deserialize the `http` crate's request and response types
+//!
+//! Structs that describe HTTP requests in configs or test fixtures want the
+//! typed values, so that a bad method or header name is caught when the
+//! file is read.  Each type has its own submodule:
+//!
+//! | Module         | Type          | As                          |
+//! |----------------|---------------|-----------------------------|
+//! | `header_name`  | `HeaderName`  | `"content-type"`            |
+//! | `header_value` | `HeaderValue` | `"application/json"`        |
+//! | `method`       | `Method`      | `"GET"`                     |
+//! | `status_code`  | `StatusCode`  | `404`                       |
+//! | `uri`          | `Uri`         | `"https://example.com/api"` |
+//!
+//! Header names are case insensitive, and are stored in lower case.
+//! Methods are case sensitive, and any valid token is accepted as an
+//! extension method.  Header values that aren't UTF-8 are serialized as
+//! bytes.
+//!
+//! Requires the `http` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use http::{HeaderName, Method, StatusCode, Uri};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::http_types::method")]
+//!     pub method: Method,
+//!     #[serde(with = "serde_stuff::http_types::uri")]
+//!     pub uri: Uri,
+//!     #[serde(with = "serde_stuff::http_types::header_name")]
+//!     pub header: HeaderName,
+//!     #[serde(with = "serde_stuff::http_types::status_code")]
+//!     pub expect: StatusCode,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "method": "POST",
+//!     "uri": "https://example.com/api/users",
+//!     "header": "X-Request-Id",
+//!     "expect": 201
+//! }
+//! ```
+
+/// Serialize and Deserialize an `http::HeaderName`
+pub mod header_name {
+    use http::HeaderName;
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &HeaderName, s: S) -> Result<S::Ok, S::Error> {
+        str::serialize(v.as_str(), s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<HeaderName, D::Error> {
+        let value = String::deserialize(d)?;
+        HeaderName::from_bytes(value.as_bytes()).map_err(|e| {
+            serde::de::Error::custom(format!("invalid header name \"{}\": {}", value, e))
+        })
+    }
+}
+
+/// Serialize and Deserialize an `http::HeaderValue`
+///
+/// Values that aren't UTF-8 are written as bytes, so any value that can be
+/// deserialized also serializes back unchanged.
+pub mod header_value {
+    use std::fmt;
+
+    use http::HeaderValue;
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &HeaderValue, s: S) -> Result<S::Ok, S::Error> {
+        match std::str::from_utf8(v.as_bytes()) {
+            Ok(value) => s.serialize_str(value),
+            Err(_) => s.serialize_bytes(v.as_bytes()),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<HeaderValue, D::Error> {
+        struct HeaderValueVisitor;
+
+        impl<'de> Visitor<'de> for HeaderValueVisitor {
+            type Value = HeaderValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a header value string or bytes")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<HeaderValue, E>
+            where
+                E: de::Error,
+            {
+                HeaderValue::from_str(value)
+                    .map_err(|e| E::custom(format!("invalid header value \"{}\": {}", value, e)))
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<HeaderValue, E>
+            where
+                E: de::Error,
+            {
+                HeaderValue::from_bytes(value).map_err(|e| {
+                    E::custom(format!(
+                        "invalid header value \"{}\": {}",
+                        value.escape_ascii(),
+                        e
+                    ))
+                })
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<HeaderValue, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                self.visit_bytes(&bytes)
+            }
+        }
+
+        // Self-describing formats may hold either a string or bytes.
+        if d.is_human_readable() {
+            d.deserialize_any(HeaderValueVisitor)
+        } else {
+            d.deserialize_bytes(HeaderValueVisitor)
+        }
+    }
+}
+
+/// Serialize and Deserialize an `http::Method`
+pub mod method {
+    use http::Method;
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &Method, s: S) -> Result<S::Ok, S::Error> {
+        str::serialize(v.as_str(), s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Method, D::Error> {
+        let value = String::deserialize(d)?;
+        Method::from_bytes(value.as_bytes())
+            .map_err(|e| serde::de::Error::custom(format!("invalid method \"{}\": {}", value, e)))
+    }
+}
+
+/// Serialize and Deserialize an `http::StatusCode` as a number
+pub mod status_code {
+    use http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &StatusCode, s: S) -> Result<S::Ok, S::Error> {
+        u16::serialize(&v.as_u16(), s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<StatusCode, D::Error> {
+        let value = u16::deserialize(d)?;
+        StatusCode::from_u16(value)
+            .map_err(|e| serde::de::Error::custom(format!("invalid status code {}: {}", value, e)))
+    }
+}
+
+/// Serialize and Deserialize an `http::Uri`
+pub mod uri {
+    use http::Uri;
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &Uri, s: S) -> Result<S::Ok, S::Error> {
+        String::serialize(&v.to_string(), s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Uri, D::Error> {
+        let value = String::deserialize(d)?;
+        value
+            .parse()
+            .map_err(|e| serde::de::Error::custom(format!("invalid URI \"{}\": {}", value, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderName, HeaderValue, Method, StatusCode, Uri};
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::http_types::method")]
+        pub method: Method,
+        #[serde(with = "crate::http_types::uri")]
+        pub uri: Uri,
+        #[serde(with = "crate::http_types::header_name")]
+        pub name: HeaderName,
+        #[serde(with = "crate::http_types::header_value")]
+        pub value: HeaderValue,
+        #[serde(with = "crate::http_types::status_code")]
+        pub status: StatusCode,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "method": "PATCH",
+            "uri": "https://example.com/api/users?page=2",
+            "name": "Content-Type",
+            "value": "application/json",
+            "status": 204
+        }"#;
+        let outer = Outer {
+            method: Method::PATCH,
+            uri: Uri::from_static("https://example.com/api/users?page=2"),
+            name: http::header::CONTENT_TYPE,
+            value: HeaderValue::from_static("application/json"),
+            status: StatusCode::NO_CONTENT,
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            method: Method::from_bytes(b"PURGE").expect("Oops!"),
+            uri: Uri::from_static("/health"),
+            name: HeaderName::from_static("x-request-id"),
+            value: HeaderValue::from_static("abc-123"),
+            status: StatusCode::IM_A_TEAPOT,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"method":"PURGE","uri":"/health","name":"x-request-id","value":"abc-123","status":418}"#
+        );
+    }
+
+    #[test]
+    fn opaque_value_round_trip() {
+        let outer = Outer {
+            method: Method::GET,
+            uri: Uri::from_static("/"),
+            name: http::header::ETAG,
+            value: HeaderValue::from_bytes(b"caf\xe9").expect("Oops!"),
+            status: StatusCode::OK,
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert!(json.contains(r#""value":[99,97,102,233]"#), "{}", json);
+        let result: Outer = serde_json::from_str(&json).expect("Oops!");
+        assert_eq!(result, outer);
+
+        let bytes = bincode::serialize(&outer).expect("Oops!");
+        let result: Outer = bincode::deserialize(&bytes).expect("Oops!");
+        assert_eq!(result, outer);
+
+        let outer = Outer {
+            value: HeaderValue::from_str("café").expect("Oops!"),
+            ..outer
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert!(json.contains(r#""value":"café""#), "{}", json);
+        let result: Outer = serde_json::from_str(&json).expect("Oops!");
+        assert_eq!(result, outer);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let valid = [
+            ("method", "\"GET\""),
+            ("uri", "\"/\""),
+            ("name", "\"etag\""),
+            ("value", "\"x\""),
+            ("status", "200"),
+        ];
+        for (field, bad, msg) in [
+            ("method", "\"GE T\"", "invalid method \"GE T\""),
+            ("uri", "\"http://\"", "invalid URI \"http://\""),
+            ("name", "\"bad name\"", "invalid header name \"bad name\""),
+            ("value", "\"a\\nb\"", "invalid header value \"a\nb\""),
+            ("status", "1000", "invalid status code 1000"),
+        ] {
+            let fields: Vec<String> = valid
+                .iter()
+                .map(|(k, v)| format!("\"{}\": {}", k, if *k == field { bad } else { v }))
+                .collect();
+            let json = format!("{{{}}}", fields.join(", "));
+            let err = serde_json::from_str::<Outer>(&json)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Serialize and Deserialize a `Duration` as a compact human string
+//!
+//! Strings are a sequence of whole numbers, each followed by a unit, such as
+//! `"2h"`, `"5m30s"` or `"1d 12h"`.  Whitespace between components is
+//! ignored.  The supported units are:
+//!
+//! | Unit        | Suffix       |
+//! |-------------|--------------|
+//! | days        | `d`          |
+//! | hours       | `h`          |
+//! | minutes     | `m`          |
+//! | seconds     | `s`          |
+//! | millis      | `ms`         |
+//! | micros      | `us` or `µs` |
+//! | nanos       | `ns`         |
+//!
+//! Durations are serialized in the same compact form, largest unit first,
+//! with zero components left out.  A zero duration is `"0s"`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::human_duration")]
+//!     pub timeout: Duration,
+//! }
+//! ```
+//! The following will deserialize to `Outer { timeout: Duration::from_secs(330) }`
+//! ```json
+//! {
+//!     "timeout": "5m30s"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::time::Duration;
+
+const UNITS: [(&str, u128); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+fn unit_nanos(unit: &str) -> Option<u128> {
+    let unit = if unit == "µs" { "us" } else { unit };
+    UNITS.iter().find(|(u, _)| *u == unit).map(|(_, n)| *n)
+}
+
+pub(crate) fn parse(value: &str) -> Result<Duration, String> {
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return Err("expected a duration such as \"5m30s\", found an empty string".to_string());
+    }
+    let mut nanos: u128 = 0;
+    while !rest.is_empty() {
+        let digits = rest.bytes().take_while(|b| b.is_ascii_digit()).count();
+        let number = &rest[..digits];
+        rest = &rest[digits..];
+        let letters = rest
+            .char_indices()
+            .find(|(_, c)| !c.is_alphabetic())
+            .map_or(rest.len(), |(idx, _)| idx);
+        let unit = &rest[..letters];
+        rest = rest[letters..].trim_start();
+        if number.is_empty() || unit.is_empty() {
+            return Err(format!(
+                "expected a duration such as \"5m30s\", found \"{}\"",
+                value
+            ));
+        }
+        let unit = unit_nanos(unit)
+            .ok_or_else(|| format!("unknown duration unit \"{}\" in \"{}\"", unit, value))?;
+        nanos = number
+            .parse::<u128>()
+            .ok()
+            .and_then(|n| n.checked_mul(unit))
+            .and_then(|n| n.checked_add(nanos))
+            .ok_or_else(|| format!("duration \"{}\" is too large", value))?;
+    }
+    let secs = u64::try_from(nanos / 1_000_000_000)
+        .map_err(|_| format!("duration \"{}\" is too large", value))?;
+    Ok(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+pub(crate) fn format(v: &Duration) -> String {
+    let mut nanos = v.as_nanos();
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    let mut out = String::new();
+    for (unit, size) in UNITS {
+        if nanos >= size {
+            out.push_str(&format!("{}{}", nanos / size, unit));
+            nanos %= size;
+        }
+    }
+    out
+}
+
+pub fn serialize<S: Serializer>(v: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    String::serialize(&format(v), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+    let value = String::deserialize(d)?;
+    parse(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::human_duration")]
+        pub timeout: Duration,
+    }
+
+    #[test]
+    fn serialize() {
+        for (duration, expected) in [
+            (Duration::from_secs(330), "5m30s"),
+            (Duration::from_secs(7200), "2h"),
+            (Duration::from_millis(150), "150ms"),
+            (Duration::new(90_061, 1_001), "1d1h1m1s1us1ns"),
+            (Duration::ZERO, "0s"),
+        ] {
+            let outer = Outer { timeout: duration };
+            let result = serde_json::to_string(&outer).expect("Oops!");
+            assert_eq!(result, format!(r#"{{"timeout":"{}"}}"#, expected));
+        }
+    }
+
+    #[test]
+    fn deserialize() {
+        for (value, expected) in [
+            ("5m30s", Duration::from_secs(330)),
+            ("2h", Duration::from_secs(7200)),
+            ("150ms", Duration::from_millis(150)),
+            ("1d 12h", Duration::from_secs(129_600)),
+            ("10µs", Duration::from_micros(10)),
+        ] {
+            let json = format!(r#"{{"timeout": "{}"}}"#, value);
+            let result: Outer = serde_json::from_str(&json).expect("Oops!");
+            assert_eq!(result.timeout, expected);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for value in ["", "5", "m", "5x", "1.5h", "-5s"] {
+            let json = format!(r#"{{"timeout": "{}"}}"#, value);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", value);
+        }
+    }
+}
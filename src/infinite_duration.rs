@@ -0,0 +1,151 @@
+//! Serialize and Deserialize an `Option<Duration>` where `None` means forever
+//!
+//! Server timeouts and retention settings commonly use a keyword for "no
+//! limit".  On deserialize, the strings `"infinite"`, `"unlimited"` and
+//! `"never"` (in any case) are `None`.  Anything else is `Some`, and can be
+//! either a number of seconds, as in [duration_secs](crate::duration_secs),
+//! or a compact string, as in [human_duration](crate::human_duration).
+//!
+//! On serialize, `None` is written as `"infinite"`, and `Some` as a compact
+//! human string.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::infinite_duration")]
+//!     pub timeout: Option<Duration>,
+//!     #[serde(with = "serde_stuff::infinite_duration")]
+//!     pub retention: Option<Duration>,
+//! }
+//! ```
+//! The following will deserialize to
+//! `Outer { timeout: Some(Duration::from_secs(30)), retention: None }`
+//! ```json
+//! {
+//!     "timeout": 30,
+//!     "retention": "never"
+//! }
+//! ```
+
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::time::Duration;
+
+use super::duration_secs::DurationSecs;
+use super::human_duration;
+
+/// The strings read as an unlimited duration.
+pub const KEYWORDS: [&str; 3] = ["infinite", "unlimited", "never"];
+
+pub fn serialize<S: Serializer>(v: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        Some(v) => human_duration::serialize(v, s),
+        None => s.serialize_str(KEYWORDS[0]),
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+    struct InfiniteDuration;
+
+    impl<'de> Visitor<'de> for InfiniteDuration {
+        type Value = Option<Duration>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a duration, or one of \"infinite\", \"unlimited\" or \"never\"")
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            DurationSecs.visit_u64(value).map(Some)
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            DurationSecs.visit_i64(value).map(Some)
+        }
+
+        fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            DurationSecs.visit_f64(value).map(Some)
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            if KEYWORDS
+                .iter()
+                .any(|k| k.eq_ignore_ascii_case(value.trim()))
+            {
+                return Ok(None);
+            }
+            human_duration::parse(value).map(Some).map_err(E::custom)
+        }
+    }
+
+    d.deserialize_any(InfiniteDuration)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::infinite_duration")]
+        pub timeout: Option<Duration>,
+        #[serde(with = "crate::infinite_duration")]
+        pub retention: Option<Duration>,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            timeout: Some(Duration::from_secs(90)),
+            retention: None,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"timeout":"1m30s","retention":"infinite"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            timeout: Some(Duration::from_millis(1500)),
+            retention: None,
+        };
+        for json in [
+            r#"{"timeout": 1.5, "retention": "infinite"}"#,
+            r#"{"timeout": "1s500ms", "retention": "Unlimited"}"#,
+            r#"{"timeout": "1s 500ms", "retention": "NEVER"}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect(json);
+            assert_eq!(&outer, &result, "{}", json);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for json in [
+            r#"{"timeout": -1, "retention": "never"}"#,
+            r#"{"timeout": "forever", "retention": "never"}"#,
+            r#"{"timeout": null, "retention": "never"}"#,
+        ] {
+            assert!(serde_json::from_str::<Outer>(json).is_err(), "{}", json);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Serialize and Deserialize integer keyed maps with string keys
+//!
+//! JSON only allows string keys, so a `HashMap<u64, V>` or
+//! `BTreeMap<i32, V>` needs its keys written as `"42"`.  This module writes
+//! every key with `Display`, and parses it back with `FromStr`.  A key that
+//! doesn't parse is named in the error.  Keys that a format gives as
+//! numbers, such as in YAML, are accepted too.
+//!
+//! This is the [map_key_fromstr](crate::map_key_fromstr) conversion, so
+//! any key type with `Display` and `FromStr` works, such as `IpAddr` or
+//! `char`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::BTreeMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::int_keys")]
+//!     pub ports: BTreeMap<u16, String>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "ports": {"80": "http", "443": "https"}
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Display;
+use std::str::FromStr;
+
+pub fn serialize<S, M, K, V>(v: &M, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    for<'a> &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Display,
+    V: Serialize,
+{
+    super::map_key_fromstr::serialize(v, s)
+}
+
+pub fn deserialize<'de, D, M, K, V>(d: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: FromIterator<(K, V)>,
+    K: FromStr,
+    K::Err: Display,
+    V: Deserialize<'de>,
+{
+    super::map_key_fromstr::deserialize(d)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::int_keys")]
+        pub ports: BTreeMap<u16, String>,
+        #[serde(with = "crate::int_keys")]
+        pub offsets: HashMap<i32, u8>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"ports": {"443": "https", "80": "http"}, "offsets": {"-5": 1}}"#;
+        let outer = Outer {
+            ports: BTreeMap::from([(80, "http".to_string()), (443, "https".to_string())]),
+            offsets: HashMap::from([(-5, 1)]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            ports: BTreeMap::from([(443, "https".to_string()), (80, "http".to_string())]),
+            offsets: HashMap::from([(-5, 1)]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"ports":{"80":"http","443":"https"},"offsets":{"-5":1}}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (ports, msg) in [
+            (r#"{"http": "x"}"#, "invalid map key \"http\""),
+            (r#"{"80": "x", "70000": "y"}"#, "invalid map key \"70000\""),
+        ] {
+            let json = format!(r#"{{"ports": {}, "offsets": {{}}}}"#, ports);
+            let err = serde_json::from_str::<Outer>(&json)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+}
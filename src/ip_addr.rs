@@ -0,0 +1,106 @@
+//! Deserialize an `IpAddr` from either a string or a host map
+//!
+//! Config files describe hosts both ways, so both of these are accepted:
+//!
+//! - a string, such as `"10.0.0.1"` or `"::1"`.
+//! - a map with a `host`, such as `{"host": "10.0.0.1"}`, which matches the
+//!   map form of [socket_addr](crate::socket_addr).
+//!
+//! Addresses are always serialized as a string.  This is built on
+//! [string_or_struct](crate::string_or_struct).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::net::IpAddr;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::ip_addr")]
+//!     pub gateway: IpAddr,
+//! }
+//! ```
+//! The following will both deserialize to `Outer`
+//! ```json
+//! {
+//!     "gateway": "10.0.0.1"
+//! }
+//! ```
+//! ```json
+//! {
+//!     "gateway": { "host": "10.0.0.1" }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::net::{AddrParseError, IpAddr};
+use std::str::FromStr;
+
+use super::string_or_struct;
+
+/// The map form of an address.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Host {
+    host: IpAddr,
+}
+
+impl FromStr for Host {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Host {
+            host: IpAddr::from_str(s.trim())?,
+        })
+    }
+}
+
+pub fn serialize<S: Serializer>(v: &IpAddr, s: S) -> Result<S::Ok, S::Error> {
+    String::serialize(&v.to_string(), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<IpAddr, D::Error> {
+    let host: Host = string_or_struct::try_deserialize(d)?;
+    Ok(host.host)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::ip_addr")]
+        pub gateway: IpAddr,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            gateway: IpAddr::V6(Ipv6Addr::LOCALHOST),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"gateway":"::1"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            gateway: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+        };
+        for json in [
+            r#"{"gateway": "10.0.0.1"}"#,
+            r#"{"gateway": " 10.0.0.1 "}"#,
+            r#"{"gateway": {"host": "10.0.0.1"}}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect(json);
+            assert_eq!(&outer, &result);
+        }
+        assert!(serde_json::from_str::<Outer>(r#"{"gateway": "10.0.0"}"#).is_err());
+        assert!(serde_json::from_str::<Outer>(r#"{"gateway": 10}"#).is_err());
+    }
+}
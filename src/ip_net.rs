@@ -0,0 +1,132 @@
+//! Serialize and Deserialize CIDR networks such as `"10.0.0.0/8"`
+//!
+//! Supports `ipnet::IpNet`, `ipnet::Ipv4Net` and `ipnet::Ipv6Net`.  Networks
+//! are parsed from, and written as, `address/prefix` strings.  Errors include
+//! the offending text, so a bad entry in a long allowlist is easy to find.
+//! Use `ip_net::vec` for a list of networks.
+//!
+//! Requires the `ipnet` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use ipnet::{IpNet, Ipv4Net};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::ip_net")]
+//!     pub subnet: Ipv4Net,
+//!     #[serde(with = "serde_stuff::ip_net::vec")]
+//!     pub allow: Vec<IpNet>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "subnet": "10.1.0.0/16",
+//!     "allow": ["10.0.0.0/8", "fd00::/8"]
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The `ipnet` network types.
+pub trait IpNetwork: FromStr<Err = ipnet::AddrParseError> + Display {}
+
+impl IpNetwork for ipnet::IpNet {}
+impl IpNetwork for ipnet::Ipv4Net {}
+impl IpNetwork for ipnet::Ipv6Net {}
+
+pub(crate) fn parse<T: IpNetwork>(value: &str) -> Result<T, String> {
+    T::from_str(value.trim()).map_err(|e| format!("invalid network \"{}\": {}", value, e))
+}
+
+pub fn serialize<S: Serializer, T: IpNetwork>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    String::serialize(&v.to_string(), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: IpNetwork>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    parse(&value).map_err(serde::de::Error::custom)
+}
+
+/// A `Vec` of networks, as a list of strings.
+pub mod vec {
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::IpNetwork;
+
+    pub fn serialize<S: Serializer, T: IpNetwork>(v: &[T], s: S) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(v.len()))?;
+        for net in v {
+            seq.serialize_element(&net.to_string())?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: IpNetwork,
+    {
+        <Vec<String>>::deserialize(d)?
+            .iter()
+            .map(|v| super::parse(v))
+            .collect::<Result<_, _>>()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ipnet::{IpNet, Ipv4Net};
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::net::Ipv4Addr;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::ip_net")]
+        pub subnet: Ipv4Net,
+        #[serde(with = "crate::ip_net::vec")]
+        pub allow: Vec<IpNet>,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            subnet: Ipv4Net::new(Ipv4Addr::new(10, 1, 0, 0), 16).unwrap(),
+            allow: vec!["10.0.0.0/8".parse().unwrap(), "fd00::/8".parse().unwrap()],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"subnet":"10.1.0.0/16","allow":["10.0.0.0/8","fd00::/8"]}"#
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"subnet": "10.1.0.0/16", "allow": ["10.0.0.0/8", "fd00::/8"]}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.subnet.prefix_len(), 16);
+        assert_eq!(result.allow.len(), 2);
+        assert!(result.allow[1].addr().is_ipv6());
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"subnet": "fd00::/8", "allow": []}"#;
+        let msg = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(msg.contains("\"fd00::/8\""), "{}", msg);
+
+        let json = r#"{"subnet": "10.1.0.0/16", "allow": ["10.0.0.0/8", "10.0.0.0/33"]}"#;
+        let msg = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(msg.contains("\"10.0.0.0/33\""), "{}", msg);
+    }
+}
@@ -0,0 +1,237 @@
+//! Serialize and Deserialize ISO 8601 week dates such as `"2024-W05-3"`
+//!
+//! Planning and payroll feeds often identify days by ISO week: the year,
+//! the week of that year (`01` to `53`) and the day of the week (`1` for
+//! Monday to `7` for Sunday).  The week-numbering year can differ from the
+//! calendar year near January 1st, so `"2025-W01-1"` is 2024-12-30.
+//!
+//! The target type decides the form:
+//!
+//! | Target                                      | Form           |
+//! |---------------------------------------------|----------------|
+//! | `chrono::NaiveDate`, `time::Date`           | `"2024-W05-3"` |
+//! | `chrono::IsoWeek`                           | `"2024-W05"`   |
+//!
+//! The basic forms without hyphens, `"2024W053"` and `"2024W05"`, are also
+//! accepted on deserialize.  The extended form is always written.  Weeks that
+//! don't exist in a year, such as `"2024-W53"`, are rejected.
+//!
+//! Support for `chrono` types is behind the `chrono` feature, and for `time`
+//! types behind the `time` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "chrono")]
+//! # {
+//! use chrono::{IsoWeek, NaiveDate};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::iso_week")]
+//!     pub sprint: IsoWeek,
+//!     #[serde(with = "serde_stuff::iso_week")]
+//!     pub payday: NaiveDate,
+//! }
+//! # }
+//! ```
+//! The following will deserialize to `Outer`, with a payday of 2024-02-02
+//! ```json
+//! {
+//!     "sprint": "2024-W05",
+//!     "payday": "2024-W05-5"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+/// Dates, or whole weeks, that can be written as an ISO 8601 week date.
+pub trait IsoWeekDate: Sized {
+    /// Whether the value has a day of the week, and is written as
+    /// `YYYY-Www-D` rather than `YYYY-Www`.
+    const HAS_DAY: bool;
+
+    /// Build from the week-numbering year, week and day of the week, from
+    /// `1` for Monday to `7` for Sunday.  The day is `1` when `HAS_DAY` is
+    /// false.  Returns `None` if there is no such date.
+    fn from_iso_week(year: i32, week: u32, weekday: u32) -> Option<Self>;
+
+    /// The week-numbering year, week and day of the week.
+    fn to_iso_week(&self) -> (i32, u32, u32);
+}
+
+#[cfg(feature = "chrono")]
+impl IsoWeekDate for chrono::NaiveDate {
+    const HAS_DAY: bool = true;
+
+    fn from_iso_week(year: i32, week: u32, weekday: u32) -> Option<Self> {
+        let weekday =
+            chrono::Weekday::try_from(u8::try_from(weekday.checked_sub(1)?).ok()?).ok()?;
+        chrono::NaiveDate::from_isoywd_opt(year, week, weekday)
+    }
+
+    fn to_iso_week(&self) -> (i32, u32, u32) {
+        use chrono::Datelike;
+        let week = self.iso_week();
+        (
+            week.year(),
+            week.week(),
+            self.weekday().number_from_monday(),
+        )
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl IsoWeekDate for chrono::IsoWeek {
+    const HAS_DAY: bool = false;
+
+    fn from_iso_week(year: i32, week: u32, _weekday: u32) -> Option<Self> {
+        use chrono::Datelike;
+        chrono::NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon).map(|d| d.iso_week())
+    }
+
+    fn to_iso_week(&self) -> (i32, u32, u32) {
+        (self.year(), self.week(), 1)
+    }
+}
+
+#[cfg(feature = "time")]
+impl IsoWeekDate for time::Date {
+    const HAS_DAY: bool = true;
+
+    fn from_iso_week(year: i32, week: u32, weekday: u32) -> Option<Self> {
+        if !(1..=7).contains(&weekday) {
+            return None;
+        }
+        let weekday = time::Weekday::Monday.nth_next((weekday - 1) as u8);
+        time::Date::from_iso_week_date(year, u8::try_from(week).ok()?, weekday).ok()
+    }
+
+    fn to_iso_week(&self) -> (i32, u32, u32) {
+        let (year, week, weekday) = self.to_iso_week_date();
+        (year, week as u32, weekday.number_from_monday() as u32)
+    }
+}
+
+fn parse<T: IsoWeekDate>(value: &str) -> Result<T, String> {
+    let expected = if T::HAS_DAY {
+        "an ISO week date such as \"2024-W05-3\""
+    } else {
+        "an ISO week such as \"2024-W05\""
+    };
+    let bad = || format!("expected {}, found \"{}\"", expected, value);
+    if !value.is_ascii() {
+        return Err(bad());
+    }
+    let (year, rest) = value.split_once('W').ok_or_else(bad)?;
+    let year = year.strip_suffix('-').unwrap_or(year);
+    let (week, weekday) = match (T::HAS_DAY, rest.len()) {
+        (true, 4) if rest.as_bytes()[2] == b'-' => (&rest[..2], &rest[3..]),
+        (true, 3) => (&rest[..2], &rest[2..]),
+        (false, 2) => (rest, "1"),
+        _ => return Err(bad()),
+    };
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if year.len() != 4 || !digits(year) || !digits(week) || !digits(weekday) {
+        return Err(bad());
+    }
+    let (year, week, weekday) = (
+        year.parse().map_err(|_| bad())?,
+        week.parse().map_err(|_| bad())?,
+        weekday.parse().map_err(|_| bad())?,
+    );
+    T::from_iso_week(year, week, weekday)
+        .ok_or_else(|| format!("ISO week date \"{}\" does not exist", value))
+}
+
+fn format<T: IsoWeekDate>(v: &T) -> Result<String, String> {
+    let (year, week, weekday) = v.to_iso_week();
+    if !(0..=9999).contains(&year) {
+        return Err(format!("year {} can not be formatted as an ISO week", year));
+    }
+    if T::HAS_DAY {
+        Ok(format!("{:04}-W{:02}-{}", year, week, weekday))
+    } else {
+        Ok(format!("{:04}-W{:02}", year, week))
+    }
+}
+
+pub fn serialize<S: Serializer, T: IsoWeekDate>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let value = format(v).map_err(serde::ser::Error::custom)?;
+    String::serialize(&value, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: IsoWeekDate>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    parse(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{IsoWeek, NaiveDate};
+        use serde::{Deserialize, Serialize};
+        use serde_json;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::iso_week")]
+            pub sprint: IsoWeek,
+            #[serde(with = "crate::iso_week")]
+            pub payday: NaiveDate,
+        }
+
+        let json = r#"{"sprint":"2024-W05","payday":"2024-W05-5"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!((result.sprint.year(), result.sprint.week()), (2024, 5));
+        assert_eq!(result.payday, NaiveDate::from_ymd_opt(2024, 2, 2).unwrap());
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+
+        let json = r#"{"sprint":"2025W01","payday":"2025W011"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(
+            result.payday,
+            NaiveDate::from_ymd_opt(2024, 12, 30).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_string(&result).expect("Oops!"),
+            r#"{"sprint":"2025-W01","payday":"2025-W01-1"}"#
+        );
+
+        for json in [
+            r#"{"sprint":"2024-W53","payday":"2024-W05-5"}"#,
+            r#"{"sprint":"2024-W05","payday":"2024-W05-8"}"#,
+            r#"{"sprint":"2024-W05-1","payday":"2024-W05-5"}"#,
+            r#"{"sprint":"2024-W05","payday":"2024-W05"}"#,
+            r#"{"sprint":"24-W05","payday":"2024-W05-5"}"#,
+            r#"{"sprint":"2024-W05","payday":"2024-W0é"}"#,
+            r#"{"sprint":"2024-Wé","payday":"2024-W05-5"}"#,
+        ] {
+            assert!(serde_json::from_str::<Outer>(json).is_err(), "{}", json);
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use serde::{Deserialize, Serialize};
+        use serde_json;
+        use time::macros::date;
+        use time::Date;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::iso_week")]
+            pub payday: Date,
+        }
+
+        let json = r#"{"payday":"2020-W53-7"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.payday, date!(2021 - 01 - 03));
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+}
@@ -0,0 +1,124 @@
+//! Serialize and Deserialize a time in the exact shape of JavaScript's `Date.toJSON`
+//!
+//! JavaScript always writes dates as `YYYY-MM-DDTHH:mm:ss.sssZ`, in UTC with
+//! exactly three fractional digits, such as `"2023-11-14T22:13:20.000Z"`.
+//! This module writes the same shape, so strings round-trip byte for byte
+//! with front-end code and can be compared against fixtures.  Sub-millisecond
+//! precision is truncated, as a JavaScript `Date` has none.
+//!
+//! Deserialize is strict: any other RFC 3339 shape, such as a missing
+//! fraction, more digits or an offset other than `Z`, is rejected.  Use
+//! [rfc3339](crate::rfc3339) to accept those as well.
+//!
+//! Any [EpochTime](crate::epoch::EpochTime) is supported.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::js_date")]
+//!     pub created: SystemTime,
+//! }
+//! ```
+//! The following will deserialize to `Outer`, and serialize back unchanged
+//! ```json
+//! {
+//!     "created": "2023-11-14T22:13:20.250Z"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+use super::civil;
+use super::epoch::EpochTime;
+
+/// The shape of `Date.toJSON`, with `9` for any digit.
+const SHAPE: &[u8; 24] = b"9999-99-99T99:99:99.999Z";
+
+fn parse(value: &str) -> Result<i128, String> {
+    let matches = value.len() == SHAPE.len()
+        && value.bytes().zip(SHAPE.iter()).all(|(b, s)| match s {
+            b'9' => b.is_ascii_digit(),
+            s => b == *s,
+        });
+    if !matches {
+        return Err(format!(
+            "expected a date such as \"2023-11-14T22:13:20.000Z\", found \"{}\"",
+            value
+        ));
+    }
+    Ok(civil::parse_rfc3339(value)?.epoch_nanos())
+}
+
+pub fn serialize<S: Serializer, T: EpochTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let value =
+        civil::format_rfc3339(v.to_epoch_nanos(), Some(3)).map_err(serde::ser::Error::custom)?;
+    String::serialize(&value, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    let nanos = parse(&value).map_err(serde::de::Error::custom)?;
+    T::from_epoch_nanos(nanos)
+        .ok_or_else(|| serde::de::Error::custom(format!("date \"{}\" is out of range", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::js_date")]
+        pub created: SystemTime,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"created":"2023-11-14T22:13:20.000Z"}"#);
+
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_nanos(1_700_000_000_250_999_999),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"created":"2023-11-14T22:13:20.250Z"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"created":"2023-11-14T22:13:20.250Z"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(
+            result.created,
+            UNIX_EPOCH + Duration::from_millis(1_700_000_000_250)
+        );
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+
+    #[test]
+    fn deserialize_strict() {
+        for value in [
+            "2023-11-14T22:13:20Z",
+            "2023-11-14T22:13:20.25Z",
+            "2023-11-14T22:13:20.250000Z",
+            "2023-11-14T22:13:20.250+00:00",
+            "2023-11-14t22:13:20.250z",
+            "2023-11-14 22:13:20.250Z",
+            "2023-02-29T22:13:20.250Z",
+        ] {
+            let json = format!(r#"{{"created": "{}"}}"#, value);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", value);
+        }
+    }
+}
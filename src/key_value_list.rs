@@ -0,0 +1,342 @@
+//! Serialize and Deserialize a map as a list of key/value objects
+//!
+//! Many XML and protobuf derived JSON schemas can't use an object for a
+//! map, and write `[{"key": "env", "value": "prod"}, ...]` instead.  This
+//! module reads that shape into a `HashMap`, `BTreeMap` or `IndexMap`, and
+//! writes it back.  Fields other than the key and value are ignored, and a missing or
+//! repeated key or value is an error.
+//!
+//! Schemas don't agree on the field names.  The [key_value_list!](crate::key_value_list!)
+//! macro generates `serialize` and `deserialize` functions for other names.
+//! It can be invoked inside a module you declare, or given a module name to
+//! declare:
+//!
+//! ```rust
+//! mod tags {
+//!     serde_stuff::key_value_list!("Key", "Value");
+//! }
+//!
+//! serde_stuff::key_value_list!(pub attributes, "name", "value");
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! serde_stuff::key_value_list!(attributes, "name", "value");
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::key_value_list")]
+//!     pub labels: HashMap<String, String>,
+//!     #[serde(with = "attributes")]
+//!     pub attributes: HashMap<String, u32>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "labels": [{"key": "env", "value": "prod"}],
+//!     "attributes": [{"name": "replicas", "value": 3}]
+//! }
+//! ```
+
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Serialize the entries of `v` as objects with `key` and `value` fields.
+pub fn serialize_with<S, M, K, V>(
+    v: &M,
+    s: S,
+    key: &'static str,
+    value: &'static str,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    for<'a> &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Serialize,
+    V: Serialize,
+{
+    struct Entry<'a, K, V>(&'a K, &'a V, &'static str, &'static str);
+
+    impl<'a, K: Serialize, V: Serialize> Serialize for Entry<'a, K, V> {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            let mut map = s.serialize_map(Some(2))?;
+            map.serialize_entry(self.2, self.0)?;
+            map.serialize_entry(self.3, self.1)?;
+            map.end()
+        }
+    }
+
+    let entries = v.into_iter();
+    let mut seq = s.serialize_seq(Some(entries.size_hint().0))?;
+    for (k, v) in entries {
+        seq.serialize_element(&Entry(k, v, key, value))?;
+    }
+    seq.end()
+}
+
+/// Deserialize a list of objects with `key` and `value` fields into a map.
+pub fn deserialize_with<'de, D, M, K, V>(
+    d: D,
+    key: &'static str,
+    value: &'static str,
+) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: FromIterator<(K, V)>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    struct EntrySeed<K, V>(&'static str, &'static str, PhantomData<(K, V)>);
+
+    impl<'de, K: Deserialize<'de>, V: Deserialize<'de>> DeserializeSeed<'de> for EntrySeed<K, V> {
+        type Value = (K, V);
+
+        fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<(K, V), D::Error> {
+            d.deserialize_map(self)
+        }
+    }
+
+    impl<'de, K: Deserialize<'de>, V: Deserialize<'de>> Visitor<'de> for EntrySeed<K, V> {
+        type Value = (K, V);
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                formatter,
+                "an object with \"{}\" and \"{}\"",
+                self.0, self.1
+            )
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<(K, V), A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let (mut key, mut value) = (None, None);
+            while let Some(name) = map.next_key::<String>()? {
+                if name == self.0 {
+                    if key.is_some() {
+                        return Err(de::Error::duplicate_field(self.0));
+                    }
+                    key = Some(map.next_value()?);
+                } else if name == self.1 {
+                    if value.is_some() {
+                        return Err(de::Error::duplicate_field(self.1));
+                    }
+                    value = Some(map.next_value()?);
+                } else {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+            let key = key.ok_or_else(|| de::Error::missing_field(self.0))?;
+            let value = value.ok_or_else(|| de::Error::missing_field(self.1))?;
+            Ok((key, value))
+        }
+    }
+
+    struct ListVisitor<M, K, V>(&'static str, &'static str, PhantomData<(M, K, V)>);
+
+    impl<'de, M, K, V> Visitor<'de> for ListVisitor<M, K, V>
+    where
+        M: FromIterator<(K, V)>,
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                formatter,
+                "a list of objects with \"{}\" and \"{}\"",
+                self.0, self.1
+            )
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<M, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut entries = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(entry) = seq.next_element_seed(EntrySeed(self.0, self.1, PhantomData))? {
+                entries.push(entry);
+            }
+            Ok(entries.into_iter().collect())
+        }
+    }
+
+    d.deserialize_seq(ListVisitor(key, value, PhantomData))
+}
+
+pub fn serialize<S, M, K, V>(v: &M, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    for<'a> &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Serialize,
+    V: Serialize,
+{
+    serialize_with(v, s, "key", "value")
+}
+
+pub fn deserialize<'de, D, M, K, V>(d: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: FromIterator<(K, V)>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    deserialize_with(d, "key", "value")
+}
+
+/// Generate a serde `with` module for a key/value list with other field names.
+///
+/// With only the field names, the functions are generated in place.  With a
+/// visibility and name first, a module of that name is declared.
+#[macro_export]
+macro_rules! key_value_list {
+    ($vis:vis $name:ident, $key:literal, $value:literal) => {
+        $vis mod $name {
+            $crate::key_value_list!($key, $value);
+        }
+    };
+    ($key:literal, $value:literal) => {
+        #[allow(dead_code)]
+        pub fn serialize<S, M, K, V>(v: &M, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+            for<'a> &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+            K: serde::Serialize,
+            V: serde::Serialize,
+        {
+            $crate::key_value_list::serialize_with(v, s, $key, $value)
+        }
+
+        #[allow(dead_code)]
+        pub fn deserialize<'de, D, M, K, V>(d: D) -> Result<M, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+            M: FromIterator<(K, V)>,
+            K: serde::Deserialize<'de>,
+            V: serde::Deserialize<'de>,
+        {
+            $crate::key_value_list::deserialize_with(d, $key, $value)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+
+    mod tags {
+        crate::key_value_list!("Key", "Value");
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::key_value_list")]
+        pub labels: BTreeMap<String, String>,
+        #[serde(with = "tags")]
+        pub tags: HashMap<u32, bool>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "labels": [
+                {"key": "env", "value": "prod"},
+                {"value": "api", "key": "app", "comment": "ignored"}
+            ],
+            "tags": [{"Key": 7, "Value": true}]
+        }"#;
+        let outer = Outer {
+            labels: BTreeMap::from([
+                ("env".to_string(), "prod".to_string()),
+                ("app".to_string(), "api".to_string()),
+            ]),
+            tags: HashMap::from([(7, true)]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            labels: BTreeMap::from([
+                ("env".to_string(), "prod".to_string()),
+                ("app".to_string(), "api".to_string()),
+            ]),
+            tags: HashMap::new(),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"labels":[{"key":"app","value":"api"},{"key":"env","value":"prod"}],"tags":[]}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (labels, msg) in [
+            (r#"[{"key": "env"}]"#, "missing field `value`"),
+            (r#"[{"value": "prod"}]"#, "missing field `key`"),
+            (
+                r#"[{"key": "a", "key": "b", "value": "c"}]"#,
+                "duplicate field `key`",
+            ),
+            (
+                r#"{"env": "prod"}"#,
+                "a list of objects with \"key\" and \"value\"",
+            ),
+        ] {
+            let json = format!(r#"{{"labels": {}, "tags": []}}"#, labels);
+            let err = serde_json::from_str::<Outer>(&json)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let outer = Outer {
+            labels: BTreeMap::from([("env".to_string(), "prod".to_string())]),
+            tags: HashMap::from([(1, false)]),
+        };
+        let bytes = bincode::serialize(&outer).expect("Oops!");
+        let result: Outer = bincode::deserialize(&bytes).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn bincode_hostile_length() {
+        // A length prefix with no entries behind it is an error, rather
+        // than an allocation of that many entries.
+        let bytes = (u64::MAX / 2).to_le_bytes();
+        assert!(bincode::deserialize::<Outer>(&bytes).is_err());
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn indexmap() {
+        use indexmap::IndexMap;
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct Ordered {
+            #[serde(with = "crate::key_value_list")]
+            pub labels: IndexMap<String, String>,
+        }
+
+        let json = r#"{"labels":[{"key":"zed","value":"1"},{"key":"alice","value":"2"}]}"#;
+        let result: Ordered = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+}
@@ -0,0 +1,196 @@
+//! Serialize a `Vec<T>` as a map keyed by a field of `T`
+//!
+//! Registry style documents key each entry by its name, as in
+//! `{"alice": {"role": "admin"}, "bob": {"role": "user"}}`, while the code
+//! would rather keep a simple list.  This module writes a `Vec<T>` as a map
+//! from each item's key to the item, and reads the map back into a `Vec` in
+//! document order, giving each item its key.  A repeated key is an error
+//! either way.
+//!
+//! `T` implements [Keyed] to get and set its key.  The key field itself is
+//! usually `#[serde(skip)]`, since the map already holds it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::keyed_vec::Keyed;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct User {
+//!     #[serde(skip)]
+//!     pub name: String,
+//!     pub role: String,
+//! }
+//!
+//! impl Keyed for User {
+//!     type Key = String;
+//!
+//!     fn key(&self) -> &String {
+//!         &self.name
+//!     }
+//!
+//!     fn set_key(&mut self, key: String) {
+//!         self.name = key;
+//!     }
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::keyed_vec")]
+//!     pub users: Vec<User>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`, with `users[1].name` of `"bob"`
+//! ```json
+//! {
+//!     "users": {"alice": {"role": "admin"}, "bob": {"role": "user"}}
+//! }
+//! ```
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashSet;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Items that are keyed by one of their fields.
+pub trait Keyed {
+    type Key: Eq + Hash + Debug;
+
+    fn key(&self) -> &Self::Key;
+    fn set_key(&mut self, key: Self::Key);
+}
+
+pub fn serialize<S, T>(v: &[T], s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Keyed + Serialize,
+    T::Key: Serialize,
+{
+    let mut seen = HashSet::with_capacity(v.len());
+    let mut map = s.serialize_map(Some(v.len()))?;
+    for item in v {
+        if !seen.insert(item.key()) {
+            return Err(serde::ser::Error::custom(format!(
+                "duplicate key {:?}",
+                item.key()
+            )));
+        }
+        map.serialize_entry(item.key(), item)?;
+    }
+    map.end()
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Keyed + Deserialize<'de>,
+    T::Key: Deserialize<'de> + Clone,
+{
+    struct KeyedVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for KeyedVisitor<T>
+    where
+        T: Keyed + Deserialize<'de>,
+        T::Key: Deserialize<'de> + Clone,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map of keyed items")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Vec<T>, A::Error> {
+            let mut seen = HashSet::new();
+            let mut items = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+            while let Some((key, mut item)) = map.next_entry::<T::Key, T>()? {
+                if !seen.insert(key.clone()) {
+                    return Err(de::Error::custom(format!("duplicate key {:?}", key)));
+                }
+                item.set_key(key);
+                items.push(item);
+            }
+            Ok(items)
+        }
+    }
+
+    d.deserialize_map(KeyedVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    use super::Keyed;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct User {
+        #[serde(skip)]
+        pub name: String,
+        pub role: String,
+    }
+
+    impl Keyed for User {
+        type Key = String;
+
+        fn key(&self) -> &String {
+            &self.name
+        }
+
+        fn set_key(&mut self, key: String) {
+            self.name = key;
+        }
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::keyed_vec")]
+        pub users: Vec<User>,
+    }
+
+    fn user(name: &str, role: &str) -> User {
+        User {
+            name: name.to_string(),
+            role: role.to_string(),
+        }
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"users": {"zed": {"role": "admin"}, "amy": {"role": "user"}}}"#;
+        let outer = Outer {
+            users: vec![user("zed", "admin"), user("amy", "user")],
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            users: vec![user("zed", "admin"), user("amy", "user")],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"users":{"zed":{"role":"admin"},"amy":{"role":"user"}}}"#
+        );
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let json = r#"{"users": {"amy": {"role": "admin"}, "amy": {"role": "user"}}}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("duplicate key \"amy\""), "{}", err);
+
+        let outer = Outer {
+            users: vec![user("amy", "admin"), user("amy", "user")],
+        };
+        let err = serde_json::to_string(&outer).unwrap_err().to_string();
+        assert!(err.contains("duplicate key \"amy\""), "{}", err);
+    }
+}
@@ -0,0 +1,197 @@
+//! Deserialize a BCP 47 language tag, such as `"en-US"`, validated and canonical
+//!
+//! Tags are checked against the RFC 5646 syntax: a 2-3 letter language
+//! (with up to three extended language subtags) or a 4-8 letter language,
+//! then an optional script, region, variants, extensions and private use
+//! subtags.  Both `-` and `_` separators are accepted.  Duplicate variants
+//! or extension singletons are rejected.
+//!
+//! Valid tags are stored in the canonical case: a lower case language, a
+//! title case script and an upper case region, as in `"zh-Hant-TW"`.  Only
+//! the syntax is checked; subtags aren't looked up in the IANA registry, and
+//! the irregular grandfathered tags, such as `"i-klingon"`, aren't accepted.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::language_tag")]
+//!     pub locale: String,
+//! }
+//! ```
+//! The following will deserialize to `Outer { locale: "zh-Hant-TW".to_string() }`
+//! ```json
+//! {
+//!     "locale": "ZH_hant_tw"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+fn is_alpha(s: &str, len: std::ops::RangeInclusive<usize>) -> bool {
+    len.contains(&s.len()) && s.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_alnum(s: &str, len: std::ops::RangeInclusive<usize>) -> bool {
+    len.contains(&s.len()) && s.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+fn is_digit(s: &str, len: usize) -> bool {
+    s.len() == len && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_variant(s: &str) -> bool {
+    is_alnum(s, 5..=8) || (s.len() == 4 && s.as_bytes()[0].is_ascii_digit() && is_alnum(s, 4..=4))
+}
+
+/// Validate `value`, and return it in canonical case.
+pub(crate) fn canonicalize(value: &str) -> Result<String, String> {
+    let bad = |why: &str| format!("invalid language tag \"{}\": {}", value, why);
+    let subtags: Vec<String> = value
+        .split(['-', '_'])
+        .map(|s| s.to_ascii_lowercase())
+        .collect();
+    let mut out: Vec<String> = Vec::with_capacity(subtags.len());
+    let mut rest = subtags.iter().peekable();
+
+    let private_only = subtags[0] == "x";
+    if !private_only {
+        let language = rest.next().ok_or_else(|| bad("empty"))?;
+        if !is_alpha(language, 2..=3) && !is_alpha(language, 4..=8) {
+            return Err(bad("the language must be 2 to 8 letters"));
+        }
+        out.push(language.clone());
+        if language.len() <= 3 {
+            for _ in 0..3 {
+                match rest.peek() {
+                    Some(s) if is_alpha(s, 3..=3) => out.push(rest.next().unwrap().clone()),
+                    _ => break,
+                }
+            }
+        }
+        if let Some(script) = rest.next_if(|s| is_alpha(s, 4..=4)) {
+            out.push(script[..1].to_ascii_uppercase() + &script[1..]);
+        }
+        if let Some(region) = rest.next_if(|s| is_alpha(s, 2..=2) || is_digit(s, 3)) {
+            out.push(region.to_ascii_uppercase());
+        }
+        while let Some(variant) = rest.next_if(|s| is_variant(s)) {
+            if out.contains(variant) {
+                return Err(bad(&format!("duplicate variant \"{}\"", variant)));
+            }
+            out.push(variant.clone());
+        }
+        let mut singletons = Vec::new();
+        while let Some(singleton) = rest.next_if(|s| s.len() == 1 && s != &"x") {
+            if !is_alnum(singleton, 1..=1) {
+                return Err(bad(&format!("invalid extension \"{}\"", singleton)));
+            }
+            if singletons.contains(singleton) {
+                return Err(bad(&format!("duplicate extension \"{}\"", singleton)));
+            }
+            singletons.push(singleton.clone());
+            out.push(singleton.clone());
+            let start = out.len();
+            while let Some(subtag) = rest.next_if(|s| is_alnum(s, 2..=8)) {
+                out.push(subtag.clone());
+            }
+            if out.len() == start {
+                return Err(bad(&format!("extension \"{}\" is empty", singleton)));
+            }
+        }
+    }
+    if let Some(x) = rest.next() {
+        if x != "x" {
+            return Err(bad(&format!("unexpected subtag \"{}\"", x)));
+        }
+        out.push(x.clone());
+        let start = out.len();
+        for subtag in rest.by_ref() {
+            if !is_alnum(subtag, 1..=8) {
+                return Err(bad(&format!("invalid private use subtag \"{}\"", subtag)));
+            }
+            out.push(subtag.clone());
+        }
+        if out.len() == start {
+            return Err(bad("private use is empty"));
+        }
+    }
+    Ok(out.join("-"))
+}
+
+/// Language tags are serialized unchanged.
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    str::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let value = String::deserialize(d)?;
+    canonicalize(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::language_tag")]
+        pub locale: String,
+    }
+
+    #[test]
+    fn deserialize() {
+        for (value, expected) in [
+            ("en", "en"),
+            ("en-us", "en-US"),
+            ("ZH_hant_tw", "zh-Hant-TW"),
+            ("es-419", "es-419"),
+            ("zh-yue-HK", "zh-yue-HK"),
+            ("sl-rozaj-biske", "sl-rozaj-biske"),
+            ("de-CH-1901", "de-CH-1901"),
+            (
+                "en-US-u-ca-gregory-x-Private",
+                "en-US-u-ca-gregory-x-private",
+            ),
+            ("x-whatever", "x-whatever"),
+        ] {
+            let json = format!(r#"{{"locale": "{}"}}"#, value);
+            let result: Outer = serde_json::from_str(&json).expect(value);
+            assert_eq!(result.locale, expected);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for value in [
+            "",
+            "e",
+            "englishlanguage",
+            "en-US-",
+            "de-419-DE",
+            "sl-rozaj-rozaj",
+            "en-a-bbb-a-ccc",
+            "en-u",
+            "en-x",
+            "en--US",
+            "i-klingon",
+        ] {
+            let json = format!(r#"{{"locale": "{}"}}"#, value);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            locale: "en-GB".to_string(),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"locale":"en-GB"}"#);
+    }
+}
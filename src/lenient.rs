@@ -0,0 +1,225 @@
+//! Deserialize what can be, and warn about what had to be repaired
+//!
+//! Ingestion pipelines would rather load a slightly wrong record, and log
+//! what was wrong with it, than drop it.  The `from_*` functions here read
+//! a value as leniently as possible, and return it with a [Warning], with
+//! its path, for each repair:
+//!
+//! - a field of the wrong type is coerced where that is unambiguous:
+//!   `"8080"` to a number, `"yes"`, `"on"`, `"1"` or `1` to a boolean, a
+//!   number or boolean to a string, a single value to a sequence of one,
+//!   and `null` to an empty sequence or map.
+//! - the tolerant modules run in lenient [strictness](crate::strictness)
+//!   mode, and each repair they make, such as a dropped duplicate in
+//!   [set_dedup](crate::set_dedup), or invalid UTF-8 in
+//!   [lossy_string](crate::lossy_string), is a warning.
+//! - anything else that fails is read as a placeholder, or `None`, as in
+//!   [accumulate](crate::accumulate), and its error is a warning.
+//!
+//! Only when the value can't be built at all, say because a required field
+//! is missing, is there an error.  Since the document is buffered as
+//! [Content], the format must be self describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use serde_stuff::lenient;
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Event {
+//!     pub id: u64,
+//!     pub retry: bool,
+//!     #[serde(with = "serde_stuff::set_dedup")]
+//!     pub tags: Vec<String>,
+//! }
+//!
+//! let json = r#"{"id": "42", "retry": "yes", "tags": ["a", "a"]}"#;
+//! let (event, warnings): (Event, _) =
+//!     lenient::deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+//! assert_eq!(event.id, 42);
+//! let warnings: Vec<String> = warnings.iter().map(|w| w.to_string()).collect();
+//! assert_eq!(
+//!     warnings,
+//!     [
+//!         "read string \"42\" as u64 at id",
+//!         "read string \"yes\" as a boolean at retry",
+//!         "dropped a duplicate element at tags",
+//!     ]
+//! );
+//! ```
+
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::accumulate::{collect, FieldError};
+use super::content::Content;
+use super::strictness;
+
+/// A repair, and the path of the field it was made at.
+pub type Warning = FieldError;
+
+/// Read the whole document leniently.  The error lists every problem, when
+/// the value can't be built.
+pub fn deserialize<'de, D, T>(d: D) -> Result<(T, Vec<Warning>), D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let content = Content::deserialize(d)?;
+    from_content(content).map_err(|errors| {
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        D::Error::custom(errors.join("; "))
+    })
+}
+
+/// [deserialize] from a buffered document.
+pub fn from_content<T: DeserializeOwned>(
+    content: Content,
+) -> Result<(T, Vec<Warning>), Vec<FieldError>> {
+    let report = strictness::collecting(|| collect::<T>(content, true));
+    match report.value {
+        Some(value) => Ok((value, report.errors)),
+        None => Err(report.errors),
+    }
+}
+
+/// [deserialize] from another `Serialize` value, such as a
+/// `serde_json::Value`.
+pub fn from_value<T, V>(value: &V) -> Result<(T, Vec<Warning>), Vec<FieldError>>
+where
+    T: DeserializeOwned,
+    V: ?Sized + Serialize,
+{
+    let content = Content::from_value(value).map_err(|e| {
+        vec![FieldError {
+            path: String::new(),
+            message: e.to_string(),
+        }]
+    })?;
+    from_content(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    use super::Warning;
+    use crate::strictness;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    pub struct Server {
+        pub host: String,
+        pub port: u16,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    pub struct Config {
+        pub name: String,
+        pub debug: bool,
+        pub ratio: f64,
+        pub servers: Vec<Server>,
+        pub tags: Vec<String>,
+        pub labels: BTreeMap<String, String>,
+        pub level: Option<u8>,
+        #[serde(default, with = "crate::set_dedup")]
+        pub roles: Vec<String>,
+    }
+
+    fn lenient(json: &str) -> Result<(Config, Vec<String>), String> {
+        super::deserialize(&mut serde_json::Deserializer::from_str(json))
+            .map(|(config, warnings): (Config, Vec<Warning>)| {
+                (config, warnings.iter().map(|w| w.to_string()).collect())
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn no_warnings() {
+        let json = r#"{
+            "name": "prod", "debug": false, "ratio": 0.5,
+            "servers": [{"host": "a", "port": 80}], "tags": ["x"],
+            "labels": {}, "level": 3
+        }"#;
+        let (config, warnings) = lenient(json).expect("Oops!");
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(config.level, Some(3));
+    }
+
+    #[test]
+    fn coercions() {
+        let json = r#"{
+            "name": 7, "debug": "on", "ratio": " 1.5 ",
+            "servers": {"host": "a", "port": "8080"}, "tags": "x",
+            "labels": null, "level": 2.0
+        }"#;
+        let (config, warnings) = lenient(json).expect("Oops!");
+        assert_eq!(
+            config,
+            Config {
+                name: "7".to_string(),
+                debug: true,
+                ratio: 1.5,
+                servers: vec![Server {
+                    host: "a".to_string(),
+                    port: 8080,
+                }],
+                tags: vec!["x".to_string()],
+                labels: BTreeMap::new(),
+                level: Some(2),
+                roles: vec![],
+            }
+        );
+        assert_eq!(
+            warnings,
+            [
+                "read integer `7` as a string at name",
+                "read string \"on\" as a boolean at debug",
+                "read string \" 1.5 \" as f64 at ratio",
+                "read map as a sequence of one at servers",
+                "read string \"8080\" as u16 at servers[0].port",
+                "read string \"x\" as a sequence of one at tags",
+                "read unit value as an empty map at labels",
+                "read floating point `2.0` as u8 at level",
+            ]
+        );
+    }
+
+    #[test]
+    fn fallbacks() {
+        let json = r#"{
+            "name": "prod", "debug": "maybe", "ratio": 0.5,
+            "servers": [], "tags": [], "labels": {}, "level": 300,
+            "roles": ["admin", "admin"]
+        }"#;
+        let (config, warnings) = strictness::strict(|| lenient(json)).expect("Oops!");
+        assert!(!config.debug);
+        assert_eq!(config.level, None);
+        assert_eq!(config.roles, vec!["admin".to_string()]);
+        assert_eq!(
+            warnings,
+            [
+                "invalid type: string \"maybe\", expected a boolean at debug",
+                "invalid value: integer `300`, expected u8 at level",
+                "dropped a duplicate element at roles",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_field() {
+        let json = r#"{"name": "prod"}"#;
+        let err = lenient(json).expect_err("Oops!");
+        assert_eq!(err, "missing field `debug`");
+    }
+
+    #[test]
+    fn from_value() {
+        let value = serde_json::json!({"host": "a", "port": "80"});
+        let (server, warnings): (Server, _) = super::from_value(&value).expect("Oops!");
+        assert_eq!(server.port, 80);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, "port");
+    }
+}
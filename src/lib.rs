@@ -10,12 +10,302 @@
 //!
 //! But this crate does not depend on it.  If you don't want to use it, just use the
 //! mod's `serialize` and `deserialize` functions.  Such as `#[serde(deserialize_with = "serde_stuff::string_or_struct::deserialize")]`.
+//!
+//! # Prelude
+//! `use serde_stuff::prelude::*;` imports the common types, traits and modules
+//! at once, so a field can use `#[serde(with = "timestamp_secs")]`.
+//!
+//! # Features
+//! Support for third party types is behind optional features.  `std` and
+//! `base64` are on by default.  With `default-features = false` the crate is
+//! `no_std`, needs only `alloc`, and depends on nothing but `serde` and
+//! `void`, for embedded and wasm builds:
+//! - `base64`: the `base64` and `option_base64` modules, the `Base64`
+//!   adapter, and the `deterministic` and `os_string` modules.  Also
+//!   enabled by `uuid`, for `uuid_bytes`.
+//! - `bitflags`: `bitflags::Flags` types in the `flag_names` module.
+//! - `chrono`: `chrono::DateTime` in the time modules, such as `timestamp_*`,
+//!   `rfc3339` and `excel_date`, and the `chrono_format` module with its
+//!   `datetime_format!` macro.  `chrono::DateTime<FixedOffset>` in the
+//!   `rfc3339_offset` module, and `NaiveDate` and `IsoWeek` in the
+//!   `iso_week` and `option_iso_week` modules.
+//! - `time`: `time::OffsetDateTime` in the time modules, and the
+//!   `time_format` module for `OffsetDateTime`, `PrimitiveDateTime` and `Date`.
+//!   `time::OffsetDateTime` in the `rfc3339_offset` module, and `time::Date`
+//!   in the `iso_week` and `option_iso_week` modules.
+//! - `cron`: `cron::Schedule` in the `cron_expr::schedule` module.
+//! - `derive`: the `stuff` attribute macro, from `serde-stuff-derive`, for
+//!   short `#[stuff(...)]` field attributes such as `#[stuff(base64)]`, and
+//!   `derive(Partial)` for PATCH request bodies of `patch::Patch` fields.
+//! - `http`: `HeaderName`, `HeaderValue`, `Method`, `StatusCode` and `Uri`
+//!   in the `http_types` module.
+//! - `idna`: internationalized names in the `hostname` module.
+//! - `indexmap`: `IndexMap` in the `unique_keys` and `multimap` modules,
+//!   and `IndexSet` in the `set_strict` and `set_dedup` modules.
+//!   The other map modules are generic over the map type, and keep the
+//!   order of an `IndexMap`.
+//! - `ipnet`: `ipnet::IpNet`, `Ipv4Net` and `Ipv6Net` in the `ip_net` module.
+//! - `jiff`: `jiff::Timestamp` and `jiff::Zoned` in the time modules.
+//! - `num-rational`: `num_rational::Ratio` in the `ratio` module.
+//! - `phonenumber`: metadata checks in the `phone_number` module.
+//! - `regex`: `regex::Regex` in the `regex_pattern` module.
+//! - `secrecy`: `secrecy::SecretBox` in the `redact::secret` module.
+//! - `sha2`: SHA-256 fingerprints in the `redact::hashed` module.
+//! - `toml`: TOML datetimes, as `chrono` and `time` types, in the
+//!   `toml_datetime` and `option_toml_datetime` modules.
+//! - `url`: `url::Url` in the `url_string` and `url_or_urls` modules.
+//! - `std`: everything else.  Without it, only the `adapters` (but for
+//!   `HashMap`), `canonical_json`, `content`, `either`, `patch`, `prelude`,
+//!   `redact`, `rename_all`, `string_or_struct`, `trimmed`, `vec_or_one`
+//!   and `xs_boolean` modules, their `option_*` companions,
+//!   `option_xsi_nil` and `option_yaml_null`, and `base64` with its
+//!   feature, are built, with `alloc` alone.  The third party features all enable
+//!   `std`.
+//! - `uuid`: `uuid::Uuid` in the `uuid_string` and `uuid_bytes` modules.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod accumulate;
+pub mod adapters;
+#[cfg(feature = "std")]
+pub mod aliases;
+#[cfg(feature = "base64")]
 pub mod base64;
+#[cfg(feature = "std")]
+pub mod bounded_int;
+#[cfg(feature = "std")]
+pub mod bounded_string;
+pub mod canonical_json;
+#[cfg(feature = "std")]
+mod case;
+#[cfg(feature = "std")]
+pub mod case_insensitive;
+#[cfg(feature = "std")]
+pub mod char_string;
+#[cfg(feature = "chrono")]
+pub mod chrono_format;
+#[cfg(feature = "std")]
+mod civil;
+#[cfg(feature = "std")]
+pub mod code_range;
+#[cfg(feature = "std")]
+pub mod compose;
+pub mod content;
+#[cfg(feature = "std")]
+pub mod country_code;
+#[cfg(feature = "std")]
+pub mod cron_expr;
+#[cfg(feature = "std")]
+pub mod currency_code;
+#[cfg(feature = "std")]
+pub mod datetime_fallback;
+#[cfg(feature = "base64")]
+pub mod deterministic;
+#[cfg(feature = "std")]
+pub mod duration_secs;
+pub mod either;
+#[cfg(feature = "std")]
+pub mod email;
+#[cfg(feature = "std")]
+pub mod enum_int;
+#[cfg(feature = "std")]
+pub mod envelope;
+#[cfg(feature = "std")]
+pub mod epoch;
+#[cfg(feature = "std")]
+pub mod excel_date;
+#[cfg(feature = "std")]
+pub mod extra;
+#[cfg(feature = "std")]
+pub mod first_ok;
+#[cfg(feature = "bitflags")]
+pub mod flag_names;
+#[cfg(feature = "std")]
+pub mod hex_color;
+#[cfg(feature = "std")]
+pub mod hostname;
+#[cfg(feature = "std")]
+pub mod http_date;
+#[cfg(feature = "http")]
+pub mod http_types;
+#[cfg(feature = "std")]
+pub mod human_duration;
+#[cfg(feature = "std")]
+pub mod infinite_duration;
+#[cfg(feature = "std")]
+pub mod int_keys;
+#[cfg(feature = "std")]
+pub mod ip_addr;
+#[cfg(feature = "ipnet")]
+pub mod ip_net;
+#[cfg(feature = "std")]
+pub mod iso_week;
+#[cfg(feature = "std")]
+pub mod js_date;
+#[cfg(feature = "std")]
+pub mod key_value_list;
+#[cfg(feature = "std")]
+pub mod keyed_vec;
+#[cfg(feature = "std")]
+pub mod language_tag;
+#[cfg(feature = "std")]
+pub mod lenient;
+#[cfg(feature = "std")]
+pub mod lossy_string;
+#[cfg(feature = "std")]
+pub mod lowercase;
+#[cfg(feature = "std")]
+pub mod map_key_fromstr;
+#[cfg(feature = "std")]
+pub mod map_keys;
+#[cfg(feature = "std")]
+pub mod map_of;
+#[cfg(feature = "std")]
+pub mod map_values;
+#[cfg(feature = "std")]
+pub mod multimap;
+#[cfg(feature = "std")]
+pub mod name_or_code;
+#[cfg(feature = "std")]
+pub mod named_tuple;
+#[cfg(feature = "std")]
+pub mod nested;
+#[cfg(feature = "std")]
+pub mod non_empty_string;
+#[cfg(feature = "std")]
+pub mod null_as_empty_map;
+#[cfg(feature = "std")]
+pub mod number_list;
+#[cfg(feature = "base64")]
 pub mod option_base64;
+#[cfg(feature = "std")]
+pub mod option_datetime_fallback;
+#[cfg(feature = "std")]
+pub mod option_duration_secs;
+#[cfg(feature = "std")]
+pub mod option_enum_int;
+#[cfg(feature = "std")]
+pub mod option_excel_date;
+#[cfg(feature = "std")]
+pub mod option_http_date;
+#[cfg(feature = "std")]
+pub mod option_human_duration;
+#[cfg(feature = "std")]
+pub mod option_iso_week;
+#[cfg(feature = "std")]
+pub mod option_js_date;
+#[cfg(feature = "std")]
+pub mod option_non_empty_string;
+#[cfg(feature = "std")]
+pub mod option_rfc2822;
+#[cfg(feature = "std")]
+pub mod option_rfc3339;
+#[cfg(feature = "std")]
+pub mod option_rfc3339_offset;
 pub mod option_string_or_struct;
+#[cfg(feature = "std")]
+pub mod option_timestamp_micros;
+#[cfg(feature = "std")]
+pub mod option_timestamp_millis;
+#[cfg(feature = "std")]
+pub mod option_timestamp_nanos;
+#[cfg(feature = "std")]
+pub mod option_timestamp_or_string;
+#[cfg(feature = "std")]
+pub mod option_timestamp_secs;
+#[cfg(feature = "toml")]
+pub mod option_toml_datetime;
+pub mod option_trimmed;
+#[cfg(feature = "url")]
+pub mod option_url_string;
+#[cfg(feature = "uuid")]
+pub mod option_uuid_string;
 pub mod option_vec_or_one;
+pub mod option_xsi_nil;
+pub mod option_yaml_null;
+mod optional;
+#[cfg(feature = "std")]
+pub mod or_other;
+#[cfg(feature = "std")]
+pub mod ordered_map;
+#[cfg(all(feature = "std", feature = "base64"))]
+pub mod os_string;
+pub mod patch;
+#[cfg(feature = "std")]
+pub mod path;
+#[cfg(feature = "std")]
+pub mod phone_number;
+#[cfg(feature = "std")]
+pub mod portable_path;
+#[cfg(feature = "std")]
+pub mod positional;
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod range;
+#[cfg(feature = "std")]
+pub mod ratio;
+pub mod redact;
+#[cfg(feature = "regex")]
+pub mod regex_pattern;
+pub mod rename_all;
+mod renamed;
+#[cfg(feature = "std")]
+pub mod rfc2822;
+#[cfg(feature = "std")]
+pub mod rfc3339;
+#[cfg(feature = "std")]
+pub mod rfc3339_offset;
+#[cfg(feature = "std")]
+pub mod set_dedup;
+#[cfg(feature = "std")]
+pub mod set_strict;
+#[cfg(feature = "std")]
+pub mod socket_addr;
+#[cfg(feature = "std")]
+pub mod sorted_map;
+#[cfg(feature = "std")]
+pub mod strictness;
 pub mod string_or_struct;
+#[cfg(feature = "std")]
+pub mod tagged_or_untagged;
+#[cfg(feature = "time")]
+pub mod time_format;
+#[cfg(feature = "std")]
+pub mod timestamp_micros;
+#[cfg(feature = "std")]
+pub mod timestamp_millis;
+#[cfg(feature = "std")]
+pub mod timestamp_nanos;
+#[cfg(feature = "std")]
+pub mod timestamp_or_string;
+#[cfg(feature = "std")]
+pub mod timestamp_secs;
+#[cfg(feature = "toml")]
+pub mod toml_datetime;
+pub mod trimmed;
+#[cfg(feature = "std")]
+pub mod unique_keys;
+#[cfg(feature = "std")]
+pub mod uppercase;
+#[cfg(feature = "url")]
+pub mod url_or_urls;
+#[cfg(feature = "url")]
+pub mod url_string;
+#[cfg(feature = "uuid")]
+pub mod uuid_bytes;
+#[cfg(feature = "uuid")]
+pub mod uuid_string;
+#[cfg(feature = "std")]
+pub mod vec_of;
 pub mod vec_or_one;
+#[cfg(feature = "std")]
+pub mod versioned;
+pub mod xs_boolean;
+
+#[cfg(feature = "derive")]
+pub use serde_stuff_derive::{stuff, Partial};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,168 @@
+//! Deserialize bytes or invalid UTF-8 strings to a `String`, lossily
+//!
+//! Invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`
+//! via [String::from_utf8_lossy], instead of rejecting the whole record.
+//! This is meant for log ingestion pipelines where one bad byte should not
+//! lose the event.
+//!
+//! In [strict](crate::strictness) mode, invalid UTF-8 is an error instead.
+//!
+//! Strings, byte buffers and sequences of `u8` are all accepted.  Values are
+//! serialized as ordinary strings.
+//!
+//! The value is requested with `deserialize_bytes`, which lets formats such
+//! as `serde_json` hand over the raw string contents before UTF-8 validation.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::lossy_string")]
+//!     pub message: String,
+//! }
+//! ```
+//! The following will both deserialize to `Outer`
+//! ```json
+//! {
+//!     "message": "hello"
+//! }
+//! ```
+//! ```json
+//! {
+//!     "message": [104, 101, 108, 108, 111]
+//! }
+//! ```
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::str::Utf8Error;
+
+use crate::strictness;
+
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(v)
+}
+
+fn invalid_utf8<E: de::Error>(err: Utf8Error) -> E {
+    E::custom(format!("invalid UTF-8 at byte {}", err.valid_up_to()))
+}
+
+fn replaced_utf8(err: Utf8Error) -> String {
+    format!("replaced invalid UTF-8 at byte {}", err.valid_up_to())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    struct LossyString;
+
+    impl<'de> Visitor<'de> for LossyString {
+        type Value = String;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a string, bytes or a sequence of bytes")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<String, E>
+        where
+            E: de::Error,
+        {
+            Ok(value.to_string())
+        }
+
+        fn visit_string<E>(self, value: String) -> Result<String, E>
+        where
+            E: de::Error,
+        {
+            Ok(value)
+        }
+
+        fn visit_bytes<E>(self, value: &[u8]) -> Result<String, E>
+        where
+            E: de::Error,
+        {
+            match std::str::from_utf8(value) {
+                Ok(value) => Ok(value.to_string()),
+                Err(err) if strictness::is_strict() => Err(invalid_utf8(err)),
+                Err(err) => {
+                    strictness::repaired(replaced_utf8(err));
+                    Ok(String::from_utf8_lossy(value).into_owned())
+                }
+            }
+        }
+
+        fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<String, E>
+        where
+            E: de::Error,
+        {
+            match String::from_utf8(value) {
+                Ok(value) => Ok(value),
+                Err(err) if strictness::is_strict() => Err(invalid_utf8(err.utf8_error())),
+                Err(err) => {
+                    strictness::repaired(replaced_utf8(err.utf8_error()));
+                    Ok(String::from_utf8_lossy(err.as_bytes()).into_owned())
+                }
+            }
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<String, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(byte) = seq.next_element::<u8>()? {
+                bytes.push(byte);
+            }
+            self.visit_byte_buf(bytes)
+        }
+    }
+
+    d.deserialize_bytes(LossyString)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::lossy_string")]
+        pub message: String,
+    }
+
+    #[test]
+    fn deserialize_string() {
+        let outer = Outer {
+            message: "héllo".to_string(),
+        };
+        let result: Outer = serde_json::from_str(r#"{"message": "héllo"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_invalid_utf8() {
+        let mut json = br#"{"message": "bad "#.to_vec();
+        json.push(0xff);
+        json.extend_from_slice(br#" byte"}"#);
+        let result: Outer = serde_json::from_slice(&json).expect("Oops!");
+        assert_eq!(result.message, "bad \u{FFFD} byte");
+    }
+
+    #[test]
+    fn deserialize_byte_array() {
+        let result: Outer = serde_json::from_str(r#"{"message": [104, 105, 255]}"#).expect("Oops!");
+        assert_eq!(result.message, "hi\u{FFFD}");
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            message: "hi".to_string(),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"message":"hi"}"#);
+    }
+}
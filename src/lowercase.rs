@@ -0,0 +1,124 @@
+//! Normalize a `String` to lowercase on deserialize
+//!
+//! Use this for case-insensitive identifiers such as email addresses and
+//! hostnames.  By default only the deserialized value is normalized, and
+//! values serialize unchanged.  The companion modules cover the other cases:
+//!
+//! - `lowercase::both` also normalizes on serialize.
+//! - `lowercase::keys` normalizes the keys of a map with `String` keys, such
+//!   as `HashMap<String, V>`.  If two keys normalize to the same value, the
+//!   map's own semantics decide which entry is kept.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::collections::HashMap;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::lowercase")]
+//!     pub id: String,
+//!     #[serde(with = "serde_stuff::lowercase::keys")]
+//!     pub tags: HashMap<String, String>,
+//! }
+//! ```
+//! The following will deserialize `id` to `"jane@example.com"`
+//! ```json
+//! {
+//!     "id": "Jane@Example.COM",
+//!     "tags": {}
+//! }
+//! ```
+
+use serde::{Deserializer, Serializer};
+
+fn convert(value: &str) -> String {
+    value.to_lowercase()
+}
+
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(v)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    crate::case::deserialize(d, convert)
+}
+
+/// Normalize on both deserialize and serialize.
+pub mod both {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+        crate::case::serialize(v, s, super::convert)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+/// Normalize the keys of a map on deserialize.
+pub mod keys {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, M: Serialize>(v: &M, s: S) -> Result<S::Ok, S::Error> {
+        v.serialize(s)
+    }
+
+    pub fn deserialize<'de, D, V, M>(d: D) -> Result<M, D::Error>
+    where
+        D: Deserializer<'de>,
+        V: Deserialize<'de>,
+        M: FromIterator<(String, V)>,
+    {
+        crate::case::deserialize_keys(d, super::convert)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::lowercase")]
+        pub id: String,
+        #[serde(with = "crate::lowercase::both")]
+        pub other: String,
+        #[serde(with = "crate::lowercase::keys")]
+        pub map: BTreeMap<String, u32>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "id": "Jane@Example.COM",
+            "other": "Jane@Example.COM",
+            "map": { "Web-01": 1 }
+        }"#;
+        let outer = Outer {
+            id: "jane@example.com".to_string(),
+            other: "jane@example.com".to_string(),
+            map: BTreeMap::from([("web-01".to_string(), 1)]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            id: "Jane@Example.COM".to_string(),
+            other: "Jane@Example.COM".to_string(),
+            map: BTreeMap::from([("Web-01".to_string(), 1)]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"id":"Jane@Example.COM","other":"jane@example.com","map":{"Web-01":1}}"#
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Serialize and Deserialize maps with `Display` and `FromStr` keys
+//!
+//! Serde only writes map keys that serialize as strings or numbers, so a
+//! `HashMap<Level, V>` keyed by an enum, or by a newtype such as
+//! `UserId(String)`, is rejected by JSON.  This module writes every key
+//! with its `Display` form, and parses it back with `FromStr`, so keys
+//! don't need `Serialize` or `Deserialize` at all.  A key that doesn't
+//! parse is named in the error.
+//!
+//! Keys that a format gives as numbers, such as in YAML, are accepted too.
+//! The [int_keys](crate::int_keys) module is the same conversion, for
+//! integer keys.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use std::fmt;
+//! use std::str::FromStr;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Eq, Hash)]
+//! pub enum Level {
+//!     Low,
+//!     High,
+//! }
+//!
+//! impl fmt::Display for Level {
+//!     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!         f.write_str(match self {
+//!             Level::Low => "low",
+//!             Level::High => "high",
+//!         })
+//!     }
+//! }
+//!
+//! impl FromStr for Level {
+//!     type Err = String;
+//!
+//!     fn from_str(s: &str) -> Result<Self, String> {
+//!         match s {
+//!             "low" => Ok(Level::Low),
+//!             "high" => Ok(Level::High),
+//!             _ => Err(format!("unknown level \"{}\"", s)),
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::map_key_fromstr")]
+//!     pub limits: HashMap<Level, u32>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "limits": {"low": 10, "high": 100}
+//! }
+//! ```
+
+use serde::de::{self, DeserializeSeed, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+pub fn serialize<S, M, K, V>(v: &M, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    for<'a> &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Display,
+    V: Serialize,
+{
+    let entries = v.into_iter();
+    let mut map = s.serialize_map(Some(entries.size_hint().0))?;
+    for (key, value) in entries {
+        map.serialize_entry(&key.to_string(), value)?;
+    }
+    map.end()
+}
+
+/// A key given as a string or a number.
+struct Key<K>(PhantomData<K>);
+
+impl<'de, K> DeserializeSeed<'de> for Key<K>
+where
+    K: FromStr,
+    K::Err: Display,
+{
+    type Value = K;
+
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<K, D::Error> {
+        d.deserialize_str(self)
+    }
+}
+
+impl<'de, K> Visitor<'de> for Key<K>
+where
+    K: FromStr,
+    K::Err: Display,
+{
+    type Value = K;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string or integer key")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<K, E>
+    where
+        E: de::Error,
+    {
+        value
+            .parse()
+            .map_err(|e| E::custom(format!("invalid map key \"{}\": {}", value, e)))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<K, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<K, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(&value.to_string())
+    }
+}
+
+pub fn deserialize<'de, D, M, K, V>(d: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: FromIterator<(K, V)>,
+    K: FromStr,
+    K::Err: Display,
+    V: Deserialize<'de>,
+{
+    struct KeysVisitor<M, K, V>(PhantomData<(M, K, V)>);
+
+    impl<'de, M, K, V> Visitor<'de> for KeysVisitor<M, K, V>
+    where
+        M: FromIterator<(K, V)>,
+        K: FromStr,
+        K::Err: Display,
+        V: Deserialize<'de>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<M, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+            while let Some(key) = map.next_key_seed(Key(PhantomData))? {
+                entries.push((key, map.next_value()?));
+            }
+            Ok(entries.into_iter().collect())
+        }
+    }
+
+    d.deserialize_map(KeysVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+    use std::fmt;
+    use std::str::FromStr;
+
+    #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub enum Level {
+        Low,
+        High,
+    }
+
+    impl fmt::Display for Level {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(match self {
+                Level::Low => "low",
+                Level::High => "high",
+            })
+        }
+    }
+
+    impl FromStr for Level {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, String> {
+            match s {
+                "low" => Ok(Level::Low),
+                "high" => Ok(Level::High),
+                _ => Err(format!("unknown level \"{}\"", s)),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct UserId(String);
+
+    impl fmt::Display for UserId {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "user:{}", self.0)
+        }
+    }
+
+    impl FromStr for UserId {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, String> {
+            s.strip_prefix("user:")
+                .map(|id| UserId(id.to_string()))
+                .ok_or_else(|| "missing \"user:\" prefix".to_string())
+        }
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::map_key_fromstr")]
+        pub limits: BTreeMap<Level, u32>,
+        #[serde(with = "crate::map_key_fromstr")]
+        pub owners: HashMap<UserId, bool>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{"limits":{"low":10,"high":100},"owners":{"user:ann":true}}"#;
+        let outer = Outer {
+            limits: BTreeMap::from([(Level::Low, 10), (Level::High, 100)]),
+            owners: HashMap::from([(UserId("ann".to_string()), true)]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"limits":{"medium":10},"owners":{}}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(
+            err.contains("invalid map key \"medium\": unknown level \"medium\""),
+            "{}",
+            err
+        );
+
+        let json = r#"{"limits":{},"owners":{"ann":true}}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("invalid map key \"ann\""), "{}", err);
+    }
+}
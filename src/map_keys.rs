@@ -0,0 +1,179 @@
+//! Apply a `serialize`/`deserialize` module to the keys of a map
+//!
+//! Serde's `with` attribute applies a module to a whole field, so a module
+//! such as [base64](crate::base64) can't reach the keys of a
+//! `HashMap<Vec<u8>, V>`.  The [map_keys!](crate::map_keys!) macro
+//! generates a module that runs the given module on each key, and
+//! serializes the values as usual:
+//!
+//! ```rust
+//! # #[cfg(feature = "base64")]
+//! serde_stuff::map_keys!(pub base64_keys, serde_stuff::base64, Vec<u8>);
+//! ```
+//!
+//! The arguments are the visibility and name of the module to declare, the
+//! path of the module to apply, and the key type.  The path must start from
+//! `crate` or a crate name, since it is used from inside the new module.
+//! Types declared in the enclosing module are in scope.
+//!
+//! The generated module works with any map that iterates as `(&K, &V)` and
+//! collects from `(K, V)`, such as `HashMap`, `BTreeMap` and `IndexMap`.
+//! Entries are read in order, so an `IndexMap` keeps it.  Keys must still
+//! serialize as something the format accepts as a key, which for
+//! JSON means a string.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! # #[cfg(feature = "base64")]
+//! serde_stuff::map_keys!(base64_keys, serde_stuff::base64, Vec<u8>);
+//!
+//! # #[cfg(feature = "base64")]
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "base64_keys")]
+//!     pub owners: HashMap<Vec<u8>, String>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "owners": {"3q2-7w==": "alice"}
+//! }
+//! ```
+
+/// The `Key` and `OwnedKey` wrappers that run a module on a map key, shared
+/// by [map_keys!](crate::map_keys!) and [map_of!](crate::map_of!).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! map_key_wrappers {
+    ($($module:ident)::+, $ty:ty) => {
+        struct Key<'a>(&'a $ty);
+
+        impl serde::Serialize for Key<'_> {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                $($module)::+::serialize(self.0, s)
+            }
+        }
+
+        struct OwnedKey($ty);
+
+        impl<'de> serde::Deserialize<'de> for OwnedKey {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                $($module)::+::deserialize(d).map(OwnedKey)
+            }
+        }
+    };
+}
+
+/// Generate a serde `with` module that applies a module to map keys.
+#[macro_export]
+macro_rules! map_keys {
+    ($vis:vis $name:ident, $($module:ident)::+, $ty:ty) => {
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::map_key_wrappers!($($module)::+, $ty);
+
+            #[allow(dead_code)]
+            pub fn serialize<S, M, V>(v: &M, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                for<'a> &'a M: IntoIterator<Item = (&'a $ty, &'a V)>,
+                V: serde::Serialize,
+            {
+                s.collect_map(v.into_iter().map(|(k, v)| (Key(k), v)))
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D, M, V>(d: D) -> Result<M, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                M: FromIterator<($ty, V)>,
+                V: serde::Deserialize<'de>,
+            {
+                let entries = $crate::ordered_map::deserialize::<D, OwnedKey, V>(d)?;
+                Ok(entries.into_iter().map(|(k, v)| (k.0, v)).collect())
+            }
+        }
+    };
+}
+
+#[cfg(all(test, feature = "base64"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+
+    crate::map_keys!(base64_keys, crate::base64, Vec<u8>);
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "base64_keys")]
+        pub owners: HashMap<Vec<u8>, String>,
+        #[serde(with = "base64_keys")]
+        pub sizes: BTreeMap<Vec<u8>, u32>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"owners": {"3q2-7w==": "alice"}, "sizes": {"AQI=": 2, "AA==": 1}}"#;
+        let outer = Outer {
+            owners: HashMap::from([(vec![0xde, 0xad, 0xbe, 0xef], "alice".to_string())]),
+            sizes: BTreeMap::from([(vec![1, 2], 2), (vec![0], 1)]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            owners: HashMap::from([(vec![0xde, 0xad, 0xbe, 0xef], "alice".to_string())]),
+            sizes: BTreeMap::from([(vec![0], 1), (vec![1, 2], 2)]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"owners":{"3q2-7w==":"alice"},"sizes":{"AA==":1,"AQI=":2}}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"owners": {"not base64!": "alice"}, "sizes": {}}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let outer = Outer {
+            owners: HashMap::from([(vec![7], "bob".to_string())]),
+            sizes: BTreeMap::from([(vec![], 0)]),
+        };
+        let bytes = bincode::serialize(&outer).expect("Oops!");
+        let result: Outer = bincode::deserialize(&bytes).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn indexmap() {
+        use indexmap::IndexMap;
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct Ordered {
+            #[serde(with = "base64_keys")]
+            pub owners: IndexMap<Vec<u8>, String>,
+        }
+
+        let json = r#"{"owners":{"AQI=":"bob","AA==":"alice"}}"#;
+        let result: Ordered = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.owners.keys().next(), Some(&vec![1, 2]));
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+}
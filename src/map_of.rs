@@ -0,0 +1,194 @@
+//! Apply `serialize`/`deserialize` modules to both the keys and the values of a map
+//!
+//! [map_keys](crate::map_keys) reaches the keys of a map and
+//! [map_values](crate::map_values) the values, but a field only takes one
+//! `with` module.  The [map_of!](crate::map_of!) macro generates a module
+//! that runs one module on each key and another on each value, so a
+//! `HashMap<Vec<u8>, SystemTime>` can have [base64](crate::base64) keys and
+//! epoch [timestamp_secs](crate::timestamp_secs) values.
+//!
+//! The arguments are the visibility and name of the module to declare, the
+//! path of the key module and the key type, `=>`, then the path of the value
+//! module and either the value type or, for modules that are generic over a
+//! trait, `T:` and the trait:
+//!
+//! ```rust
+//! # #[cfg(feature = "base64")]
+//! serde_stuff::map_of!(
+//!     pub epoch_by_key,
+//!     serde_stuff::base64, Vec<u8> =>
+//!     serde_stuff::timestamp_secs, T: serde_stuff::epoch::EpochTime
+//! );
+//! # #[cfg(feature = "base64")]
+//! serde_stuff::map_of!(
+//!     pub blobs_by_key,
+//!     serde_stuff::base64, Vec<u8> => serde_stuff::base64, Vec<u8>
+//! );
+//! ```
+//!
+//! The paths must start from `crate` or a crate name, since they are used
+//! from inside the new module.  Types declared in the enclosing module are
+//! in scope.  As with [map_keys](crate::map_keys), the generated module
+//! works with any map that iterates as `(&K, &V)` and collects from
+//! `(K, V)`, and keys must still serialize as something the format accepts
+//! as a key.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use std::time::SystemTime;
+//! use serde::{Deserialize, Serialize};
+//!
+//! # #[cfg(feature = "base64")]
+//! serde_stuff::map_of!(
+//!     epoch_by_key,
+//!     serde_stuff::base64, Vec<u8> =>
+//!     serde_stuff::timestamp_secs, T: serde_stuff::epoch::EpochTime
+//! );
+//!
+//! # #[cfg(feature = "base64")]
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "epoch_by_key")]
+//!     pub rotated: HashMap<Vec<u8>, SystemTime>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "rotated": {"3q2-7w==": 1700000000}
+//! }
+//! ```
+
+/// Generate a serde `with` module that applies modules to map keys and values.
+#[macro_export]
+macro_rules! map_of {
+    (
+        $vis:vis $name:ident,
+        $($key:ident)::+, $kty:ty =>
+        $($value:ident)::+, T: $bound:path
+    ) => {
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::map_key_wrappers!($($key)::+, $kty);
+            $crate::map_value_wrappers!($($value)::+, T: $bound);
+
+            #[allow(dead_code)]
+            pub fn serialize<S, M, T>(v: &M, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                for<'a> &'a M: IntoIterator<Item = (&'a $kty, &'a T)>,
+                T: $bound,
+            {
+                s.collect_map(v.into_iter().map(|(k, v)| (Key(k), Value(v))))
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D, M, T>(d: D) -> Result<M, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                M: FromIterator<($kty, T)>,
+                T: $bound,
+            {
+                let entries = $crate::ordered_map::deserialize::<D, OwnedKey, OwnedValue<T>>(d)?;
+                Ok(entries.into_iter().map(|(k, v)| (k.0, v.0)).collect())
+            }
+        }
+    };
+    (
+        $vis:vis $name:ident,
+        $($key:ident)::+, $kty:ty =>
+        $($value:ident)::+, $vty:ty
+    ) => {
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::map_key_wrappers!($($key)::+, $kty);
+            $crate::map_value_wrappers!($($value)::+, $vty);
+
+            #[allow(dead_code)]
+            pub fn serialize<S, M>(v: &M, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                for<'a> &'a M: IntoIterator<Item = (&'a $kty, &'a $vty)>,
+            {
+                s.collect_map(v.into_iter().map(|(k, v)| (Key(k), Value(v))))
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D, M>(d: D) -> Result<M, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                M: FromIterator<($kty, $vty)>,
+            {
+                let entries = $crate::ordered_map::deserialize::<D, OwnedKey, OwnedValue>(d)?;
+                Ok(entries.into_iter().map(|(k, v)| (k.0, v.0)).collect())
+            }
+        }
+    };
+}
+
+#[cfg(all(test, feature = "base64"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeMap, HashMap};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    crate::map_of!(
+        epoch_by_key,
+        crate::base64, Vec<u8> =>
+        crate::timestamp_secs, T: crate::epoch::EpochTime
+    );
+    crate::map_of!(blobs_by_key, crate::base64, Vec<u8> => crate::base64, Vec<u8>);
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "epoch_by_key")]
+        pub rotated: HashMap<Vec<u8>, SystemTime>,
+        #[serde(with = "blobs_by_key")]
+        pub blobs: BTreeMap<Vec<u8>, Vec<u8>>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"rotated": {"3q2-7w==": 1700000000}, "blobs": {"AA==": "AQI="}}"#;
+        let outer = Outer {
+            rotated: HashMap::from([(
+                vec![0xde, 0xad, 0xbe, 0xef],
+                UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            )]),
+            blobs: BTreeMap::from([(vec![0], vec![1, 2])]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            rotated: HashMap::from([(vec![1, 2], UNIX_EPOCH)]),
+            blobs: BTreeMap::from([(vec![1, 2], vec![0]), (vec![0], vec![])]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"rotated":{"AQI=":0},"blobs":{"AA==":"","AQI=":"AA=="}}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_key() {
+        let json = r#"{"rotated": {"not base64!": 0}, "blobs": {}}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+    }
+
+    #[test]
+    fn deserialize_invalid_value() {
+        let json = r#"{"rotated": {}, "blobs": {"AA==": "not base64!"}}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+    }
+}
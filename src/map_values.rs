@@ -0,0 +1,228 @@
+//! Apply a `serialize`/`deserialize` module to the values of a map
+//!
+//! The companion of [map_keys](crate::map_keys).  The
+//! [map_values!](crate::map_values!) macro generates a module that runs the
+//! given module on each value, and serializes the keys as usual, so a
+//! `HashMap<String, Vec<u8>>` can hold [base64](crate::base64) values, or a
+//! `HashMap<String, SystemTime>` epoch [timestamp_secs](crate::timestamp_secs)
+//! values.
+//!
+//! The arguments are the visibility and name of the module to declare, the
+//! path of the module to apply, and either the value type or, for modules
+//! that are generic over a trait, `T:` and the trait:
+//!
+//! ```rust
+//! # #[cfg(feature = "base64")]
+//! serde_stuff::map_values!(pub base64_values, serde_stuff::base64, Vec<u8>);
+//! serde_stuff::map_values!(
+//!     pub epoch_values,
+//!     serde_stuff::timestamp_secs,
+//!     T: serde_stuff::epoch::EpochTime
+//! );
+//! ```
+//!
+//! The path must start from `crate` or a crate name, since it is used from
+//! inside the new module.  Types declared in the enclosing module are in
+//! scope.  The generated module works with any map that iterates as
+//! `(&K, &V)` and collects from `(K, V)`, such as `HashMap`, `BTreeMap` and
+//! `IndexMap`, which keeps its order.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use std::time::SystemTime;
+//! use serde::{Deserialize, Serialize};
+//!
+//! serde_stuff::map_values!(
+//!     epoch_values,
+//!     serde_stuff::timestamp_secs,
+//!     T: serde_stuff::epoch::EpochTime
+//! );
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "epoch_values")]
+//!     pub last_seen: HashMap<String, SystemTime>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "last_seen": {"alice": 1700000000, "bob": 1700003600}
+//! }
+//! ```
+
+/// The `Value` and `OwnedValue` wrappers that run a module on a map value,
+/// shared by [map_values!](crate::map_values!) and [map_of!](crate::map_of!).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! map_value_wrappers {
+    ($($module:ident)::+, T: $bound:path) => {
+        struct Value<'a, T>(&'a T);
+
+        impl<T: $bound> serde::Serialize for Value<'_, T> {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                $($module)::+::serialize(self.0, s)
+            }
+        }
+
+        struct OwnedValue<T>(T);
+
+        impl<'de, T: $bound> serde::Deserialize<'de> for OwnedValue<T> {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                $($module)::+::deserialize(d).map(OwnedValue)
+            }
+        }
+    };
+    ($($module:ident)::+, $ty:ty) => {
+        struct Value<'a>(&'a $ty);
+
+        impl serde::Serialize for Value<'_> {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                $($module)::+::serialize(self.0, s)
+            }
+        }
+
+        struct OwnedValue($ty);
+
+        impl<'de> serde::Deserialize<'de> for OwnedValue {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                $($module)::+::deserialize(d).map(OwnedValue)
+            }
+        }
+    };
+}
+
+/// Generate a serde `with` module that applies a module to map values.
+#[macro_export]
+macro_rules! map_values {
+    ($vis:vis $name:ident, $($module:ident)::+, T: $bound:path) => {
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::map_value_wrappers!($($module)::+, T: $bound);
+
+            #[allow(dead_code)]
+            pub fn serialize<S, M, K, T>(v: &M, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                for<'a> &'a M: IntoIterator<Item = (&'a K, &'a T)>,
+                K: serde::Serialize,
+                T: $bound,
+            {
+                s.collect_map(v.into_iter().map(|(k, v)| (k, Value(v))))
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D, M, K, T>(d: D) -> Result<M, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                M: FromIterator<(K, T)>,
+                K: serde::Deserialize<'de>,
+                T: $bound,
+            {
+                let entries = $crate::ordered_map::deserialize::<D, K, OwnedValue<T>>(d)?;
+                Ok(entries.into_iter().map(|(k, v)| (k, v.0)).collect())
+            }
+        }
+    };
+    ($vis:vis $name:ident, $($module:ident)::+, $ty:ty) => {
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::map_value_wrappers!($($module)::+, $ty);
+
+            #[allow(dead_code)]
+            pub fn serialize<S, M, K>(v: &M, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                for<'a> &'a M: IntoIterator<Item = (&'a K, &'a $ty)>,
+                K: serde::Serialize,
+            {
+                s.collect_map(v.into_iter().map(|(k, v)| (k, Value(v))))
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D, M, K>(d: D) -> Result<M, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                M: FromIterator<(K, $ty)>,
+                K: serde::Deserialize<'de>,
+            {
+                let entries = $crate::ordered_map::deserialize::<D, K, OwnedValue>(d)?;
+                Ok(entries.into_iter().map(|(k, v)| (k, v.0)).collect())
+            }
+        }
+    };
+}
+
+#[cfg(all(test, feature = "base64"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    crate::map_values!(base64_values, crate::base64, Vec<u8>);
+    crate::map_values!(epoch_values, crate::timestamp_secs, T: crate::epoch::EpochTime);
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "base64_values")]
+        pub blobs: BTreeMap<String, Vec<u8>>,
+        #[serde(with = "epoch_values")]
+        pub last_seen: HashMap<String, SystemTime>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"blobs": {"key": "3q2-7w=="}, "last_seen": {"alice": 1700000000}}"#;
+        let outer = Outer {
+            blobs: BTreeMap::from([("key".to_string(), vec![0xde, 0xad, 0xbe, 0xef])]),
+            last_seen: HashMap::from([(
+                "alice".to_string(),
+                UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            )]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            blobs: BTreeMap::from([("b".to_string(), vec![1, 2]), ("a".to_string(), vec![0])]),
+            last_seen: HashMap::from([("bob".to_string(), UNIX_EPOCH)]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"blobs":{"a":"AA==","b":"AQI="},"last_seen":{"bob":0}}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"blobs": {}, "last_seen": {"alice": "yesterday"}}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn indexmap() {
+        use indexmap::IndexMap;
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct Ordered {
+            #[serde(with = "base64_values")]
+            pub blobs: IndexMap<String, Vec<u8>>,
+        }
+
+        let json = r#"{"blobs":{"zed":"AQI=","alice":"AA=="}}"#;
+        let result: Ordered = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+}
@@ -0,0 +1,163 @@
+//! Deserialize repeated map keys into `HashMap<K, Vec<V>>`
+//!
+//! Query strings, HTTP headers and similar documents repeat a key for
+//! each of its values, as in `{"tag": "a", "tag": "b"}`.  Serde keeps the
+//! last one, and [unique_keys](crate::unique_keys) rejects them.  This
+//! module collects every value, in order, under its key.
+//!
+//! On serialize, each value is written as its own entry with the key
+//! repeated, which formats such as JSON allow.  A key with no values isn't
+//! written.
+//!
+//! [MultiMap] is implemented for `HashMap<K, Vec<V>>` and
+//! `BTreeMap<K, Vec<V>>`, and for `IndexMap<K, Vec<V>>` with the `indexmap`
+//! feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::multimap")]
+//!     pub query: HashMap<String, Vec<String>>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`, with `query["tag"]` holding
+//! `["a", "b"]`
+//! ```json
+//! {
+//!     "query": {"tag": "a", "page": "2", "tag": "b"}
+//! }
+//! ```
+
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+/// Maps that collect every value of a key.
+pub trait MultiMap<K, V>: Default {
+    /// Add `value` to the values of `key`.
+    fn push(&mut self, key: K, value: V);
+}
+
+impl<K: Eq + Hash, V, H: BuildHasher + Default> MultiMap<K, V> for HashMap<K, Vec<V>, H> {
+    fn push(&mut self, key: K, value: V) {
+        self.entry(key).or_default().push(value);
+    }
+}
+
+impl<K: Ord, V> MultiMap<K, V> for BTreeMap<K, Vec<V>> {
+    fn push(&mut self, key: K, value: V) {
+        self.entry(key).or_default().push(value);
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K: Eq + Hash, V, H: BuildHasher + Default> MultiMap<K, V>
+    for indexmap::IndexMap<K, Vec<V>, H>
+{
+    fn push(&mut self, key: K, value: V) {
+        self.entry(key).or_default().push(value);
+    }
+}
+
+pub fn serialize<S, M, K, V>(v: &M, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    for<'a> &'a M: IntoIterator<Item = (&'a K, &'a Vec<V>)>,
+    K: Serialize,
+    V: Serialize,
+{
+    let mut map = s.serialize_map(None)?;
+    for (key, values) in v {
+        for value in values {
+            map.serialize_entry(key, value)?;
+        }
+    }
+    map.end()
+}
+
+pub fn deserialize<'de, D, M, K, V>(d: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: MultiMap<K, V>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    struct MultiVisitor<M, K, V>(PhantomData<(M, K, V)>);
+
+    impl<'de, M, K, V> Visitor<'de> for MultiVisitor<M, K, V>
+    where
+        M: MultiMap<K, V>,
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<M, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut out = M::default();
+            while let Some((key, value)) = map.next_entry::<K, V>()? {
+                out.push(key, value);
+            }
+            Ok(out)
+        }
+    }
+
+    d.deserialize_map(MultiVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::multimap")]
+        pub query: HashMap<String, Vec<String>>,
+        #[serde(with = "crate::multimap")]
+        pub headers: BTreeMap<String, Vec<u32>>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "query": {"tag": "a", "page": "2", "tag": "b"},
+            "headers": {"x": 1, "x": 2, "x": 3}
+        }"#;
+        let outer = Outer {
+            query: HashMap::from([
+                ("tag".to_string(), vec!["a".to_string(), "b".to_string()]),
+                ("page".to_string(), vec!["2".to_string()]),
+            ]),
+            headers: BTreeMap::from([("x".to_string(), vec![1, 2, 3])]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            query: HashMap::from([("tag".to_string(), vec![])]),
+            headers: BTreeMap::from([("b".to_string(), vec![3]), ("a".to_string(), vec![1, 2])]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"query":{},"headers":{"a":1,"a":2,"b":3}}"#);
+    }
+}
@@ -0,0 +1,233 @@
+//! Serialize and Deserialize an enum as either its name or its numeric code
+//!
+//! Some APIs send `"status": "active"` in one place and `"status": 2` in
+//! another.  This module accepts both, mapping through the [NameOrCode]
+//! trait.  It serializes the name, or the code with
+//! `name_or_code::code`.
+//!
+//! Accepting both needs a self describing format.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::name_or_code::NameOrCode;
+//!
+//! #[derive(Debug)]
+//! pub enum Status {
+//!     Inactive,
+//!     Active,
+//! }
+//!
+//! impl NameOrCode for Status {
+//!     fn name(&self) -> &str {
+//!         match self {
+//!             Status::Inactive => "inactive",
+//!             Status::Active => "active",
+//!         }
+//!     }
+//!
+//!     fn code(&self) -> i64 {
+//!         match self {
+//!             Status::Inactive => 1,
+//!             Status::Active => 2,
+//!         }
+//!     }
+//!
+//!     fn from_name(name: &str) -> Option<Self> {
+//!         match name {
+//!             "inactive" => Some(Status::Inactive),
+//!             "active" => Some(Status::Active),
+//!             _ => None,
+//!         }
+//!     }
+//!
+//!     fn from_code(code: i64) -> Option<Self> {
+//!         match code {
+//!             1 => Some(Status::Inactive),
+//!             2 => Some(Status::Active),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::name_or_code")]
+//!     pub status: Status,
+//!     #[serde(with = "serde_stuff::name_or_code::code")]
+//!     pub previous: Status,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "status": 2,
+//!     "previous": "inactive"
+//! }
+//! ```
+
+use serde::de::{self, Unexpected, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Enums with both a name and a numeric code for each variant.
+pub trait NameOrCode: Sized {
+    fn name(&self) -> &str;
+    fn code(&self) -> i64;
+    fn from_name(name: &str) -> Option<Self>;
+    fn from_code(code: i64) -> Option<Self>;
+}
+
+struct NameOrCodeVisitor<T>(PhantomData<T>);
+
+impl<'de, T: NameOrCode> Visitor<'de> for NameOrCodeVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a known name or code")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        T::from_name(value).ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        T::from_code(value).ok_or_else(|| E::invalid_value(Unexpected::Signed(value), &self))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        i64::try_from(value)
+            .ok()
+            .and_then(T::from_code)
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(value), &self))
+    }
+}
+
+pub fn serialize<S: Serializer, T: NameOrCode>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(v.name())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: NameOrCode>(d: D) -> Result<T, D::Error> {
+    d.deserialize_any(NameOrCodeVisitor(PhantomData))
+}
+
+/// Serialize the numeric code.
+pub mod code {
+    use super::NameOrCode;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, T: NameOrCode>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_i64(v.code())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: NameOrCode>(d: D) -> Result<T, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    use super::NameOrCode;
+
+    #[derive(PartialEq, Debug)]
+    pub enum Status {
+        Inactive,
+        Active,
+    }
+
+    impl NameOrCode for Status {
+        fn name(&self) -> &str {
+            match self {
+                Status::Inactive => "inactive",
+                Status::Active => "active",
+            }
+        }
+
+        fn code(&self) -> i64 {
+            match self {
+                Status::Inactive => 1,
+                Status::Active => 2,
+            }
+        }
+
+        fn from_name(name: &str) -> Option<Self> {
+            match name {
+                "inactive" => Some(Status::Inactive),
+                "active" => Some(Status::Active),
+                _ => None,
+            }
+        }
+
+        fn from_code(code: i64) -> Option<Self> {
+            match code {
+                1 => Some(Status::Inactive),
+                2 => Some(Status::Active),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::name_or_code")]
+        pub status: Status,
+        #[serde(with = "crate::name_or_code::code")]
+        pub previous: Status,
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            status: Status::Active,
+            previous: Status::Inactive,
+        };
+        for json in [
+            r#"{"status": 2, "previous": "inactive"}"#,
+            r#"{"status": "active", "previous": 1}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(&outer, &result);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            status: Status::Active,
+            previous: Status::Inactive,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"status":"active","previous":1}"#);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (json, msg) in [
+            (
+                r#"{"status": "paused", "previous": 1}"#,
+                "invalid value: string \"paused\", expected a known name or code",
+            ),
+            (
+                r#"{"status": 7, "previous": 1}"#,
+                "invalid value: integer `7`, expected a known name or code",
+            ),
+        ] {
+            let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Serialize a tuple or tuple struct as a map with named fields
+//!
+//! APIs that started with positional tuples, such as `[1.5, 2.0]`, often
+//! grow into named objects, such as `{"x": 1.5, "y": 2.0}`.  The
+//! [named_tuple!](crate::named_tuple!) macro generates a module, given the
+//! field names, that writes the named form, and reads either form:
+//!
+//! ```rust
+//! mod point {
+//!     serde_stuff::named_tuple!("x", "y");
+//! }
+//!
+//! serde_stuff::named_tuple!(pub range, "start", "end");
+//! ```
+//!
+//! A map must have every name, and no others.  The tuple must have one
+//! element per name.  Since the value is buffered as
+//! [Content](crate::content::Content), the format must be self describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! serde_stuff::named_tuple!(point, "x", "y");
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Point(f64, f64);
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "point")]
+//!     pub origin: Point,
+//!     #[serde(with = "point")]
+//!     pub size: (u32, u32),
+//! }
+//! ```
+//! The following will both deserialize to `Outer`, and it serializes to
+//! the first
+//! ```json
+//! {
+//!     "origin": {"x": 1.5, "y": 2.0},
+//!     "size": {"y": 480, "x": 640}
+//! }
+//! ```
+//! ```json
+//! {
+//!     "origin": [1.5, 2.0],
+//!     "size": [640, 480]
+//! }
+//! ```
+
+use serde::de::{self, DeserializeOwned};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::content::{Content, ContentDeserializer, ContentSerializer};
+
+/// Serialize the elements of `v` as a map with `names` as keys.
+pub fn serialize_with<S, T>(v: &T, s: S, names: &'static [&'static str]) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    match v.serialize(ContentSerializer::<S::Error>::new())? {
+        Content::Seq(items) if items.len() == names.len() => s.collect_map(names.iter().zip(items)),
+        Content::Seq(items) => Err(serde::ser::Error::custom(format!(
+            "expected {} elements, found {}",
+            names.len(),
+            items.len()
+        ))),
+        _ => Err(serde::ser::Error::custom("expected a tuple")),
+    }
+}
+
+/// Deserialize a tuple from a sequence, or from a map with `names` as keys.
+pub fn deserialize_with<'de, D, T>(d: D, names: &'static [&'static str]) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let content = match Content::deserialize(d)? {
+        Content::Map(entries) => {
+            let mut items: Vec<Option<Content>> = vec![None; names.len()];
+            for (key, value) in entries {
+                let key = key.as_str().unwrap_or_default().to_string();
+                let i = names
+                    .iter()
+                    .position(|name| *name == key)
+                    .ok_or_else(|| de::Error::unknown_field(&key, names))?;
+                if items[i].replace(value).is_some() {
+                    return Err(de::Error::duplicate_field(names[i]));
+                }
+            }
+            let items = items
+                .into_iter()
+                .zip(names)
+                .map(|(item, name)| item.ok_or_else(|| de::Error::missing_field(name)))
+                .collect::<Result<Vec<_>, _>>()?;
+            Content::Seq(items)
+        }
+        content => content,
+    };
+    T::deserialize(ContentDeserializer::<D::Error>::new(content))
+}
+
+/// Generate a serde `with` module for a tuple with named fields.
+///
+/// With only the field names, the functions are generated in place.  With a
+/// visibility and name first, a module of that name is declared.
+#[macro_export]
+macro_rules! named_tuple {
+    ($vis:vis $name:ident, $($field:literal),+ $(,)?) => {
+        $vis mod $name {
+            $crate::named_tuple!($($field),+);
+        }
+    };
+    ($($field:literal),+ $(,)?) => {
+        #[allow(dead_code)]
+        pub fn serialize<S, T>(v: &T, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+            T: serde::Serialize,
+        {
+            $crate::named_tuple::serialize_with(v, s, &[$($field),+])
+        }
+
+        #[allow(dead_code)]
+        pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+            T: serde::de::DeserializeOwned,
+        {
+            $crate::named_tuple::deserialize_with(d, &[$($field),+])
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    mod point {
+        crate::named_tuple!("x", "y");
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Point(f64, f64);
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "point")]
+        pub origin: Point,
+        #[serde(with = "point")]
+        pub size: (u32, u32),
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            origin: Point(1.5, 2.0),
+            size: (640, 480),
+        };
+        for json in [
+            r#"{"origin": {"x": 1.5, "y": 2.0}, "size": {"y": 480, "x": 640}}"#,
+            r#"{"origin": [1.5, 2.0], "size": [640, 480]}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(&outer, &result);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            origin: Point(1.5, 2.0),
+            size: (640, 480),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"origin":{"x":1.5,"y":2.0},"size":{"x":640,"y":480}}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (origin, msg) in [
+            (r#"{"x": 1}"#, "missing field `y`"),
+            (r#"{"x": 1, "y": 2, "z": 3}"#, "unknown field `z`"),
+            (r#"{"x": 1, "x": 2}"#, "duplicate field `x`"),
+            ("[1]", "invalid length 1"),
+        ] {
+            let json = format!(r#"{{"origin": {}, "size": [1, 2]}}"#, origin);
+            let err = serde_json::from_str::<Outer>(&json)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+
+    #[test]
+    fn serialize_invalid() {
+        mod triple {
+            crate::named_tuple!("a", "b", "c");
+        }
+
+        #[derive(Serialize)]
+        pub struct Pair {
+            #[serde(with = "triple")]
+            pub pair: (u8, u8),
+        }
+
+        let err = serde_json::to_string(&Pair { pair: (1, 2) }).unwrap_err();
+        assert!(err.to_string().contains("expected 3 elements, found 2"));
+    }
+}
@@ -0,0 +1,432 @@
+//! Convert between nested structs and flat maps with dotted keys
+//!
+//! Java properties files, environment variables and many key/value stores
+//! hold a nested config as a single level of keys, such as
+//! `server.port = 8080`.  [from_flat] regroups those keys into the nested
+//! maps that a derived struct expects, and then deserializes the struct:
+//!
+//! ```json
+//! {"server.host": "localhost", "server.port": "8080", "debug": "true"}
+//! ```
+//! is read as
+//! ```json
+//! {"server": {"host": "localhost", "port": "8080"}, "debug": "true"}
+//! ```
+//!
+//! Since these formats only hold strings, a string value also deserializes
+//! into a number or a boolean when the struct asks for one.  Numeric keys,
+//! as in `servers.0.host`, fill a `Vec` in index order.  Keys that are
+//! already nested are merged, and a key that is both a value and a table,
+//! such as `server` and `server.port`, is an error.
+//!
+//! [to_flat] goes the other way, for exporting to properties files, metrics
+//! labels and flat key/value stores.  Nested maps and structs become dotted
+//! keys, sequences use their indexes as keys, and `None` values are left
+//! out.  Empty maps and sequences have no keys, so they are left out too.
+//!
+//! [from_flat_with] and [to_flat_with] take another separator, such as
+//! `"__"` for environment variables.  As a `with` module, `nested` reads and
+//! writes a field as a flat map.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Server {
+//!     pub host: String,
+//!     pub port: u16,
+//! }
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Config {
+//!     pub server: Server,
+//!     pub debug: bool,
+//! }
+//!
+//! let json = r#"{"server.host": "localhost", "server.port": "8080", "debug": "true"}"#;
+//! let mut de = serde_json::Deserializer::from_str(json);
+//! let config: Config = serde_stuff::nested::from_flat(&mut de).unwrap();
+//! assert_eq!(config.server.port, 8080);
+//! ```
+//!
+//! ```rust
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! pub struct Server {
+//!     pub host: String,
+//!     pub ports: Vec<u16>,
+//! }
+//!
+//! let server = Server { host: "localhost".to_string(), ports: vec![80, 443] };
+//! let mut out = Vec::new();
+//! serde_stuff::nested::to_flat(&server, &mut serde_json::Serializer::new(&mut out)).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(out).unwrap(),
+//!     r#"{"host":"localhost","ports.0":80,"ports.1":443}"#
+//! );
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::content::{Content, ContentDeserializer, ContentSerializer};
+
+/// Insert `value` into `table` at `path`.
+fn insert(
+    table: &mut Vec<(Content, Content)>,
+    path: &[&str],
+    key: &str,
+    value: Content,
+) -> Result<(), String> {
+    let conflict = || format!("conflicting keys for \"{}\"", key);
+    let position = table.iter().position(|(k, _)| k.as_str() == Some(path[0]));
+    if path.len() == 1 {
+        return match (position, value) {
+            (None, value) => {
+                table.push((Content::String(path[0].to_string()), value));
+                Ok(())
+            }
+            (Some(i), Content::Map(entries)) => match &mut table[i].1 {
+                Content::Map(existing) => {
+                    for (k, v) in entries {
+                        let k = k.as_str().map(str::to_string).ok_or_else(conflict)?;
+                        insert(existing, &[k.as_str()], key, v)?;
+                    }
+                    Ok(())
+                }
+                _ => Err(conflict()),
+            },
+            (Some(_), _) => Err(conflict()),
+        };
+    }
+    let i = match position {
+        Some(i) => i,
+        None => {
+            table.push((
+                Content::String(path[0].to_string()),
+                Content::Map(Vec::new()),
+            ));
+            table.len() - 1
+        }
+    };
+    match &mut table[i].1 {
+        Content::Map(child) => insert(child, &path[1..], key, value),
+        _ => Err(conflict()),
+    }
+}
+
+/// Regroup the dotted keys of `entries`, and of any maps within them.
+fn expand(entries: Vec<(Content, Content)>, separator: &str) -> Result<Content, String> {
+    let mut table = Vec::with_capacity(entries.len());
+    for (key, value) in entries {
+        let key = match key {
+            Content::String(key) => key,
+            _ => return Err("expected string keys".to_string()),
+        };
+        let path: Vec<&str> = key.split(separator).collect();
+        if path.iter().any(|segment| segment.is_empty()) {
+            return Err(format!("invalid key \"{}\"", key));
+        }
+        let value = match value {
+            Content::Map(entries) => expand(entries, separator)?,
+            value => value,
+        };
+        insert(&mut table, &path, &key, value)?;
+    }
+    Ok(Content::Map(table))
+}
+
+/// Deserialize `T` from a flat map, splitting keys on `.`.
+pub fn from_flat<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    from_flat_with(d, ".")
+}
+
+/// Deserialize `T` from a flat map, splitting keys on `separator`.
+pub fn from_flat_with<'de, D, T>(d: D, separator: &str) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let nested = match Content::deserialize(d)? {
+        Content::Map(entries) => expand(entries, separator).map_err(serde::de::Error::custom)?,
+        _ => return Err(serde::de::Error::custom("expected a map")),
+    };
+    T::deserialize(ContentDeserializer::<D::Error>::lenient(nested))
+}
+
+/// Add the leaves of `content` to `out`, with keys starting with `prefix`.
+fn flatten(
+    content: Content,
+    prefix: &str,
+    separator: &str,
+    out: &mut Vec<(String, Content)>,
+) -> Result<(), String> {
+    let join = |key: &str| match prefix {
+        "" => key.to_string(),
+        _ => format!("{}{}{}", prefix, separator, key),
+    };
+    match content {
+        Content::Map(entries) => {
+            for (key, value) in entries {
+                let key = match key {
+                    Content::String(key) => key,
+                    Content::U64(key) => key.to_string(),
+                    Content::I64(key) => key.to_string(),
+                    Content::Bool(key) => key.to_string(),
+                    _ => return Err("map keys must be strings or numbers".to_string()),
+                };
+                flatten(value, &join(&key), separator, out)?;
+            }
+        }
+        Content::Seq(items) => {
+            for (i, item) in items.into_iter().enumerate() {
+                flatten(item, &join(&i.to_string()), separator, out)?;
+            }
+        }
+        Content::Unit => {}
+        leaf => out.push((prefix.to_string(), leaf)),
+    }
+    Ok(())
+}
+
+/// Serialize `v` as a flat map, joining keys with `.`.
+pub fn to_flat<S, T>(v: &T, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ?Sized + Serialize,
+{
+    to_flat_with(v, s, ".")
+}
+
+/// Serialize `v` as a flat map, joining keys with `separator`.
+pub fn to_flat_with<S, T>(v: &T, s: S, separator: &str) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ?Sized + Serialize,
+{
+    let content = v.serialize(ContentSerializer::<S::Error>::new())?;
+    if !matches!(content, Content::Map(_)) {
+        return Err(serde::ser::Error::custom("expected a struct or map"));
+    }
+    let mut entries = Vec::new();
+    flatten(content, "", separator, &mut entries).map_err(serde::ser::Error::custom)?;
+    s.collect_map(entries)
+}
+
+pub fn serialize<S, T>(v: &T, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    to_flat(v, s)
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    from_flat(d)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::HashMap;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Server {
+        pub host: String,
+        pub port: u16,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub enum Mode {
+        Fast,
+        Safe,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Config {
+        pub server: Server,
+        pub replicas: Vec<Server>,
+        pub debug: bool,
+        pub ratio: Option<f64>,
+        pub mode: Mode,
+        pub labels: HashMap<String, String>,
+    }
+
+    fn from_flat(json: &str) -> Result<Config, serde_json::Error> {
+        let mut de = serde_json::Deserializer::from_str(json);
+        super::from_flat(&mut de)
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "server.host": "localhost",
+            "server.port": "8080",
+            "replicas.1.host": "b",
+            "replicas.1.port": 2,
+            "replicas.0.host": "a",
+            "replicas.0.port": "1",
+            "debug": "true",
+            "ratio": "0.5",
+            "mode": "Safe",
+            "labels": {"app": "api"},
+            "labels.env": "prod"
+        }"#;
+        let config = Config {
+            server: Server {
+                host: "localhost".to_string(),
+                port: 8080,
+            },
+            replicas: vec![
+                Server {
+                    host: "a".to_string(),
+                    port: 1,
+                },
+                Server {
+                    host: "b".to_string(),
+                    port: 2,
+                },
+            ],
+            debug: true,
+            ratio: Some(0.5),
+            mode: Mode::Safe,
+            labels: HashMap::from([
+                ("app".to_string(), "api".to_string()),
+                ("env".to_string(), "prod".to_string()),
+            ]),
+        };
+        assert_eq!(from_flat(json).expect("Oops!"), config);
+    }
+
+    #[test]
+    fn separator() {
+        let json = r#"{"SERVER__HOST": "h", "SERVER__PORT": "1"}"#;
+        #[derive(Deserialize, PartialEq, Debug)]
+        #[serde(rename_all = "UPPERCASE")]
+        pub struct Env {
+            pub server: Server2,
+        }
+        #[derive(Deserialize, PartialEq, Debug)]
+        #[serde(rename_all = "UPPERCASE")]
+        pub struct Server2 {
+            pub host: String,
+            pub port: u16,
+        }
+        let mut de = serde_json::Deserializer::from_str(json);
+        let env: Env = super::from_flat_with(&mut de, "__").expect("Oops!");
+        assert_eq!(env.server.port, 1);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (json, msg) in [
+            (
+                r#"{"server": "x", "server.port": 1}"#,
+                "conflicting keys for \"server.port\"",
+            ),
+            (
+                r#"{"server.port": 1, "server.port": 2}"#,
+                "conflicting keys for \"server.port\"",
+            ),
+            (r#"{"server..port": 1}"#, "invalid key \"server..port\""),
+            (
+                r#"{"server.host": "h", "server.port": "http"}"#,
+                "invalid value: string \"http\"",
+            ),
+        ] {
+            let err = from_flat(json).unwrap_err().to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let config = Config {
+            server: Server {
+                host: "localhost".to_string(),
+                port: 8080,
+            },
+            replicas: vec![Server {
+                host: "a".to_string(),
+                port: 1,
+            }],
+            debug: false,
+            ratio: None,
+            mode: Mode::Fast,
+            labels: HashMap::from([("app".to_string(), "api".to_string())]),
+        };
+        let result = serde_json::to_string(&Flat(&config)).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"server.host":"localhost","server.port":8080,"replicas.0.host":"a","replicas.0.port":1,"debug":false,"mode":"Fast","labels.app":"api"}"#
+        );
+
+        let round_trip = from_flat(&result).expect("Oops!");
+        assert_eq!(round_trip, config);
+    }
+
+    #[test]
+    fn with_module() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::nested")]
+            pub server: Server,
+        }
+
+        let json = r#"{"server":{"host":"h","port":1}}"#;
+        let outer = Outer {
+            server: Server {
+                host: "h".to_string(),
+                port: 1,
+            },
+        };
+        assert_eq!(serde_json::to_string(&outer).expect("Oops!"), json);
+        let json = r#"{"server":{"host":"h","port":"1"}}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize_separator() {
+        struct Env<'a>(&'a Server);
+
+        impl Serialize for Env<'_> {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                super::to_flat_with(&HashMap::from([("server", self.0)]), s, "__")
+            }
+        }
+
+        let server = Server {
+            host: "h".to_string(),
+            port: 1,
+        };
+        let result = serde_json::to_string(&Env(&server)).expect("Oops!");
+        assert!(result.contains(r#""server__host":"h""#), "{}", result);
+        assert!(result.contains(r#""server__port":1"#), "{}", result);
+    }
+
+    #[test]
+    fn serialize_invalid() {
+        assert!(serde_json::to_string(&Flat(&42)).is_err());
+    }
+
+    struct Flat<'a, T>(&'a T);
+
+    impl<T: Serialize> Serialize for Flat<'_, T> {
+        fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            super::to_flat(self.0, s)
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Deserialize a `String`, rejecting empty or whitespace-only values
+//!
+//! This replaces the `NonEmptyString` newtype that otherwise gets written for
+//! almost every API model.  The value is not trimmed; combine with
+//! [trimmed](crate::trimmed) in your own module if that is needed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::non_empty_string")]
+//!     pub name: String,
+//! }
+//! ```
+//! The following fails with `invalid value: string " ", expected a non-empty string`
+//! ```json
+//! {
+//!     "name": " "
+//! }
+//! ```
+
+use serde::de::{self, Unexpected};
+use serde::{Deserialize, Deserializer, Serializer};
+
+pub(crate) fn validate<E: de::Error>(value: String) -> Result<String, E> {
+    if value.trim().is_empty() {
+        return Err(E::invalid_value(
+            Unexpected::Str(&value),
+            &"a non-empty string",
+        ));
+    }
+    Ok(value)
+}
+
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(v)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    validate(String::deserialize(d)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::non_empty_string")]
+        pub name: String,
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            name: "value".to_string(),
+        };
+        let result: Outer = serde_json::from_str(r#"{"name": "value"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_empty() {
+        let result = serde_json::from_str::<Outer>(r#"{"name": ""}"#);
+        assert!(result.is_err());
+        let result = serde_json::from_str::<Outer>(r#"{"name": " \t "}"#);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("expected a non-empty string"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Deserialize `null` to an empty map
+//!
+//! Kubernetes style APIs write `"labels": null` for a map with no entries,
+//! which a plain `HashMap` field rejects.  This module reads `null` as an
+//! empty map, so the field doesn't need to be an `Option`.  Add
+//! `#[serde(default)]` to also accept a missing field.
+//!
+//! - `null_as_empty_map` writes an empty map as `{}`.
+//! - `null_as_empty_map::empty_as_null` writes an empty map as `null`, for
+//!   APIs that expect it back that way.
+//!
+//! Any map that implements `Default` works, such as `HashMap`, `BTreeMap`,
+//! and `IndexMap` with the `indexmap` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(default, with = "serde_stuff::null_as_empty_map")]
+//!     pub labels: HashMap<String, String>,
+//! }
+//! ```
+//! The following will deserialize to `Outer { labels: HashMap::new() }`
+//! ```json
+//! {
+//!     "labels": null
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<S: Serializer, M: Serialize>(v: &M, s: S) -> Result<S::Ok, S::Error> {
+    v.serialize(s)
+}
+
+pub fn deserialize<'de, D, M>(d: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: Deserialize<'de> + Default,
+{
+    Ok(Option::<M>::deserialize(d)?.unwrap_or_default())
+}
+
+/// Also write an empty map as `null`.
+pub mod empty_as_null {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, M>(v: &M, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        M: Serialize,
+        for<'a> &'a M: IntoIterator,
+    {
+        match v.into_iter().next() {
+            None => s.serialize_none(),
+            Some(_) => s.serialize_some(v),
+        }
+    }
+
+    pub fn deserialize<'de, D, M>(d: D) -> Result<M, D::Error>
+    where
+        D: Deserializer<'de>,
+        M: Deserialize<'de> + Default,
+    {
+        super::deserialize(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(default, with = "crate::null_as_empty_map")]
+        pub labels: HashMap<String, String>,
+        #[serde(default, with = "crate::null_as_empty_map::empty_as_null")]
+        pub annotations: BTreeMap<String, String>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let empty = Outer {
+            labels: HashMap::new(),
+            annotations: BTreeMap::new(),
+        };
+        for json in [r#"{"labels": null, "annotations": null}"#, r#"{}"#] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(&empty, &result);
+        }
+
+        let json = r#"{"labels": {"app": "api"}, "annotations": {"a": "b"}}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.labels["app"], "api");
+        assert_eq!(result.annotations["a"], "b");
+    }
+
+    #[test]
+    fn serialize() {
+        let mut outer = Outer {
+            labels: HashMap::new(),
+            annotations: BTreeMap::new(),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"labels":{},"annotations":null}"#);
+
+        outer.annotations.insert("a".to_string(), "b".to_string());
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"labels":{},"annotations":{"a":"b"}}"#);
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn indexmap() {
+        use indexmap::IndexMap;
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct Ordered {
+            #[serde(with = "crate::null_as_empty_map::empty_as_null")]
+            pub labels: IndexMap<String, String>,
+        }
+
+        let result: Ordered = serde_json::from_str(r#"{"labels":null}"#).expect("Oops!");
+        assert!(result.labels.is_empty());
+
+        let json = r#"{"labels":{"zed":"1","alice":"2"}}"#;
+        let result: Ordered = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+}
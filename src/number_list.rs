@@ -0,0 +1,164 @@
+//! Serialize and Deserialize a `Vec<u32>` to a list string such as `"1,3-5,9"`
+//!
+//! This is the format used for CPU affinity lists, page selections and port
+//! sets.  Ranges are inclusive, and are expanded in place on deserialize.
+//! On serialize, runs of consecutive ascending numbers are compressed back
+//! into ranges.  The order of the `Vec` is otherwise preserved, so a list
+//! that round-trips is unchanged.  An empty string is an empty `Vec`.
+//!
+//! A list that expands to more than [MAX_LEN] numbers is an error, so a
+//! short string such as `"0-4294967295"` can not exhaust memory.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::number_list")]
+//!     pub cpus: Vec<u32>,
+//! }
+//! ```
+//! The following will deserialize to `Outer { cpus: vec![0, 2, 3, 4, 8] }`
+//! ```json
+//! {
+//!     "cpus": "0,2-4,8"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+/// The most numbers a list may expand to.
+pub const MAX_LEN: usize = 65_536;
+
+fn parse_number(value: &str, list: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| format!("invalid number \"{}\" in \"{}\"", value.trim(), list))
+}
+
+fn too_long(list: &str) -> String {
+    format!("\"{}\" expands to more than {} numbers", list, MAX_LEN)
+}
+
+fn parse(list: &str) -> Result<Vec<u32>, String> {
+    let mut result = Vec::new();
+    if list.trim().is_empty() {
+        return Ok(result);
+    }
+    for item in list.split(',') {
+        match item.split_once('-') {
+            Some((start, end)) => {
+                let start = parse_number(start, list)?;
+                let end = parse_number(end, list)?;
+                if start > end {
+                    return Err(format!(
+                        "range \"{}\" in \"{}\" is reversed",
+                        item.trim(),
+                        list
+                    ));
+                }
+                if (end - start) as usize >= MAX_LEN - result.len() {
+                    return Err(too_long(list));
+                }
+                result.extend(start..=end);
+            }
+            None => {
+                if result.len() == MAX_LEN {
+                    return Err(too_long(list));
+                }
+                result.push(parse_number(item, list)?)
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn compress(v: &[u32]) -> String {
+    let mut items = Vec::new();
+    let mut idx = 0;
+    while idx < v.len() {
+        let start = v[idx];
+        let mut end = start;
+        while idx + 1 < v.len() && end.checked_add(1) == Some(v[idx + 1]) {
+            idx += 1;
+            end = v[idx];
+        }
+        if start == end {
+            items.push(start.to_string());
+        } else {
+            items.push(format!("{}-{}", start, end));
+        }
+        idx += 1;
+    }
+    items.join(",")
+}
+
+pub fn serialize<S: Serializer>(v: &[u32], s: S) -> Result<S::Ok, S::Error> {
+    String::serialize(&compress(v), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u32>, D::Error> {
+    let list = String::deserialize(d)?;
+    parse(&list).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::number_list")]
+        pub items: Vec<u32>,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            items: vec![1, 3, 4, 5, 9, 10, 7],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"items":"1,3-5,9-10,7"}"#);
+
+        let outer = Outer { items: vec![] };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"items":""}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            items: vec![1, 3, 4, 5, 9],
+        };
+        let result: Outer = serde_json::from_str(r#"{"items": "1, 3-5 ,9"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+
+        let result: Outer = serde_json::from_str(r#"{"items": ""}"#).expect("Oops!");
+        assert!(result.items.is_empty());
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        assert!(serde_json::from_str::<Outer>(r#"{"items": "1,x"}"#).is_err());
+        assert!(serde_json::from_str::<Outer>(r#"{"items": "5-3"}"#).is_err());
+        assert!(serde_json::from_str::<Outer>(r#"{"items": "1,,2"}"#).is_err());
+    }
+
+    #[test]
+    fn deserialize_too_long() {
+        let err = serde_json::from_str::<Outer>(r#"{"items": "0-4294967295"}"#)
+            .expect_err("Oops!")
+            .to_string();
+        let msg = "\"0-4294967295\" expands to more than 65536 numbers";
+        assert!(err.contains(msg), "{}", err);
+
+        let result: Outer = serde_json::from_str(r#"{"items": "1-65536"}"#).expect("Oops!");
+        assert_eq!(result.items.len(), super::MAX_LEN);
+        assert!(serde_json::from_str::<Outer>(r#"{"items": "1-65536,0"}"#).is_err());
+    }
+}
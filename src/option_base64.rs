@@ -17,6 +17,8 @@
 //! }
 //! ```
 
+use alloc::string::String;
+use alloc::vec::Vec;
 use base64::{engine::general_purpose, Engine as _};
 
 use serde::{Deserialize, Serialize};
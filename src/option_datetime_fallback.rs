@@ -0,0 +1,78 @@
+//! Serialize and Deserialize an optional date-time, trying several formats in turn
+//!
+//! See [datetime_fallback](crate::datetime_fallback).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_datetime_fallback",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub expires: Option<SystemTime>,
+//! }
+//! ```
+
+crate::optional::option_module!(datetime_fallback, T: crate::rfc3339::Rfc3339Time);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_datetime_fallback",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub expires: Option<SystemTime>,
+    }
+
+    #[test]
+    fn serialize_some() {
+        let outer = Outer {
+            expires: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"expires":"2023-11-14T22:13:20Z"}"#);
+    }
+
+    #[test]
+    fn serialize_none() {
+        let outer = Outer { expires: None };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, "{}");
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let outer = Outer {
+            expires: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        };
+        let result: Outer =
+            serde_json::from_str(r#"{"expires": "Tue, 14 Nov 2023 22:13:20 GMT"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer { expires: None };
+        let result: Outer = serde_json::from_str("{}").expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result: Outer = serde_json::from_str(r#"{"expires": null}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
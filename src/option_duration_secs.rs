@@ -0,0 +1,144 @@
+//! Serialize and Deserialize an `Option<Duration>` as seconds
+//!
+//! See [duration_secs](crate::duration_secs).  Use
+//! `option_duration_secs::float` to serialize float seconds.
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_duration_secs",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub timeout: Option<Duration>,
+//! }
+//! ```
+
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::time::Duration;
+
+use super::duration_secs::{self, DurationSecs};
+
+pub fn serialize<S: Serializer>(v: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        Some(v) => duration_secs::serialize(v, s),
+        None => s.serialize_none(),
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+    struct OptionDuration;
+
+    impl<'de> Visitor<'de> for OptionDuration {
+        type Value = Option<Duration>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a null or a non-negative number of seconds")
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(DurationSecs).map(Some)
+        }
+    }
+
+    d.deserialize_option(OptionDuration)
+}
+
+/// Serialize as float seconds.
+pub mod float {
+    use serde::{Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(v: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+        match v {
+            Some(v) => crate::duration_secs::float::serialize(v, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_duration_secs",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub timeout: Option<Duration>,
+        #[serde(default, with = "crate::option_duration_secs::float")]
+        pub backoff: Option<Duration>,
+    }
+
+    #[test]
+    fn serialize_some() {
+        let outer = Outer {
+            timeout: Some(Duration::from_secs(30)),
+            backoff: Some(Duration::from_millis(500)),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"timeout":30,"backoff":0.5}"#);
+    }
+
+    #[test]
+    fn serialize_none() {
+        let outer = Outer {
+            timeout: None,
+            backoff: None,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"backoff":null}"#);
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let outer = Outer {
+            timeout: Some(Duration::from_millis(1_500)),
+            backoff: Some(Duration::from_secs(2)),
+        };
+        let result: Outer =
+            serde_json::from_str(r#"{"timeout": 1.5, "backoff": 2}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer {
+            timeout: None,
+            backoff: None,
+        };
+        let result: Outer = serde_json::from_str(r#"{"backoff": null}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
@@ -0,0 +1,126 @@
+//! Serialize and Deserialize an `Option` of a C-like enum as its discriminant
+//!
+//! See [enum_int](crate::enum_int).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug)]
+//! pub enum Level {
+//!     Debug = 1,
+//!     Info = 2,
+//! }
+//! serde_stuff::int_enum!(Level { Debug, Info });
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_enum_int",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub level: Option<Level>,
+//! }
+//! ```
+
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::enum_int::{self, IntEnum, IntEnumVisitor};
+
+pub fn serialize<S: Serializer, T: IntEnum>(v: &Option<T>, s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        Some(v) => enum_int::serialize(v, s),
+        None => s.serialize_none(),
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: IntEnum>(d: D) -> Result<Option<T>, D::Error> {
+    struct OptionIntEnum<T>(PhantomData<T>);
+
+    impl<'de, T: IntEnum> Visitor<'de> for OptionIntEnum<T> {
+        type Value = Option<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a null or a {} discriminant", T::NAME)
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer
+                .deserialize_i64(IntEnumVisitor(PhantomData))
+                .map(Some)
+        }
+    }
+
+    d.deserialize_option(OptionIntEnum(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(PartialEq, Debug)]
+    pub enum Level {
+        Debug = 1,
+        Info = 2,
+    }
+    crate::int_enum!(Level { Debug, Info });
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_enum_int",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub level: Option<Level>,
+    }
+
+    #[test]
+    fn serialize_some() {
+        let outer = Outer {
+            level: Some(Level::Info),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"level":2}"#);
+    }
+
+    #[test]
+    fn serialize_none() {
+        let outer = Outer { level: None };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        for (json, level) in [
+            (r#"{"level": 1}"#, Some(Level::Debug)),
+            (r#"{"level": null}"#, None),
+            (r#"{}"#, None),
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(Outer { level }, result);
+        }
+        assert!(serde_json::from_str::<Outer>(r#"{"level": 9}"#).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+//! Serialize and Deserialize an optional time as an Excel serial date number
+//!
+//! See [excel_date](crate::excel_date).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_excel_date",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub shipped: Option<SystemTime>,
+//! }
+//! ```
+
+crate::optional::option_module!(excel_date, T: crate::epoch::EpochTime);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::SystemTime;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_excel_date",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub shipped: Option<SystemTime>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let result: Outer = serde_json::from_str(r#"{"shipped": 45123.5}"#).expect("Oops!");
+        assert!(result.shipped.is_some());
+        let result = serde_json::to_string(&result).expect("Oops!");
+        assert_eq!(result, r#"{"shipped":45123.5}"#);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let result: Outer = serde_json::from_str(r#"{"shipped": null}"#).expect("Oops!");
+        assert_eq!(result.shipped, None);
+        let result = serde_json::to_string(&result).expect("Oops!");
+        assert_eq!(result, "{}");
+    }
+}
@@ -0,0 +1,77 @@
+//! Serialize and Deserialize an optional `Duration` as a compact human string
+//!
+//! See [human_duration](crate::human_duration).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_human_duration",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub timeout: Option<Duration>,
+//! }
+//! ```
+
+crate::optional::option_module!(human_duration, std::time::Duration);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_human_duration",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub timeout: Option<Duration>,
+    }
+
+    #[test]
+    fn serialize_some() {
+        let outer = Outer {
+            timeout: Some(Duration::from_secs(330)),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"timeout":"5m30s"}"#);
+    }
+
+    #[test]
+    fn serialize_none() {
+        let outer = Outer { timeout: None };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, "{}");
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let outer = Outer {
+            timeout: Some(Duration::from_secs(330)),
+        };
+        let result: Outer = serde_json::from_str(r#"{"timeout": "5m30s"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer { timeout: None };
+        let result: Outer = serde_json::from_str("{}").expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result: Outer = serde_json::from_str(r#"{"timeout": null}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
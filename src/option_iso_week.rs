@@ -0,0 +1,109 @@
+//! Serialize and Deserialize an optional date or week as an ISO 8601 week date
+//!
+//! See [iso_week](crate::iso_week).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "chrono")]
+//! # {
+//! use chrono::{IsoWeek, NaiveDate};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_iso_week",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub sprint: Option<IsoWeek>,
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_iso_week",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub payday: Option<NaiveDate>,
+//! }
+//! # }
+//! ```
+
+crate::optional::option_module!(iso_week, T: crate::iso_week::IsoWeekDate);
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{IsoWeek, NaiveDate};
+        use serde::{Deserialize, Serialize};
+        use serde_json;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(
+                default,
+                with = "crate::option_iso_week",
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub sprint: Option<IsoWeek>,
+            #[serde(
+                default,
+                with = "crate::option_iso_week",
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub payday: Option<NaiveDate>,
+        }
+
+        let json = r#"{"sprint":"2024-W05","payday":"2024-W05-5"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        let sprint = result.sprint.expect("Oops!");
+        assert_eq!((sprint.year(), sprint.week()), (2024, 5));
+        assert_eq!(result.payday, NaiveDate::from_ymd_opt(2024, 2, 2));
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+
+        let result: Outer = serde_json::from_str(r#"{"sprint": null}"#).expect("Oops!");
+        assert_eq!(
+            result,
+            Outer {
+                sprint: None,
+                payday: None,
+            }
+        );
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), "{}");
+
+        assert!(serde_json::from_str::<Outer>(r#"{"sprint": "2024-W53"}"#).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use serde::{Deserialize, Serialize};
+        use serde_json;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(default, with = "crate::option_iso_week")]
+            pub payday: Option<time::Date>,
+        }
+
+        let json = r#"{"payday":"2025-W01-1"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(
+            result.payday,
+            time::Date::from_calendar_date(2024, time::Month::December, 30).ok()
+        );
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+
+        let result: Outer = serde_json::from_str("{}").expect("Oops!");
+        assert_eq!(result.payday, None);
+        assert_eq!(
+            serde_json::to_string(&result).expect("Oops!"),
+            r#"{"payday":null}"#
+        );
+    }
+}
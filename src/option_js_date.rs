@@ -0,0 +1,60 @@
+//! Serialize and Deserialize an optional time in the shape of `Date.toJSON`
+//!
+//! See [js_date](crate::js_date).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_js_date",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub created: Option<SystemTime>,
+//! }
+//! ```
+
+crate::optional::option_module!(js_date, T: crate::epoch::EpochTime);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::SystemTime;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_js_date",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub created: Option<SystemTime>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let result: Outer =
+            serde_json::from_str(r#"{"created": "2023-11-14T22:13:20.250Z"}"#).expect("Oops!");
+        assert!(result.created.is_some());
+        let result = serde_json::to_string(&result).expect("Oops!");
+        assert_eq!(result, r#"{"created":"2023-11-14T22:13:20.250Z"}"#);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let result: Outer = serde_json::from_str(r#"{"created": null}"#).expect("Oops!");
+        assert_eq!(result.created, None);
+        let result = serde_json::to_string(&result).expect("Oops!");
+        assert_eq!(result, "{}");
+    }
+}
@@ -0,0 +1,79 @@
+//! Deserialize an `Option<String>`, rejecting empty or whitespace-only values
+//!
+//! A missing or `null` value is `None`, but a present value must not be blank.
+//! See [non_empty_string](crate::non_empty_string).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_non_empty_string",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub nickname: Option<String>,
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+use super::non_empty_string;
+
+pub fn serialize<S: Serializer>(v: &Option<String>, s: S) -> Result<S::Ok, S::Error> {
+    <Option<String>>::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<String>, D::Error> {
+    match <Option<String>>::deserialize(d)? {
+        Some(v) => non_empty_string::validate(v).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_non_empty_string",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub name: Option<String>,
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let outer = Outer {
+            name: Some("value".to_string()),
+        };
+        let result: Outer = serde_json::from_str(r#"{"name": "value"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer { name: None };
+        let result: Outer = serde_json::from_str(r#"{}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result: Outer = serde_json::from_str(r#"{"name": null}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_empty() {
+        assert!(serde_json::from_str::<Outer>(r#"{"name": "  "}"#).is_err());
+    }
+}
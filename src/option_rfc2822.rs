@@ -0,0 +1,79 @@
+//! Serialize and Deserialize an optional date-time as an RFC 2822 string
+//!
+//! See [rfc2822](crate::rfc2822).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_rfc2822",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub expires: Option<SystemTime>,
+//! }
+//! ```
+
+crate::optional::option_module!(rfc2822, T: crate::rfc3339::Rfc3339Time);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_rfc2822",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub expires: Option<SystemTime>,
+    }
+
+    #[test]
+    fn serialize_some() {
+        let outer = Outer {
+            expires: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"expires":"Tue, 14 Nov 2023 22:13:20 +0000"}"#);
+    }
+
+    #[test]
+    fn serialize_none() {
+        let outer = Outer { expires: None };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, "{}");
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let outer = Outer {
+            expires: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        };
+        let result: Outer =
+            serde_json::from_str(r#"{"expires": "Tue, 14 Nov 2023 22:13:20 +0000"}"#)
+                .expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer { expires: None };
+        let result: Outer = serde_json::from_str("{}").expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result: Outer = serde_json::from_str(r#"{"expires": null}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
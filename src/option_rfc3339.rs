@@ -0,0 +1,78 @@
+//! Serialize and Deserialize an optional date-time as an RFC 3339 string
+//!
+//! See [rfc3339](crate::rfc3339).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_rfc3339",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub deleted: Option<SystemTime>,
+//! }
+//! ```
+
+crate::optional::option_module!(rfc3339, T: crate::rfc3339::Rfc3339Time);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_rfc3339",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub deleted: Option<SystemTime>,
+    }
+
+    #[test]
+    fn serialize_some() {
+        let outer = Outer {
+            deleted: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"deleted":"2023-11-14T22:13:20Z"}"#);
+    }
+
+    #[test]
+    fn serialize_none() {
+        let outer = Outer { deleted: None };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, "{}");
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let outer = Outer {
+            deleted: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        };
+        let result: Outer =
+            serde_json::from_str(r#"{"deleted": "2023-11-14T22:13:20Z"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer { deleted: None };
+        let result: Outer = serde_json::from_str(r#"{"deleted": null}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result: Outer = serde_json::from_str("{}").expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
@@ -0,0 +1,58 @@
+//! Serialize and Deserialize an optional date-time as RFC 3339, keeping its UTC offset
+//!
+//! See [rfc3339_offset](crate::rfc3339_offset).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "time")]
+//! # {
+//! use serde::{Deserialize, Serialize};
+//! use time::OffsetDateTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_rfc3339_offset",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub reviewed: Option<OffsetDateTime>,
+//! }
+//! # }
+//! ```
+
+crate::optional::option_module!(rfc3339_offset, T: crate::rfc3339_offset::OffsetTime);
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use serde::{Deserialize, Serialize};
+        use serde_json;
+        use time::OffsetDateTime;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(
+                default,
+                with = "crate::option_rfc3339_offset",
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub reviewed: Option<OffsetDateTime>,
+        }
+
+        let json = r#"{"reviewed":"2023-11-14T23:13:20+01:00"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+
+        let result: Outer = serde_json::from_str("{}").expect("Oops!");
+        assert_eq!(result.reviewed, None);
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), "{}");
+    }
+}
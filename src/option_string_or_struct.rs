@@ -52,11 +52,11 @@
 //! }
 //! ```
 
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer};
-use std::fmt;
-use std::marker::PhantomData;
-use std::str::FromStr;
 use void::Void;
 
 use super::string_or_struct;
@@ -0,0 +1,95 @@
+//! Serialize and Deserialize an optional time as milliseconds since the Unix epoch
+//!
+//! See [timestamp_millis](crate::timestamp_millis).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_timestamp_millis",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub expires: Option<SystemTime>,
+//! }
+//! ```
+
+crate::optional::option_module!(timestamp_millis, T: crate::epoch::EpochTime);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_timestamp_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub expires: Option<SystemTime>,
+    }
+
+    #[test]
+    fn serialize_some() {
+        let outer = Outer {
+            expires: Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123)),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"expires":1700000000123}"#);
+    }
+
+    #[test]
+    fn serialize_none() {
+        let outer = Outer { expires: None };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, "{}");
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let outer = Outer {
+            expires: Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123)),
+        };
+        let result: Outer = serde_json::from_str(r#"{"expires": 1700000000123}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer { expires: None };
+        let result: Outer = serde_json::from_str("{}").expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result: Outer = serde_json::from_str(r#"{"expires": null}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{DateTime, TimeZone, Utc};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Chrono {
+            #[serde(default, with = "crate::option_timestamp_millis")]
+            pub expires: Option<DateTime<Utc>>,
+        }
+
+        let json = r#"{"expires":1700000000123}"#;
+        let result: Chrono = serde_json::from_str(json).expect("Oops!");
+        let expected = Utc.timestamp_millis_opt(1_700_000_000_123).unwrap();
+        assert_eq!(result.expires, Some(expected));
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+}
@@ -0,0 +1,81 @@
+//! Serialize and Deserialize an optional `chrono` or `time` value as a TOML datetime
+//!
+//! Requires the `toml` feature.  See [toml_datetime](crate::toml_datetime).
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(all(feature = "toml", feature = "chrono"))]
+//! # {
+//! use chrono::NaiveDate;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_toml_datetime",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub holiday: Option<NaiveDate>,
+//! }
+//!
+//! let outer: Outer = toml::from_str("holiday = 1979-12-25").unwrap();
+//! assert_eq!(outer.holiday, NaiveDate::from_ymd_opt(1979, 12, 25));
+//! let outer: Outer = toml::from_str("").unwrap();
+//! assert_eq!(outer.holiday, None);
+//! # }
+//! ```
+
+crate::optional::option_module!(toml_datetime, T: crate::toml_datetime::TomlDatetime);
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_toml_datetime",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub holiday: Option<NaiveDate>,
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let outer = Outer {
+            holiday: NaiveDate::from_ymd_opt(1979, 12, 25),
+        };
+        let result: Outer = toml::from_str("holiday = 1979-12-25").expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result: Outer = serde_json::from_str(r#"{"holiday": "1979-12-25"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer { holiday: None };
+        let result: Outer = toml::from_str("").expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result: Outer = serde_json::from_str(r#"{"holiday": null}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            holiday: NaiveDate::from_ymd_opt(1979, 12, 25),
+        };
+        let result = toml::to_string(&outer).expect("Oops!");
+        assert_eq!(result, "holiday = \"1979-12-25\"\n");
+        let result = toml::to_string(&Outer { holiday: None }).expect("Oops!");
+        assert_eq!(result, "");
+    }
+}
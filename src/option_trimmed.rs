@@ -0,0 +1,108 @@
+//! Deserialize an `Option<String>` with leading and trailing whitespace removed
+//!
+//! See [trimmed](crate::trimmed).  Use `option_trimmed::collapse` to also
+//! collapse internal whitespace.
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_trimmed",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub nickname: Option<String>,
+//! }
+//! ```
+
+use alloc::string::{String, ToString};
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(v: &Option<String>, s: S) -> Result<S::Ok, S::Error> {
+    <Option<String>>::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<String>, D::Error> {
+    let value = <Option<String>>::deserialize(d)?;
+    Ok(value.map(|v| v.trim().to_string()))
+}
+
+/// Trim, and collapse internal whitespace.
+pub mod collapse {
+    use alloc::string::String;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &Option<String>, s: S) -> Result<S::Ok, S::Error> {
+        super::serialize(v, s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<String>, D::Error> {
+        let value = <Option<String>>::deserialize(d)?;
+        Ok(value.map(|v| crate::trimmed::collapse_whitespace(&v)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_trimmed",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub name: Option<String>,
+        #[serde(
+            default,
+            with = "crate::option_trimmed::collapse",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub title: Option<String>,
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let json = r#"{
+            "name": "  Jane  Doe ",
+            "title": " Chief   Cook "
+        }"#;
+        let outer = Outer {
+            name: Some("Jane  Doe".to_string()),
+            title: Some("Chief Cook".to_string()),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer {
+            name: None,
+            title: None,
+        };
+        let result: Outer = serde_json::from_str(r#"{"name": null}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize_none() {
+        let outer = Outer {
+            name: None,
+            title: None,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, "{}");
+    }
+}
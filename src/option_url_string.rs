@@ -0,0 +1,62 @@
+//! Serialize and Deserialize an optional `url::Url` as a string
+//!
+//! See [url_string](crate::url_string).  Requires the `url` feature.
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use url::Url;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_url_string",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub homepage: Option<Url>,
+//! }
+//! ```
+
+crate::optional::option_module!(url_string, url::Url);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use url::Url;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_url_string",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub homepage: Option<Url>,
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let json = r#"{"homepage":"https://example.com/"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(
+            result.homepage,
+            Some(Url::parse("https://example.com").unwrap())
+        );
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let result: Outer = serde_json::from_str(r#"{"homepage": null}"#).expect("Oops!");
+        assert_eq!(result.homepage, None);
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), "{}");
+        assert!(serde_json::from_str::<Outer>(r#"{"homepage": "nope"}"#).is_err());
+    }
+}
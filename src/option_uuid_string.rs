@@ -0,0 +1,87 @@
+//! Serialize and Deserialize an optional `Uuid` as a string, in a chosen form
+//!
+//! See [uuid_string](crate::uuid_string).  Requires the `uuid` feature.
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use uuid::Uuid;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_uuid_string::simple",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub parent: Option<Uuid>,
+//! }
+//! ```
+
+crate::optional::option_module!(uuid_string, uuid::Uuid);
+
+/// Accept any form, and serialize without hyphens.
+pub mod simple {
+    crate::optional::option_module!(uuid_string::simple, uuid::Uuid);
+}
+
+/// Accept any form, and serialize as a `urn:uuid:` URN.
+pub mod urn {
+    crate::optional::option_module!(uuid_string::urn, uuid::Uuid);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use uuid::Uuid;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_uuid_string",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub id: Option<Uuid>,
+        #[serde(
+            default,
+            with = "crate::option_uuid_string::urn",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub parent: Option<Uuid>,
+    }
+
+    const ID: Uuid = Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8);
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            id: Some(ID),
+            parent: None,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"id":"67e55044-10b1-426f-9247-bb680e5fe0c8"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"parent": "67e5504410b1426f9247bb680e5fe0c8"}"#;
+        let outer = Outer {
+            id: None,
+            parent: Some(ID),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result = serde_json::to_string(&result).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"parent":"urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"}"#
+        );
+    }
+}
@@ -38,10 +38,12 @@
 //! {
 //! }
 //! ```
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
 use serde::{self, de, Deserialize, Serialize, Serializer};
-use std::fmt;
-use std::marker::PhantomData;
 
+#[allow(clippy::multiple_bound_locations)]
 pub fn deserialize<'de, D: de::Deserializer<'de>, T: Deserialize<'de>>(
     deserializer: D,
 ) -> Result<Option<Vec<T>>, D::Error>
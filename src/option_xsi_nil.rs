@@ -0,0 +1,264 @@
+//! Deserialize an `xsi:nil` or empty XML element to `None`
+//!
+//! XSD derived documents, such as SAML metadata, mark an absent value with
+//! `<Element xsi:nil="true"/>`, or leave the element empty.  A plain
+//! `Option<T>` only becomes `None` when the element is missing, so these
+//! fail to read as `T`.  This module reads `None` from:
+//! - a missing element, with `#[serde(default)]`, or `null`.
+//! - an empty element, which quick-xml buffers as an empty map.
+//! - an element with `xsi:nil` set to `"true"` or `"1"`, whatever its other
+//!   attributes.  quick-xml drops the prefix, so `@nil` is also checked.
+//!
+//! Anything else is read as `T`.  Since the element is buffered as
+//! [Content](crate::content::Content) first, quick-xml can no longer tell
+//! `T` what it expects, so the element is read as it would be from JSON:
+//! - an element with only text, `$text` or `$value`, is read as the text.
+//! - repeated child elements are read as a sequence.  A single child is
+//!   not, so use [vec_or_one](crate::vec_or_one) for a `Vec` field.
+//! - text is read as a number or boolean where `T` asks for one.
+//!
+//! `option_xsi_nil` writes `None` as `null`, which quick-xml writes as an
+//! empty element, so skip it with `skip_serializing_if`.
+//! `option_xsi_nil::nil` writes `None` as `xsi:nil="true"`, and the
+//! document must declare the `xsi` namespace.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Organization {
+//!     #[serde(rename = "OrganizationName")]
+//!     pub name: String,
+//!     #[serde(rename = "OrganizationURL")]
+//!     pub url: String,
+//! }
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Outer {
+//!     #[serde(rename = "@entityID")]
+//!     pub entity_id: String,
+//!     #[serde(
+//!         rename = "Organization",
+//!         default,
+//!         with = "serde_stuff::option_xsi_nil"
+//!     )]
+//!     pub organization: Option<Organization>,
+//!     #[serde(rename = "ValidUntil", default, with = "serde_stuff::option_xsi_nil")]
+//!     pub valid_until: Option<String>,
+//! }
+//!
+//! let xml = r#"
+//!     <EntityDescriptor
+//!         xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+//!         entityID="https://idp.example.com">
+//!       <Organization xsi:nil="true"/>
+//!       <ValidUntil/>
+//!     </EntityDescriptor>"#;
+//! let outer: Outer = quick_xml::de::from_str(xml).unwrap();
+//! assert!(outer.organization.is_none());
+//! assert!(outer.valid_until.is_none());
+//! ```
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or a missing
+//! element will not be properly deserialized.  You will get a missing
+//! attribute error from Serde.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::content::{Content, ContentDeserializer};
+use super::xs_boolean;
+
+/// The keys of the `xsi:nil` attribute, with and without its prefix.
+const NIL_KEYS: [&str; 2] = ["@xsi:nil", "@nil"];
+
+/// The keys quick-xml uses for the text of an element.
+const TEXT_KEYS: [&str; 2] = ["$text", "$value"];
+
+pub fn serialize<S: Serializer, T: Serialize>(v: &Option<T>, s: S) -> Result<S::Ok, S::Error> {
+    v.serialize(s)
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let content = Content::deserialize(d)?;
+    if is_nil(&content) {
+        return Ok(None);
+    }
+    T::deserialize(ContentDeserializer::<D::Error>::lenient(unwrap_text(
+        content,
+    )))
+    .map(Some)
+}
+
+/// Whether a buffered element is empty, or marked `xsi:nil`.
+fn is_nil(content: &Content) -> bool {
+    match content {
+        Content::Unit => true,
+        Content::Map(entries) => {
+            entries.is_empty()
+                || entries.iter().any(|(k, v)| {
+                    k.as_str().is_some_and(|k| NIL_KEYS.contains(&k))
+                        && v.as_str().and_then(xs_boolean::parse) == Some(true)
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Read a buffered element as JSON would be: text only elements as their
+/// text, and repeated children as a sequence, all the way down.
+pub(crate) fn unwrap_text(content: Content) -> Content {
+    match content {
+        Content::Map(entries) => {
+            if let [(key, _)] = entries.as_slice() {
+                if key.as_str().is_some_and(|k| TEXT_KEYS.contains(&k)) {
+                    let (_, text) = entries.into_iter().next().unwrap();
+                    return unwrap_text(text);
+                }
+            }
+            let mut grouped: Vec<(Content, Content)> = Vec::with_capacity(entries.len());
+            for (key, value) in entries {
+                let value = unwrap_text(value);
+                // An element is never buffered as a sequence, so a
+                // sequence is an earlier repeat.
+                match grouped.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, Content::Seq(items))) => items.push(value),
+                    Some((_, slot)) => {
+                        let first = mem::replace(slot, Content::Unit);
+                        *slot = Content::Seq(vec![first, value]);
+                    }
+                    None => grouped.push((key, value)),
+                }
+            }
+            Content::Map(grouped)
+        }
+        Content::Seq(items) => Content::Seq(items.into_iter().map(unwrap_text).collect()),
+        content => content,
+    }
+}
+
+/// Also write `None` as an element with `xsi:nil="true"`.
+pub mod nil {
+    use serde::ser::SerializeMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, T: Serialize>(v: &Option<T>, s: S) -> Result<S::Ok, S::Error> {
+        match v {
+            Some(v) => v.serialize(s),
+            None => {
+                let mut map = s.serialize_map(Some(1))?;
+                map.serialize_entry("@xsi:nil", "true")?;
+                map.end()
+            }
+        }
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        super::deserialize(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Contact {
+        #[serde(rename = "@contactType")]
+        pub kind: String,
+        #[serde(rename = "EmailAddress")]
+        pub emails: Vec<String>,
+        #[serde(rename = "Priority")]
+        pub priority: u8,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            rename = "Contact",
+            default,
+            with = "crate::option_xsi_nil",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub contact: Option<Contact>,
+        #[serde(rename = "Name", default, with = "crate::option_xsi_nil::nil")]
+        pub name: Option<String>,
+    }
+
+    fn from_xml(body: &str) -> Outer {
+        let xml = format!(
+            r#"<Outer xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">{}</Outer>"#,
+            body
+        );
+        quick_xml::de::from_str(&xml).expect("Oops!")
+    }
+
+    #[test]
+    fn deserialize_nil() {
+        let none = Outer {
+            contact: None,
+            name: None,
+        };
+        assert_eq!(from_xml(r#"<Contact xsi:nil="true"/><Name/>"#), none);
+        assert_eq!(from_xml(r#"<Contact xsi:nil="1" contactType="x"/>"#), none);
+        assert_eq!(from_xml(r#"<Contact></Contact>"#), none);
+        assert_eq!(from_xml(""), none);
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let outer = from_xml(
+            r#"<Contact contactType="technical" xsi:nil="false">
+                <EmailAddress>a@example.com</EmailAddress>
+                <EmailAddress>b@example.com</EmailAddress>
+                <Priority>2</Priority>
+            </Contact>
+            <Name>Example</Name>"#,
+        );
+        assert_eq!(
+            outer,
+            Outer {
+                contact: Some(Contact {
+                    kind: "technical".to_string(),
+                    emails: vec!["a@example.com".to_string(), "b@example.com".to_string()],
+                    priority: 2,
+                }),
+                name: Some("Example".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_json() {
+        let json = r#"{"Contact": {"@xsi:nil": "true"}, "Name": null}"#;
+        let outer: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(outer.contact, None);
+        assert_eq!(outer.name, None);
+
+        let outer: Outer = serde_json::from_str(r#"{"Name": "Example"}"#).expect("Oops!");
+        assert_eq!(outer.name.as_deref(), Some("Example"));
+    }
+
+    #[test]
+    fn serialize_nil() {
+        let outer = Outer {
+            contact: None,
+            name: None,
+        };
+        let xml = quick_xml::se::to_string(&outer).expect("Oops!");
+        assert_eq!(xml, r#"<Outer><Name xsi:nil="true"/></Outer>"#);
+    }
+}
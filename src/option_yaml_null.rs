@@ -0,0 +1,141 @@
+//! Deserialize YAML's null tokens to `None`, when they arrive as strings
+//!
+//! YAML writes null as `~`, `null`, `Null`, `NULL`, or nothing at all, as
+//! in `key:`.  A YAML parser reads them as null, but once the document has
+//! been through a converter or a template that quotes every scalar, they
+//! arrive as strings, and a plain `Option<String>` is `Some("~")` or
+//! `Some("")`.  This module reads `None` from a null, or a string that is
+//! exactly one of those tokens, and anything else as `T`.
+//!
+//! `T` can be any type, such as a number, which is read from the buffered
+//! value, so the format must be self describing.
+//!
+//! ## USE DEFAULT!!
+//! **Note:** The attribute must be decorated with `default`, or it will not
+//! be properly deserialized.  You will get a missing attribute error from Serde.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         default,
+//!         with = "serde_stuff::option_yaml_null",
+//!         skip_serializing_if = "Option::is_none"
+//!     )]
+//!     pub description: Option<String>,
+//!     #[serde(default, with = "serde_stuff::option_yaml_null")]
+//!     pub replicas: Option<u32>,
+//! }
+//! ```
+//! The following will deserialize to
+//! `Outer { description: None, replicas: None }`
+//! ```json
+//! {
+//!     "description": "",
+//!     "replicas": "~"
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::content::{Content, ContentDeserializer};
+
+/// The YAML 1.2 core schema null tokens, with the empty scalar.
+pub const NULL_TOKENS: [&str; 5] = ["", "~", "null", "Null", "NULL"];
+
+/// Whether `value` is a YAML null token.
+pub fn is_null(value: &str) -> bool {
+    NULL_TOKENS.contains(&value)
+}
+
+pub fn serialize<S: Serializer, T: Serialize>(v: &Option<T>, s: S) -> Result<S::Ok, S::Error> {
+    v.serialize(s)
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    match Content::deserialize(d)? {
+        Content::Unit => Ok(None),
+        Content::String(v) if is_null(&v) => Ok(None),
+        content => T::deserialize(ContentDeserializer::<D::Error>::new(content)).map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "crate::option_yaml_null",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub name: Option<String>,
+        #[serde(
+            default,
+            with = "crate::option_yaml_null",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub replicas: Option<u32>,
+    }
+
+    #[test]
+    fn deserialize_some() {
+        let json = r#"{"name": "~web", "replicas": 3}"#;
+        let outer = Outer {
+            name: Some("~web".to_string()),
+            replicas: Some(3),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+
+        let result: Outer = serde_json::from_str(r#"{"name": " "}"#).expect("Oops!");
+        assert_eq!(result.name.as_deref(), Some(" "));
+    }
+
+    #[test]
+    fn deserialize_none() {
+        let outer = Outer {
+            name: None,
+            replicas: None,
+        };
+        for json in [
+            r#"{}"#,
+            r#"{"name": null, "replicas": null}"#,
+            r#"{"name": "~", "replicas": "~"}"#,
+            r#"{"name": "", "replicas": ""}"#,
+            r#"{"name": "NULL", "replicas": "Null"}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(&outer, &result, "{}", json);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let err = serde_json::from_str::<Outer>(r#"{"replicas": "nil"}"#)
+            .expect_err("Oops!")
+            .to_string();
+        let msg = "invalid type: string \"nil\", expected u32";
+        assert!(err.contains(msg), "{}", err);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            name: Some("web".to_string()),
+            replicas: None,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"name":"web"}"#);
+    }
+}
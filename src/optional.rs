@@ -0,0 +1,200 @@
+//! Generate the `option_*` companion of a `serialize`/`deserialize` module
+//!
+//! Each `option_*` module wraps its base module the same way: `None` is
+//! written as a null, and a null or a missing value reads as `None`.  Rather
+//! than hand rolling a visitor for each one, the companion is generated with
+//! the public [optionize!](crate::optionize!) macro, or, within this crate,
+//! `option_module!`, given the base module path relative to the crate root,
+//! and either the value type or the trait that the base module is generic
+//! over.
+//!
+//! ```rust,ignore
+//! crate::optional::option_module!(timestamp_millis, T: crate::epoch::EpochTime);
+//! crate::optional::option_module!(human_duration, std::time::Duration);
+//! ```
+
+/// Generate an `Option` aware `with` module from another one.
+///
+/// Give the path of the base module, and either the value type it handles,
+/// or `T:` and the trait its functions are generic over.  `None` is written
+/// as a null, and a null reads as `None`.  With a visibility and name first,
+/// a module of that name is declared, otherwise the functions are generated
+/// in place.  Since the path is used from inside the declared module,
+/// start it with a crate name, `crate::` or `super::`.
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+///
+/// serde_stuff::optionize!(pub option_trimmed, serde_stuff::trimmed, String);
+///
+/// mod option_hosts {
+///     serde_stuff::optionize!(serde_stuff::vec_or_one, Vec<String>);
+/// }
+///
+/// #[derive(Debug, Deserialize, Serialize)]
+/// pub struct Outer {
+///     #[serde(default, with = "option_trimmed", skip_serializing_if = "Option::is_none")]
+///     pub name: Option<String>,
+///     #[serde(default, with = "option_hosts", skip_serializing_if = "Option::is_none")]
+///     pub hosts: Option<Vec<String>>,
+/// }
+///
+/// let json = r#"{"name": " web ", "hosts": "a.example"}"#;
+/// let outer: Outer = serde_json::from_str(json).unwrap();
+/// assert_eq!(outer.name.as_deref(), Some("web"));
+/// assert_eq!(outer.hosts, Some(vec!["a.example".to_string()]));
+/// ```
+///
+/// As with the crate's own `option_*` modules, a missing field only reads
+/// as `None` if the field also has `#[serde(default)]`.
+#[macro_export]
+macro_rules! optionize {
+    ($vis:vis $name:ident, $($base:ident)::+, T: $bound:path) => {
+        $vis mod $name {
+            $crate::optionize!($($base)::+, T: $bound);
+        }
+    };
+    ($vis:vis $name:ident, $($base:ident)::+, $ty:ty) => {
+        $vis mod $name {
+            $crate::optionize!($($base)::+, $ty);
+        }
+    };
+    ($($base:ident)::+, T: $bound:path) => {
+        #[allow(dead_code)]
+        pub fn serialize<S, T>(v: &Option<T>, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+            T: $bound,
+        {
+            match v {
+                Some(v) => $($base)::+::serialize(v, s),
+                None => s.serialize_none(),
+            }
+        }
+
+        #[allow(dead_code)]
+        pub fn deserialize<'de, D, T>(d: D) -> Result<Option<T>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+            T: $bound,
+        {
+            struct Base<T>(T);
+
+            impl<'de, T: $bound> serde::Deserialize<'de> for Base<T> {
+                fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                    $($base)::+::deserialize(d).map(Base)
+                }
+            }
+
+            Ok(<Option<Base<T>> as serde::Deserialize>::deserialize(d)?.map(|v| v.0))
+        }
+    };
+    ($($base:ident)::+, $ty:ty) => {
+        #[allow(dead_code)]
+        pub fn serialize<S: serde::Serializer>(v: &Option<$ty>, s: S) -> Result<S::Ok, S::Error> {
+            match v {
+                Some(v) => $($base)::+::serialize(v, s),
+                None => s.serialize_none(),
+            }
+        }
+
+        #[allow(dead_code)]
+        pub fn deserialize<'de, D>(d: D) -> Result<Option<$ty>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            struct Base($ty);
+
+            impl<'de> serde::Deserialize<'de> for Base {
+                fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                    $($base)::+::deserialize(d).map(Base)
+                }
+            }
+
+            Ok(<Option<Base> as serde::Deserialize>::deserialize(d)?.map(|v| v.0))
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+macro_rules! option_module {
+    ($($base:ident)::+, $($rest:tt)+) => {
+        $crate::optionize!(crate::$($base)::+, $($rest)+);
+    };
+}
+
+#[cfg(feature = "std")]
+pub(crate) use option_module;
+
+#[cfg(all(test, feature = "base64"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    crate::optionize!(option_base64, crate::base64, Vec<u8>);
+
+    mod hex {
+        use crate::adapters::{As, Hex};
+
+        pub fn serialize<S: serde::Serializer>(v: &Vec<u8>, s: S) -> Result<S::Ok, S::Error> {
+            As::<Hex>::serialize(v, s)
+        }
+
+        pub fn deserialize<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+            As::<Hex>::deserialize(d)
+        }
+    }
+
+    crate::optionize!(option_hex, super::hex, Vec<u8>);
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(
+            default,
+            with = "option_base64",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub key: Option<Vec<u8>>,
+        #[serde(default, with = "option_hex")]
+        pub hash: Option<Vec<u8>>,
+    }
+
+    #[test]
+    fn deserialize() {
+        for (json, outer) in [
+            (
+                r#"{"key": "AAE=", "hash": "00ff"}"#,
+                Outer {
+                    key: Some(vec![0, 1]),
+                    hash: Some(vec![0, 255]),
+                },
+            ),
+            (
+                r#"{"key": null, "hash": null}"#,
+                Outer {
+                    key: None,
+                    hash: None,
+                },
+            ),
+            (
+                r#"{}"#,
+                Outer {
+                    key: None,
+                    hash: None,
+                },
+            ),
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(&outer, &result);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            key: None,
+            hash: Some(vec![0xbe, 0xef]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"hash":"beef"}"#);
+    }
+}
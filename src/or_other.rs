@@ -0,0 +1,225 @@
+//! Capture unknown enum variants in an `Other` variant
+//!
+//! `#[serde(other)]` maps unknown variants to a unit variant and throws the
+//! value away.  With this module an enum that has an `Other(String)` variant
+//! receives any unrecognized string there instead of failing, and writes the
+//! captured string back verbatim, so values added upstream survive a round
+//! trip.  Known variants behave as usual.
+//!
+//! Unknown variants with data, such as `{"Webhook": {"url": "..."}}`, can be
+//! kept too.  Make the variant `Other(Content)`, or `Other(serde_json::Value)`,
+//! and it receives the whole raw value, which is written back verbatim.  The
+//! `Other` variant must be a newtype variant.
+//!
+//! Since the value is buffered as [Content](crate::content::Content), the
+//! format must be self describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! #[serde(rename_all = "lowercase")]
+//! pub enum Channel {
+//!     Stable,
+//!     Beta,
+//!     #[serde(rename = "Other")]
+//!     Other(String),
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::or_other")]
+//!     pub channel: Channel,
+//! }
+//! ```
+//! The following will deserialize to `Outer` with `Channel::Other("nightly")`
+//! ```json
+//! {
+//!     "channel": "nightly"
+//! }
+//! ```
+
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::marker::PhantomData;
+
+use super::content::{Content, ContentDeserializer, ContentSerializer};
+
+/// The name of the variant that captures unknown values.  Keep it when
+/// using `rename_all`, as in the example above.
+pub const OTHER: &str = "Other";
+
+/// Replays a [Content], moving an unknown variant into [OTHER].
+struct OrOther<E> {
+    content: Content,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for OrOther<E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        ContentDeserializer::new(self.content).deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        ContentDeserializer::new(self.content).deserialize_option(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        let known = match &self.content {
+            Content::String(v) => variants.contains(&v.as_str()),
+            Content::Map(entries) if entries.len() == 1 => {
+                entries[0].0.as_str().is_some_and(|v| variants.contains(&v))
+            }
+            _ => false,
+        };
+        let content = match self.content {
+            content if !known && variants.contains(&OTHER) => {
+                Content::Map(vec![(Content::String(OTHER.to_string()), content)])
+            }
+            content => content,
+        };
+        ContentDeserializer::new(content).deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+pub fn serialize<S: Serializer, T: Serialize>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    if let Content::Map(entries) = v.serialize(ContentSerializer::<S::Error>::new())? {
+        if let [(Content::String(variant), value)] = entries.as_slice() {
+            if variant == OTHER {
+                return value.serialize(s);
+            }
+        }
+    }
+    v.serialize(s)
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    T::deserialize(OrOther {
+        content: Content::deserialize(d)?,
+        marker: PhantomData,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    use crate::content::Content;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Channel {
+        Stable,
+        Beta,
+        Pinned(String),
+        #[serde(rename = "Other")]
+        Other(String),
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::or_other")]
+        pub channel: Channel,
+    }
+
+    #[test]
+    fn deserialize() {
+        for (json, channel) in [
+            (r#""beta""#, Channel::Beta),
+            (
+                r#""Nightly-2024""#,
+                Channel::Other("Nightly-2024".to_string()),
+            ),
+            (r#"{"pinned": "1.0"}"#, Channel::Pinned("1.0".to_string())),
+        ] {
+            let json = format!(r#"{{"channel": {}}}"#, json);
+            let result: Outer = serde_json::from_str(&json).expect("Oops!");
+            assert_eq!(Outer { channel }, result);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        for (channel, json) in [
+            (Channel::Stable, r#"{"channel":"stable"}"#),
+            (
+                Channel::Other("Nightly-2024".to_string()),
+                r#"{"channel":"Nightly-2024"}"#,
+            ),
+            (
+                Channel::Pinned("1.0".to_string()),
+                r#"{"channel":{"pinned":"1.0"}}"#,
+            ),
+        ] {
+            let result = serde_json::to_string(&Outer { channel }).expect("Oops!");
+            assert_eq!(result, json);
+        }
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub enum Notify {
+        Email(String),
+        Other(Content),
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Hook {
+        #[serde(with = "crate::or_other")]
+        pub notify: Notify,
+    }
+
+    #[test]
+    fn payload() {
+        for (json, notify) in [
+            (
+                r#"{"notify":{"Email":"a@b.c"}}"#,
+                Notify::Email("a@b.c".to_string()),
+            ),
+            (
+                r#"{"notify":{"Webhook":{"url":"https://x"}}}"#,
+                Notify::Other(Content::Map(vec![(
+                    Content::String("Webhook".to_string()),
+                    Content::Map(vec![(
+                        Content::String("url".to_string()),
+                        Content::String("https://x".to_string()),
+                    )]),
+                )])),
+            ),
+            (
+                r#"{"notify":"Pager"}"#,
+                Notify::Other(Content::String("Pager".to_string())),
+            ),
+            (r#"{"notify":7}"#, Notify::Other(Content::U64(7))),
+        ] {
+            let result: Hook = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(result, Hook { notify });
+            assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"channel": 3}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+    }
+}
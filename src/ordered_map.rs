@@ -0,0 +1,127 @@
+//! Serialize and Deserialize `Vec<(K, V)>` as a map, keeping the order
+//!
+//! The inverse of [key_value_list](crate::key_value_list): the wire format
+//! is an object, but the order of its entries matters, as for HTTP headers
+//! or ordered steps.  Entries are written in the order of the `Vec`, and
+//! read back in the order they appear.  Repeated keys are all kept.  When
+//! keys are unique, an `IndexMap` field with the `indexmap` feature keeps
+//! the order without this module.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::ordered_map")]
+//!     pub steps: Vec<(String, u32)>,
+//! }
+//! ```
+//! The following will deserialize to
+//! `Outer { steps: vec![("build".to_string(), 10), ("test".to_string(), 30)] }`
+//! ```json
+//! {
+//!     "steps": {"build": 10, "test": 30}
+//! }
+//! ```
+
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+pub fn serialize<S, K, V>(v: &[(K, V)], s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize,
+    V: Serialize,
+{
+    let mut map = s.serialize_map(Some(v.len()))?;
+    for (key, value) in v {
+        map.serialize_entry(key, value)?;
+    }
+    map.end()
+}
+
+pub fn deserialize<'de, D, K, V>(d: D) -> Result<Vec<(K, V)>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    struct PairsVisitor<K, V>(PhantomData<(K, V)>);
+
+    impl<'de, K, V> Visitor<'de> for PairsVisitor<K, V>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        type Value = Vec<(K, V)>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Vec<(K, V)>, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+            while let Some(entry) = map.next_entry()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+    }
+
+    d.deserialize_map(PairsVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::ordered_map")]
+        pub steps: Vec<(String, u32)>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"steps": {"test": 30, "build": 10, "test": 5}}"#;
+        let outer = Outer {
+            steps: vec![
+                ("test".to_string(), 30),
+                ("build".to_string(), 10),
+                ("test".to_string(), 5),
+            ],
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            steps: vec![("zip".to_string(), 1), ("build".to_string(), 2)],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"steps":{"zip":1,"build":2}}"#);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"steps": [["build", 10]]}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+    }
+
+    #[test]
+    fn bincode_hostile_length() {
+        let bytes = (u64::MAX / 2).to_le_bytes();
+        assert!(bincode::deserialize::<Outer>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! Serialize and Deserialize an `OsString` or `PathBuf` losslessly
+//!
+//! OS strings, and so paths, don't have to be valid UTF-8.  Values that are
+//! valid UTF-8 are written as a plain string.  Any other value is written as
+//! a map with its raw bytes in URL safe base64, so it survives a round-trip
+//! exactly:
+//!
+//! ```json
+//! { "base64": "Zm9vgGJhcg" }
+//! ```
+//!
+//! The raw bytes are platform specific: the bytes of the `OsStr` on Unix,
+//! and the UTF-16 code units, little endian, on Windows.  Restore them on
+//! the same kind of platform that wrote them.  Other platforms only support
+//! UTF-8 values.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::ffi::OsString;
+//! use std::path::PathBuf;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::os_string")]
+//!     pub name: OsString,
+//!     #[serde(with = "serde_stuff::os_string")]
+//!     pub path: PathBuf,
+//! }
+//! ```
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserializer, Serializer};
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::marker::PhantomData;
+
+const BYTES_KEY: &str = "base64";
+
+#[cfg(unix)]
+fn to_bytes(v: &OsStr) -> Option<Vec<u8>> {
+    use std::os::unix::ffi::OsStrExt;
+    Some(v.as_bytes().to_vec())
+}
+
+#[cfg(unix)]
+fn from_bytes(bytes: Vec<u8>) -> Option<OsString> {
+    use std::os::unix::ffi::OsStringExt;
+    Some(OsString::from_vec(bytes))
+}
+
+#[cfg(windows)]
+fn to_bytes(v: &OsStr) -> Option<Vec<u8>> {
+    use std::os::windows::ffi::OsStrExt;
+    Some(v.encode_wide().flat_map(u16::to_le_bytes).collect())
+}
+
+#[cfg(windows)]
+fn from_bytes(bytes: Vec<u8>) -> Option<OsString> {
+    use std::os::windows::ffi::OsStringExt;
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    Some(OsString::from_wide(&wide))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn to_bytes(_v: &OsStr) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(not(any(unix, windows)))]
+fn from_bytes(bytes: Vec<u8>) -> Option<OsString> {
+    String::from_utf8(bytes).ok().map(OsString::from)
+}
+
+pub fn serialize<S, T>(v: &T, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: AsRef<OsStr>,
+{
+    let v = v.as_ref();
+    if let Some(value) = v.to_str() {
+        return s.serialize_str(value);
+    }
+    let bytes =
+        to_bytes(v).ok_or_else(|| serde::ser::Error::custom("OS string is not valid UTF-8"))?;
+    let mut map = s.serialize_map(Some(1))?;
+    map.serialize_entry(BYTES_KEY, &general_purpose::URL_SAFE_NO_PAD.encode(bytes))?;
+    map.end()
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: From<OsString>,
+{
+    struct OsStringVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: From<OsString>> Visitor<'de> for OsStringVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a string, or a map with base64 bytes")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            Ok(OsString::from(value).into())
+        }
+
+        fn visit_map<M>(self, mut map: M) -> Result<T, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            let key: String = map
+                .next_key()?
+                .ok_or_else(|| de::Error::missing_field(BYTES_KEY))?;
+            if key != BYTES_KEY {
+                return Err(de::Error::unknown_field(&key, &[BYTES_KEY]));
+            }
+            let value: String = map.next_value()?;
+            if map.next_key::<String>()?.is_some() {
+                return Err(de::Error::invalid_length(2, &self));
+            }
+            let bytes = general_purpose::URL_SAFE_NO_PAD
+                .decode(value.trim_end_matches('='))
+                .map_err(de::Error::custom)?;
+            from_bytes(bytes)
+                .map(T::from)
+                .ok_or_else(|| de::Error::custom("bytes are not a valid OS string here"))
+        }
+    }
+
+    d.deserialize_any(OsStringVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::os_string")]
+        pub name: OsString,
+        #[serde(with = "crate::os_string")]
+        pub path: PathBuf,
+    }
+
+    #[test]
+    fn utf8() {
+        let outer = Outer {
+            name: OsString::from("résumé.txt"),
+            path: PathBuf::from("/tmp/résumé.txt"),
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(json, r#"{"name":"résumé.txt","path":"/tmp/résumé.txt"}"#);
+        let result: Outer = serde_json::from_str(&json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let name = OsString::from_vec(b"foo\x80bar".to_vec());
+        let outer = Outer {
+            name: name.clone(),
+            path: PathBuf::from(name),
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            json,
+            r#"{"name":{"base64":"Zm9vgGJhcg"},"path":{"base64":"Zm9vgGJhcg"}}"#
+        );
+        let result: Outer = serde_json::from_str(&json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for json in [
+            r#"{"name": {"bytes": "Zm9v"}, "path": "a"}"#,
+            r#"{"name": {"base64": "Zm9v", "extra": 1}, "path": "a"}"#,
+            r#"{"name": {"base64": "!!"}, "path": "a"}"#,
+            r#"{"name": 1, "path": "a"}"#,
+        ] {
+            assert!(serde_json::from_str::<Outer>(json).is_err(), "{}", json);
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! A field of a PATCH request: missing, `null`, or a value
+//!
+//! `Option<T>` can't tell a missing field, which leaves the stored value
+//! alone, from a `null`, which clears it.  [Patch] keeps all three apart.
+//! Give the field `#[serde(default)]`, so a missing field is
+//! [Patch::Missing], and `skip_serializing_if = "Patch::is_missing"`, so
+//! it stays missing when written.
+//!
+//! [Patch::apply] and [Patch::apply_option] merge a patch into the stored
+//! value.  The `Partial` derive, with the `derive` feature, generates a
+//! struct of patches for a whole struct, with an `apply` for all of them.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::patch::Patch;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(default, skip_serializing_if = "Patch::is_missing")]
+//!     pub nickname: Patch<String>,
+//! }
+//! ```
+//! The following will deserialize to `Outer` with `Missing`, `Null` and
+//! `Value("Bob")`
+//! ```json
+//! {}
+//! ```
+//! ```json
+//! {
+//!     "nickname": null
+//! }
+//! ```
+//! ```json
+//! {
+//!     "nickname": "Bob"
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A field that may be missing, `null`, or a value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Patch<T> {
+    /// Leave the stored value alone.
+    #[default]
+    Missing,
+    /// Clear the stored value.
+    Null,
+    /// Replace the stored value.
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Patch::Missing)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Patch::Null)
+    }
+
+    /// Replace `target` with the value, if there is one.  A required value
+    /// can't be cleared, so `Null` leaves it alone too.
+    pub fn apply(self, target: &mut T) {
+        if let Patch::Value(value) = self {
+            *target = value;
+        }
+    }
+
+    /// Replace `target` with the value, or clear it on `Null`.
+    pub fn apply_option(self, target: &mut Option<T>) {
+        match self {
+            Patch::Missing => {}
+            Patch::Null => *target = None,
+            Patch::Value(value) => *target = Some(value),
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Patch::Value(value) => s.serialize_some(value),
+            _ => s.serialize_none(),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(d)? {
+            Some(value) => Patch::Value(value),
+            None => Patch::Null,
+        })
+    }
+}
+
+/// Read a patch for a required value, where `null` is an error rather
+/// than [Patch::Null].  Use with `deserialize_with`.
+pub fn required<'de, D, T>(d: D) -> Result<Patch<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(d).map(Patch::Value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Patch;
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub nickname: Patch<String>,
+        #[serde(
+            default,
+            deserialize_with = "super::required",
+            skip_serializing_if = "Patch::is_missing"
+        )]
+        pub age: Patch<u32>,
+    }
+
+    #[test]
+    fn deserialize() {
+        for (json, nickname, age) in [
+            (r#"{}"#, Patch::Missing, Patch::Missing),
+            (r#"{"nickname": null}"#, Patch::Null, Patch::Missing),
+            (
+                r#"{"nickname": "Bob", "age": 42}"#,
+                Patch::Value("Bob".to_string()),
+                Patch::Value(42),
+            ),
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(&Outer { nickname, age }, &result);
+        }
+    }
+
+    #[test]
+    fn deserialize_required_null() {
+        assert!(serde_json::from_str::<Outer>(r#"{"age": null}"#).is_err());
+    }
+
+    #[test]
+    fn serialize() {
+        for (outer, json) in [
+            (
+                Outer {
+                    nickname: Patch::Missing,
+                    age: Patch::Missing,
+                },
+                r#"{}"#,
+            ),
+            (
+                Outer {
+                    nickname: Patch::Null,
+                    age: Patch::Value(7),
+                },
+                r#"{"nickname":null,"age":7}"#,
+            ),
+        ] {
+            assert_eq!(serde_json::to_string(&outer).expect("Oops!"), json);
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let mut nickname = Some("Al".to_string());
+        Patch::Missing.apply_option(&mut nickname);
+        assert_eq!(nickname.as_deref(), Some("Al"));
+        Patch::Value("Bob".to_string()).apply_option(&mut nickname);
+        assert_eq!(nickname.as_deref(), Some("Bob"));
+        Patch::Null.apply_option(&mut nickname);
+        assert_eq!(nickname, None);
+
+        let mut age = 1;
+        Patch::Null.apply(&mut age);
+        Patch::Missing.apply(&mut age);
+        assert_eq!(age, 1);
+        Patch::Value(2).apply(&mut age);
+        assert_eq!(age, 2);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn partial() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug, crate::Partial)]
+        #[partial(crate = "crate", derive(PartialEq, Debug))]
+        #[serde(rename_all = "camelCase", deny_unknown_fields)]
+        pub struct User {
+            pub user_name: String,
+            #[serde(rename = "nick")]
+            pub nickname: Option<String>,
+            pub age: u32,
+        }
+
+        let mut user = User {
+            user_name: "al".to_string(),
+            nickname: Some("Ally".to_string()),
+            age: 30,
+        };
+        let patch: PartialUser =
+            serde_json::from_str(r#"{"userName": "bob", "nick": null}"#).expect("Oops!");
+        assert_eq!(patch.age, Patch::Missing);
+        assert_eq!(
+            serde_json::to_string(&patch).expect("Oops!"),
+            r#"{"userName":"bob","nick":null}"#
+        );
+        patch.apply(&mut user);
+        assert_eq!(
+            user,
+            User {
+                user_name: "bob".to_string(),
+                nickname: None,
+                age: 30,
+            }
+        );
+
+        for json in [r#"{"age": null}"#, r#"{"user_name": "x"}"#] {
+            assert!(
+                serde_json::from_str::<PartialUser>(json).is_err(),
+                "{}",
+                json
+            );
+        }
+        assert_eq!(PartialUser::default().age, Patch::Missing);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn partial_skip() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug, crate::Partial)]
+        #[partial(crate = "crate", derive(Debug))]
+        pub struct Cached {
+            pub name: String,
+            #[serde(skip)]
+            pub cache: u32,
+        }
+
+        let mut cached = Cached {
+            name: "a".to_string(),
+            cache: 1,
+        };
+        let patch: PartialCached =
+            serde_json::from_str(r#"{"name": "b", "cache": 5}"#).expect("Oops!");
+        patch.apply(&mut cached);
+        assert_eq!(
+            cached,
+            Cached {
+                name: "b".to_string(),
+                cache: 1,
+            }
+        );
+    }
+
+    #[cfg(all(feature = "derive", feature = "base64"))]
+    #[test]
+    fn partial_with() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug, crate::Partial)]
+        #[partial(crate = "crate", derive(Debug))]
+        pub struct Keys {
+            #[serde(with = "crate::base64")]
+            pub key: Vec<u8>,
+            #[serde(default, with = "crate::option_base64")]
+            pub cert: Option<Vec<u8>>,
+            #[serde(deserialize_with = "crate::base64::deserialize")]
+            pub salt: Vec<u8>,
+        }
+
+        let mut keys = Keys {
+            key: vec![0],
+            cert: Some(vec![0]),
+            salt: vec![0],
+        };
+        let json = r#"{"key":"AQID","cert":"BAU="}"#;
+        let patch: PartialKeys = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(serde_json::to_string(&patch).expect("Oops!"), json);
+        patch.apply(&mut keys);
+        assert_eq!(keys.key, vec![1, 2, 3]);
+        assert_eq!(keys.cert, Some(vec![4, 5]));
+
+        let patch: PartialKeys =
+            serde_json::from_str(r#"{"cert": null, "salt": "Bg=="}"#).expect("Oops!");
+        assert!(patch.cert.is_null());
+        assert_eq!(
+            serde_json::to_string(&patch).expect("Oops!"),
+            r#"{"cert":null,"salt":[6]}"#
+        );
+        patch.apply(&mut keys);
+        assert_eq!(keys.cert, None);
+        assert_eq!(keys.salt, vec![6]);
+
+        for json in [r#"{"key": null}"#, r#"{"key": [1]}"#] {
+            assert!(
+                serde_json::from_str::<PartialKeys>(json).is_err(),
+                "{}",
+                json
+            );
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Add the path of the failing field to deserialize errors
+//!
+//! Errors from the modules in this crate, and from serde itself, say what
+//! went wrong, but not where.  A format may add a line and column, but a
+//! client of a JSON API can't map those back to a field.  Deserializing
+//! through [deserialize] adds the path, so an error reads `Invalid byte
+//! 33, offset 0. at items[3].signature`.
+//!
+//! The document is buffered as [Content] first, so the format must be self
+//! describing.  Paths stop at enum variants.  Use [accumulate](crate::accumulate)
+//! to collect every error, rather than the first.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use serde_stuff::path;
+//!
+//! # #[cfg(feature = "base64")]
+//! # fn main() {
+//! #[derive(Debug, Deserialize)]
+//! pub struct Item {
+//!     #[serde(with = "serde_stuff::base64")]
+//!     pub signature: Vec<u8>,
+//! }
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Outer {
+//!     pub items: Vec<Item>,
+//! }
+//!
+//! let json = r#"{"items": [{"signature": "AQID"}, {"signature": "!"}]}"#;
+//! let err = path::deserialize::<_, Outer>(&mut serde_json::Deserializer::from_str(json))
+//!     .unwrap_err()
+//!     .to_string();
+//! assert!(err.contains(" at items[1].signature"), "{}", err);
+//! # }
+//! # #[cfg(not(feature = "base64"))]
+//! # fn main() {}
+//! ```
+//!
+//! As a field's `deserialize_with`, paths start at the field:
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use std::collections::BTreeMap;
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Outer {
+//!     #[serde(deserialize_with = "serde_stuff::path::deserialize")]
+//!     pub limits: BTreeMap<String, u32>,
+//! }
+//! ```
+//!
+//! The following will fail with `invalid value: integer \`-1\`, expected u32
+//! at disk`:
+//!
+//! ```json
+//! {
+//!     "limits": {"cpu": 2, "disk": -1}
+//! }
+//! ```
+
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+
+use super::accumulate::{first_error, FieldError};
+use super::content::Content;
+
+/// Deserialize, with the path of the failing field in the error.
+pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let content = Content::deserialize(d)?;
+    from_content(content).map_err(D::Error::custom)
+}
+
+/// [deserialize] from a buffered document.
+pub fn from_content<T: DeserializeOwned>(content: Content) -> Result<T, FieldError> {
+    first_error(content)
+}
+
+#[cfg(all(test, feature = "base64"))]
+mod tests {
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    use super::FieldError;
+    use crate::content::Content;
+
+    #[derive(Deserialize, Debug)]
+    pub struct Item {
+        #[serde(with = "crate::base64")]
+        pub signature: Vec<u8>,
+        pub count: Option<u8>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct Outer {
+        pub items: Vec<Item>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct Limits {
+        #[serde(deserialize_with = "super::deserialize")]
+        pub limits: BTreeMap<String, u32>,
+    }
+
+    fn err(json: &str) -> String {
+        super::deserialize::<_, Outer>(&mut serde_json::Deserializer::from_str(json))
+            .expect_err("Oops!")
+            .to_string()
+    }
+
+    #[test]
+    fn ok() {
+        let json = r#"{"items": [{"signature": "AQID", "count": 3}]}"#;
+        let outer: Outer =
+            super::deserialize(&mut serde_json::Deserializer::from_str(json)).expect("Oops!");
+        assert_eq!(outer.items[0].signature, vec![1, 2, 3]);
+        assert_eq!(outer.items[0].count, Some(3));
+
+        let json = r#"{"limits": {"cpu": 2}}"#;
+        let limits: Limits = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(limits.limits["cpu"], 2);
+    }
+
+    #[test]
+    fn module_error() {
+        let json = r#"{"items": [{"signature": "AQID"}, {"signature": "!"}]}"#;
+        let err = err(json);
+        assert_eq!(err, "Invalid byte 33, offset 0. at items[1].signature");
+    }
+
+    #[test]
+    fn type_error() {
+        let json = r#"{"items": [{"signature": "AQID", "count": 300}]}"#;
+        let err = err(json);
+        assert!(
+            err.starts_with("invalid value: integer `300`, expected u8 at items[0].count"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn missing_field() {
+        let json = r#"{"items": [{"count": 1}]}"#;
+        let err = err(json);
+        assert!(
+            err.starts_with("missing field `signature` at items[0]"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn field() {
+        let json = r#"{"limits": {"cpu": 2, "disk": -1}}"#;
+        let err = serde_json::from_str::<Limits>(json)
+            .expect_err("Oops!")
+            .to_string();
+        assert!(
+            err.starts_with("invalid value: integer `-1`, expected u32 at disk"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn root() {
+        let content = Content::String("items".to_string());
+        let err = super::from_content::<Outer>(content).expect_err("Oops!");
+        assert_eq!(err.path, "");
+        assert_eq!(
+            err,
+            FieldError {
+                path: String::new(),
+                message: "invalid type: string \"items\", expected struct Outer".to_string(),
+            }
+        );
+    }
+}
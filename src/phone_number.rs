@@ -0,0 +1,156 @@
+//! Deserialize a phone number, normalized to E.164, such as `"+14155550123"`
+//!
+//! Numbers must be in international form, starting with `+` or the `00`
+//! international prefix.  Spaces, hyphens, dots and parentheses are
+//! removed, so `"+1 (415) 555-0123"` and `"001.415.555.0123"` are both
+//! stored as `"+14155550123"`.  What's left must be 7 to 15 digits, with a
+//! country code that doesn't start with `0`.
+//!
+//! Those are only the E.164 syntax rules.  With the `phonenumber` feature,
+//! numbers are also checked against the libphonenumber metadata, which
+//! rejects country codes that aren't assigned and numbers of the wrong
+//! length or shape for their country.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::phone_number")]
+//!     pub mobile: String,
+//! }
+//! ```
+//! The following will deserialize to `Outer { mobile: "+442071838750".to_string() }`
+//! ```json
+//! {
+//!     "mobile": "+44 20 7183 8750"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+#[cfg(feature = "phonenumber")]
+fn check(value: &str, e164: &str) -> Result<(), String> {
+    match phonenumber::parse(None, e164) {
+        Ok(number) if phonenumber::is_valid(&number) => Ok(()),
+        _ => Err(format!("invalid phone number \"{}\"", value)),
+    }
+}
+
+#[cfg(not(feature = "phonenumber"))]
+fn check(_value: &str, _e164: &str) -> Result<(), String> {
+    Ok(())
+}
+
+/// Validate `value`, and return it in E.164 form.
+pub(crate) fn normalize(value: &str) -> Result<String, String> {
+    let bad = || format!("invalid phone number \"{}\"", value);
+    let trimmed = value.trim();
+    let number = match trimmed.strip_prefix('+') {
+        Some(number) => number,
+        None => trimmed.strip_prefix("00").ok_or_else(bad)?,
+    };
+    let mut digits = String::with_capacity(number.len() + 1);
+    digits.push('+');
+    for c in number.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' | '-' | '.' | '(' | ')' => {}
+            _ => return Err(bad()),
+        }
+    }
+    if !(8..=16).contains(&digits.len()) || digits.starts_with("+0") {
+        return Err(bad());
+    }
+    check(value, &digits)?;
+    Ok(digits)
+}
+
+/// Phone numbers are serialized unchanged.
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    str::serialize(v, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let value = String::deserialize(d)?;
+    normalize(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::phone_number")]
+        pub phone: String,
+    }
+
+    #[test]
+    fn deserialize() {
+        for phone in [
+            "+442071838750",
+            "+44 20 7183 8750",
+            "0044 (20) 7183-8750",
+            " +44.20.7183.8750 ",
+        ] {
+            let json = format!(r#"{{"phone": "{}"}}"#, phone);
+            let result: Outer = serde_json::from_str(&json).expect("Oops!");
+            assert_eq!(result.phone, "+442071838750");
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            phone: "+442071838750".to_string(),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"phone":"+442071838750"}"#);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for phone in [
+            "020 7183 8750",
+            "+44 20 7183 875x",
+            "+44/20/7183/8750",
+            "+123456",
+            "+1234567890123456",
+            "+0442071838750",
+            "+",
+            "",
+        ] {
+            let json = format!(r#"{{"phone": "{}"}}"#, phone);
+            let msg = serde_json::from_str::<Outer>(&json)
+                .unwrap_err()
+                .to_string();
+            assert!(
+                msg.contains(&format!("invalid phone number \"{}\"", phone)),
+                "{}",
+                msg
+            );
+        }
+    }
+
+    #[cfg(feature = "phonenumber")]
+    #[test]
+    fn metadata() {
+        for phone in ["+4420718387", "+9991234567"] {
+            let json = format!(r#"{{"phone": "{}"}}"#, phone);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", json);
+        }
+    }
+
+    #[cfg(not(feature = "phonenumber"))]
+    #[test]
+    fn syntax_only() {
+        let json = r#"{"phone": "+9991234567"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.phone, "+9991234567");
+    }
+}
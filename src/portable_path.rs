@@ -0,0 +1,174 @@
+//! Serialize and Deserialize a `PathBuf` portably across operating systems
+//!
+//! Config files are shared between Windows and Unix machines, so paths
+//! written on one must work on the other.  On deserialize, both `\` and `/`
+//! are accepted as separators, and repeated separators are collapsed.  On
+//! serialize, paths are always written with `/`, which every supported OS
+//! accepts.  Paths that are not valid UTF-8 can't be serialized.
+//!
+//! Untrusted configs can also restrict where a path may point:
+//!
+//! - `portable_path::relative` rejects absolute paths, including Windows
+//!   drive (`C:\`) and UNC (`\\server\share`) paths.
+//! - `portable_path::contained` also rejects `..` components, so the path
+//!   can't climb out of the directory it is joined to.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::path::PathBuf;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::portable_path")]
+//!     pub cache: PathBuf,
+//!     #[serde(with = "serde_stuff::portable_path::contained")]
+//!     pub template: PathBuf,
+//! }
+//! ```
+//! The following will deserialize to `Outer`, and serialize with `/`
+//! ```json
+//! {
+//!     "cache": "C:\\Users\\me\\cache",
+//!     "template": "templates\\email\\welcome.html"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::path::{Path, PathBuf};
+
+fn normalize(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let unc = value.starts_with("\\\\") || value.starts_with("//");
+    if unc {
+        out.push('/');
+    }
+    for c in value.chars() {
+        let c = if c == '\\' { '/' } else { c };
+        if c == '/' && out.ends_with('/') && !(unc && out.len() == 1) {
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn is_absolute(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    value.starts_with('/')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+pub(crate) fn parse(value: &str, relative: bool, contained: bool) -> Result<PathBuf, String> {
+    let value = normalize(value);
+    if (relative || contained) && is_absolute(&value) {
+        return Err(format!("path \"{}\" must be relative", value));
+    }
+    if contained && value.split('/').any(|c| c == "..") {
+        return Err(format!("path \"{}\" must not contain \"..\"", value));
+    }
+    Ok(PathBuf::from(value))
+}
+
+pub(crate) fn format(v: &Path) -> Result<String, String> {
+    v.to_str()
+        .map(normalize)
+        .ok_or_else(|| format!("path {:?} is not valid UTF-8", v))
+}
+
+pub fn serialize<S: Serializer>(v: &Path, s: S) -> Result<S::Ok, S::Error> {
+    let value = format(v).map_err(serde::ser::Error::custom)?;
+    String::serialize(&value, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<PathBuf, D::Error> {
+    let value = String::deserialize(d)?;
+    parse(&value, false, false).map_err(serde::de::Error::custom)
+}
+
+/// Reject absolute paths.
+pub mod relative {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::path::{Path, PathBuf};
+
+    pub fn serialize<S: Serializer>(v: &Path, s: S) -> Result<S::Ok, S::Error> {
+        super::serialize(v, s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<PathBuf, D::Error> {
+        let value = String::deserialize(d)?;
+        super::parse(&value, true, false).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Reject absolute paths and `..` components.
+pub mod contained {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::path::{Path, PathBuf};
+
+    pub fn serialize<S: Serializer>(v: &Path, s: S) -> Result<S::Ok, S::Error> {
+        super::serialize(v, s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<PathBuf, D::Error> {
+        let value = String::deserialize(d)?;
+        super::parse(&value, true, true).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::path::PathBuf;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::portable_path")]
+        pub cache: PathBuf,
+        #[serde(with = "crate::portable_path::relative")]
+        pub output: PathBuf,
+        #[serde(with = "crate::portable_path::contained")]
+        pub template: PathBuf,
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{
+            "cache": "C:\\Users\\me\\\\cache",
+            "output": "..\\build/out",
+            "template": "templates\\email/welcome.html"
+        }"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(
+            result.template,
+            PathBuf::from("templates/email/welcome.html")
+        );
+        assert_eq!(
+            serde_json::to_string(&result).expect("Oops!"),
+            r#"{"cache":"C:/Users/me/cache","output":"../build/out","template":"templates/email/welcome.html"}"#
+        );
+    }
+
+    #[test]
+    fn unc() {
+        let json = r#"{"cache": "\\\\server\\share", "output": "a", "template": "b"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.cache, PathBuf::from("//server/share"));
+    }
+
+    #[test]
+    fn deserialize_rejected() {
+        for json in [
+            r#"{"cache": "a", "output": "/etc/passwd", "template": "b"}"#,
+            r#"{"cache": "a", "output": "c:\\temp", "template": "b"}"#,
+            r#"{"cache": "a", "output": "\\\\server\\share", "template": "b"}"#,
+            r#"{"cache": "a", "output": "b", "template": "../secrets"}"#,
+            r#"{"cache": "a", "output": "b", "template": "x\\..\\..\\secrets"}"#,
+        ] {
+            assert!(serde_json::from_str::<Outer>(json).is_err(), "{}", json);
+        }
+    }
+}
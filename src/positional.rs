@@ -0,0 +1,177 @@
+//! Serialize and Deserialize a struct as an array of its field values
+//!
+//! Compact wire formats, such as CSV rows and market data feeds, send
+//! `["AAPL", 189.5, 300]` and leave the field names implied by position.
+//! This module writes a struct's fields as an array in declaration order,
+//! and reads them back from one.  The named map form is accepted too.
+//!
+//! A short array leaves the trailing fields missing, which is fine for
+//! `Option` and `#[serde(default)]` fields.  A long array is an error.
+//! Fields skipped with `skip_serializing_if` would shift the positions of
+//! those after them, so don't use it on positional structs.  Since the value
+//! is buffered as [Content](crate::content::Content), the format must be
+//! self describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Tick {
+//!     pub symbol: String,
+//!     pub price: f64,
+//!     pub size: u32,
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::positional")]
+//!     pub tick: Tick,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "tick": ["AAPL", 189.5, 300]
+//! }
+//! ```
+
+use serde::de::value::MapDeserializer;
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::marker::PhantomData;
+
+use super::content::{Content, ContentDeserializer, ContentSerializer};
+
+/// Gives a sequence to a struct as a map of its fields, in order.
+struct Positional<E> {
+    items: Vec<Content>,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for Positional<E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        ContentDeserializer::new(Content::Seq(self.items)).deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        if self.items.len() > fields.len() {
+            return Err(de::Error::invalid_length(
+                self.items.len(),
+                &format!("at most {} fields", fields.len()).as_str(),
+            ));
+        }
+        let entries = fields
+            .iter()
+            .map(|field| Content::String(field.to_string()))
+            .zip(self.items);
+        let mut map = MapDeserializer::new(entries);
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+pub fn serialize<S: Serializer, T: Serialize>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    match v.serialize(ContentSerializer::<S::Error>::new())? {
+        Content::Map(entries) => s.collect_seq(entries.into_iter().map(|(_, v)| v)),
+        _ => Err(serde::ser::Error::custom("expected a struct")),
+    }
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    match Content::deserialize(d)? {
+        Content::Seq(items) => T::deserialize(Positional {
+            items,
+            marker: PhantomData,
+        }),
+        content => T::deserialize(ContentDeserializer::<D::Error>::new(content)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Tick {
+        pub symbol: String,
+        pub price: f64,
+        pub size: u32,
+        pub venue: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::positional")]
+        pub tick: Tick,
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            tick: Tick {
+                symbol: "AAPL".to_string(),
+                price: 189.5,
+                size: 300,
+                venue: None,
+            },
+        };
+        for json in [
+            r#"{"tick": ["AAPL", 189.5, 300]}"#,
+            r#"{"tick": ["AAPL", 189.5, 300, null]}"#,
+            r#"{"tick": {"symbol": "AAPL", "price": 189.5, "size": 300}}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(&outer, &result);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            tick: Tick {
+                symbol: "AAPL".to_string(),
+                price: 189.5,
+                size: 300,
+                venue: Some("XNAS".to_string()),
+            },
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"tick":["AAPL",189.5,300,"XNAS"]}"#);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (tick, msg) in [
+            (r#"["AAPL", 189.5]"#, "missing field `size`"),
+            (r#"["AAPL", 189.5, 300, null, 1]"#, "invalid length 5"),
+            (r#"["AAPL", "high", 300]"#, "invalid type: string \"high\""),
+        ] {
+            let json = format!(r#"{{"tick": {}}}"#, tick);
+            let err = serde_json::from_str::<Outer>(&json)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+}
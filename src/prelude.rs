@@ -0,0 +1,121 @@
+//! The common types, traits and modules, in one import
+//!
+//! ```rust
+//! use serde_stuff::prelude::*;
+//! ```
+//!
+//! brings in the [adapters](crate::adapters) and [compose](crate::compose)
+//! types, the wrapper types such as [VecOrOne], [Either], [Email] and
+//! [Extra], the traits that types implement to work with a module, such as
+//! [EpochTime] and [IntEnum], and the most used `with` modules, so that
+//! `#[serde(with = "timestamp_secs")]` works without the crate path.
+//!
+//! The names are re-exports, so the crate paths keep working.  Modules
+//! behind a feature, and the less common ones, still need their crate path.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::prelude::*;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "trimmed")]
+//!     pub name: String,
+//!     #[serde(with = "As::<OneOrMany>")]
+//!     pub hosts: Vec<String>,
+//!     #[serde(deserialize_with = "string_or_struct::deserialize")]
+//!     pub owner: Owner,
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Owner {
+//!     pub name: String,
+//! }
+//!
+//! impl std::str::FromStr for Owner {
+//!     type Err = void::Void;
+//!
+//!     fn from_str(s: &str) -> Result<Self, Self::Err> {
+//!         Ok(Owner { name: s.to_string() })
+//!     }
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "name": " web ",
+//!     "hosts": "a.example",
+//!     "owner": "alice"
+//! }
+//! ```
+
+pub use crate::adapters::{
+    As, DeserializeAs, DisplayFromStr, Hex, OneOrMany, Same, SerializeAs, StringOrStruct,
+};
+#[cfg(feature = "base64")]
+pub use crate::adapters::{Base64, Standard, UrlSafe};
+pub use crate::content::Content;
+pub use crate::either::{Either, Either3};
+pub use crate::patch::Patch;
+pub use crate::rename_all::Case;
+pub use crate::vec_or_one::VecOrOne;
+#[cfg(feature = "std")]
+pub use crate::{
+    compose::{Compose, Dedup, Lowercase, Parse, Step, Trim},
+    email::Email,
+    enum_int::IntEnum,
+    epoch::EpochTime,
+    extra::Extra,
+    hex_color::{Rgb, Rgba},
+    keyed_vec::Keyed,
+    name_or_code::NameOrCode,
+    rfc3339::Rfc3339Time,
+};
+
+#[cfg(feature = "base64")]
+pub use crate::{base64, option_base64};
+#[cfg(feature = "std")]
+pub use crate::{
+    duration_secs, human_duration, option_duration_secs, option_human_duration, option_rfc3339,
+    option_timestamp_millis, option_timestamp_secs, rfc3339, timestamp_millis, timestamp_secs,
+};
+pub use crate::{
+    option_string_or_struct, option_trimmed, option_vec_or_one, string_or_struct, trimmed,
+    vec_or_one,
+};
+
+#[cfg(all(test, feature = "std", feature = "base64"))]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "base64")]
+        pub key: Vec<u8>,
+        #[serde(with = "As::<OneOrMany>")]
+        pub hosts: Vec<String>,
+        #[serde(with = "timestamp_secs")]
+        pub expires: SystemTime,
+        #[serde(default, with = "option_timestamp_millis")]
+        pub seen: Option<SystemTime>,
+        pub owner: Email,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"key": "3q2-7w==", "hosts": "a.example", "expires": 60, "owner": "alice@example.com"}"#;
+        let outer = Outer {
+            key: vec![0xde, 0xad, 0xbe, 0xef],
+            hosts: vec!["a.example".to_string()],
+            expires: UNIX_EPOCH + Duration::from_secs(60),
+            seen: None,
+            owner: serde_json::from_str(r#""alice@example.com""#).expect("Oops!"),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
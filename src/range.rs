@@ -0,0 +1,208 @@
+//! Serialize and Deserialize range strings to `std::ops` ranges
+//!
+//! The accepted forms depend on the target type:
+//!
+//! | Target              | Accepted             | Serialized as |
+//! |---------------------|----------------------|---------------|
+//! | `Range<T>`          | `"10..20"`           | `"10..20"`    |
+//! | `RangeInclusive<T>` | `"1-5"`, `"1..=5"`   | `"1-5"`       |
+//! | `RangeFrom<T>`      | `"100.."`            | `"100.."`     |
+//!
+//! A form that doesn't match the target, such as `"1-5"` for a `Range<T>`,
+//! is rejected rather than silently shifting an endpoint.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::ops::{RangeFrom, RangeInclusive};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::range")]
+//!     pub ports: RangeInclusive<u16>,
+//!     #[serde(with = "serde_stuff::range")]
+//!     pub versions: RangeFrom<u32>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "ports": "8000-8080",
+//!     "versions": "3.."
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::fmt::Display;
+use std::ops::{Range, RangeFrom, RangeInclusive};
+use std::str::FromStr;
+
+/// The shape of a parsed range string, before it is matched to a target type.
+pub enum RangeForm<T> {
+    Exclusive(T, T),
+    Inclusive(T, T),
+    From(T),
+}
+
+/// Range types that can be built from, and written as, a range string.
+pub trait RangeValue: Sized {
+    type Idx: FromStr + Display;
+
+    fn from_form(form: RangeForm<Self::Idx>) -> Result<Self, &'static str>;
+    fn to_range_string(&self) -> String;
+}
+
+impl<T: FromStr + Display> RangeValue for Range<T> {
+    type Idx = T;
+
+    fn from_form(form: RangeForm<T>) -> Result<Self, &'static str> {
+        match form {
+            RangeForm::Exclusive(start, end) => Ok(start..end),
+            _ => Err("an exclusive range such as \"10..20\""),
+        }
+    }
+
+    fn to_range_string(&self) -> String {
+        format!("{}..{}", self.start, self.end)
+    }
+}
+
+impl<T: FromStr + Display> RangeValue for RangeInclusive<T> {
+    type Idx = T;
+
+    fn from_form(form: RangeForm<T>) -> Result<Self, &'static str> {
+        match form {
+            RangeForm::Inclusive(start, end) => Ok(start..=end),
+            _ => Err("an inclusive range such as \"1-5\" or \"1..=5\""),
+        }
+    }
+
+    fn to_range_string(&self) -> String {
+        format!("{}-{}", self.start(), self.end())
+    }
+}
+
+impl<T: FromStr + Display> RangeValue for RangeFrom<T> {
+    type Idx = T;
+
+    fn from_form(form: RangeForm<T>) -> Result<Self, &'static str> {
+        match form {
+            RangeForm::From(start) => Ok(start..),
+            _ => Err("an open range such as \"100..\""),
+        }
+    }
+
+    fn to_range_string(&self) -> String {
+        format!("{}..", self.start)
+    }
+}
+
+fn parse_bound<T: FromStr>(bound: &str, value: &str) -> Result<T, String> {
+    bound
+        .trim()
+        .parse::<T>()
+        .map_err(|_| format!("invalid range bound \"{}\" in \"{}\"", bound.trim(), value))
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<RangeForm<T>, String> {
+    let trimmed = value.trim();
+    if let Some((start, end)) = trimmed.split_once("..") {
+        let start = parse_bound(start, value)?;
+        if end.trim().is_empty() {
+            return Ok(RangeForm::From(start));
+        }
+        return match end.strip_prefix('=') {
+            Some(end) => Ok(RangeForm::Inclusive(start, parse_bound(end, value)?)),
+            None => Ok(RangeForm::Exclusive(start, parse_bound(end, value)?)),
+        };
+    }
+    // Skip the first character, so that a negative start isn't taken as the separator
+    match trimmed.char_indices().skip(1).find(|(_, c)| *c == '-') {
+        Some((idx, _)) => Ok(RangeForm::Inclusive(
+            parse_bound(&trimmed[..idx], value)?,
+            parse_bound(&trimmed[idx + 1..], value)?,
+        )),
+        None => Err(format!("expected a range string, found \"{}\"", value)),
+    }
+}
+
+pub fn serialize<S: Serializer, T: RangeValue>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    String::serialize(&v.to_range_string(), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: RangeValue>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    let form = parse(&value).map_err(serde::de::Error::custom)?;
+    T::from_form(form).map_err(|expected| {
+        serde::de::Error::custom(format!("expected {}, found \"{}\"", expected, value))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::ops::{Range, RangeFrom, RangeInclusive};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::range")]
+        pub exclusive: Range<u32>,
+        #[serde(with = "crate::range")]
+        pub inclusive: RangeInclusive<i32>,
+        #[serde(with = "crate::range")]
+        pub from: RangeFrom<u64>,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            exclusive: 10..20,
+            inclusive: -5..=-1,
+            from: 100..,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"exclusive":"10..20","inclusive":"-5--1","from":"100.."}"#
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "exclusive": "10..20",
+            "inclusive": "1-5",
+            "from": "100.."
+        }"#;
+        let outer = Outer {
+            exclusive: 10..20,
+            inclusive: 1..=5,
+            from: 100..,
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+
+        let json = r#"{
+            "exclusive": "10..20",
+            "inclusive": "-5..=-1",
+            "from": "100.."
+        }"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.inclusive, -5..=-1);
+    }
+
+    #[test]
+    fn deserialize_mismatched_form() {
+        let json = r#"{
+            "exclusive": "10-20",
+            "inclusive": "1-5",
+            "from": "100.."
+        }"#;
+        let result = serde_json::from_str::<Outer>(json);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("exclusive range"));
+    }
+}
@@ -0,0 +1,151 @@
+//! Serialize and Deserialize ratio strings such as `"16:9"` or `"3/4"`
+//!
+//! Both the colon and slash forms are accepted on deserialize.  Ratios are
+//! always serialized in the colon form.  The target can be a `(u32, u32)`
+//! tuple, or, with the `num-rational` feature, a `num_rational::Ratio<T>`.
+//! Note that `Ratio` reduces its value, so `"4:2"` will serialize as `"2:1"`.
+//!
+//! A zero denominator is rejected in either case.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::ratio")]
+//!     pub aspect: (u32, u32),
+//! }
+//! ```
+//! The following will both deserialize to `Outer { aspect: (16, 9) }`
+//! ```json
+//! {
+//!     "aspect": "16:9"
+//! }
+//! ```
+//! ```json
+//! {
+//!     "aspect": "16/9"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Types that can be built from, and split into, a numerator and denominator.
+pub trait RatioParts: Sized {
+    type Int: FromStr + Display + PartialEq + Default;
+
+    fn from_parts(numer: Self::Int, denom: Self::Int) -> Self;
+    fn parts(&self) -> (Self::Int, Self::Int);
+}
+
+impl RatioParts for (u32, u32) {
+    type Int = u32;
+
+    fn from_parts(numer: u32, denom: u32) -> Self {
+        (numer, denom)
+    }
+
+    fn parts(&self) -> (u32, u32) {
+        *self
+    }
+}
+
+#[cfg(feature = "num-rational")]
+impl<T> RatioParts for num_rational::Ratio<T>
+where
+    T: Clone + num_integer::Integer + FromStr + Display + Default,
+{
+    type Int = T;
+
+    fn from_parts(numer: T, denom: T) -> Self {
+        num_rational::Ratio::new(numer, denom)
+    }
+
+    fn parts(&self) -> (T, T) {
+        (self.numer().clone(), self.denom().clone())
+    }
+}
+
+fn parse<T: RatioParts>(value: &str) -> Result<T, String> {
+    let (numer, denom) = value
+        .split_once(':')
+        .or_else(|| value.split_once('/'))
+        .ok_or_else(|| format!("expected a ratio such as \"16:9\", found \"{}\"", value))?;
+    let numer = numer
+        .trim()
+        .parse::<T::Int>()
+        .map_err(|_| format!("invalid ratio numerator in \"{}\"", value))?;
+    let denom = denom
+        .trim()
+        .parse::<T::Int>()
+        .map_err(|_| format!("invalid ratio denominator in \"{}\"", value))?;
+    if denom == T::Int::default() {
+        return Err(format!("ratio \"{}\" has a zero denominator", value));
+    }
+    Ok(T::from_parts(numer, denom))
+}
+
+pub fn serialize<S: Serializer, T: RatioParts>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let (numer, denom) = v.parts();
+    String::serialize(&format!("{}:{}", numer, denom), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: RatioParts>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    parse(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::ratio")]
+        pub aspect: (u32, u32),
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer { aspect: (16, 9) };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"aspect":"16:9"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer { aspect: (4, 3) };
+        let result: Outer = serde_json::from_str(r#"{"aspect": "4:3"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+        let result: Outer = serde_json::from_str(r#"{"aspect": "4/3"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        assert!(serde_json::from_str::<Outer>(r#"{"aspect": "16x9"}"#).is_err());
+        assert!(serde_json::from_str::<Outer>(r#"{"aspect": "a:9"}"#).is_err());
+        assert!(serde_json::from_str::<Outer>(r#"{"aspect": "16:0"}"#).is_err());
+    }
+
+    #[cfg(feature = "num-rational")]
+    #[test]
+    fn rational() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Rational {
+            #[serde(with = "crate::ratio")]
+            pub value: num_rational::Ratio<i32>,
+        }
+
+        let result: Rational = serde_json::from_str(r#"{"value": "-4/2"}"#).expect("Oops!");
+        assert_eq!(result.value, num_rational::Ratio::new(-2, 1));
+        let result = serde_json::to_string(&result).expect("Oops!");
+        assert_eq!(result, r#"{"value":"-2:1"}"#);
+    }
+}
@@ -0,0 +1,252 @@
+//! Serialize a secret as `"***"`, and deserialize it as usual
+//!
+//! Config structs often hold passwords and API keys, and the same structs
+//! get serialized for debug exports and logs.  Fields using this module
+//! deserialize normally, but always serialize as [REDACTED], so the secret
+//! never leaves the process.  Note that the config can't be written back
+//! with the secret.
+//!
+//! [Redacted] does the same as a wrapper type, and also hides the value
+//! from `Debug` and `Display`.  With the `sha2` feature, `hashed`
+//! serializes a SHA-256 fingerprint instead, so two exports can show that a
+//! secret changed without showing it.  With the `secrecy` feature, `secret`
+//! reads a `secrecy::SecretBox`, such as a `SecretString`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::redact::Redacted;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     pub user: String,
+//!     #[serde(with = "serde_stuff::redact")]
+//!     pub password: String,
+//!     pub token: Option<Redacted<String>>,
+//! }
+//!
+//! let outer: Outer =
+//!     serde_json::from_str(r#"{"user": "al", "password": "hunter2", "token": "abc"}"#).unwrap();
+//! assert_eq!(outer.password, "hunter2");
+//! assert_eq!(
+//!     serde_json::to_string(&outer).unwrap(),
+//!     r#"{"user":"al","password":"***","token":"***"}"#
+//! );
+//! ```
+
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// What a secret serializes as.
+pub const REDACTED: &str = "***";
+
+pub fn serialize<S: Serializer, T: ?Sized>(_: &T, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(REDACTED)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(d: D) -> Result<T, D::Error> {
+    T::deserialize(d)
+}
+
+/// A value that deserializes as `T`, and serializes, debugs and displays as
+/// [REDACTED].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Redacted<T>(pub T);
+
+impl<T> Redacted<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Redacted<T> {
+    fn from(value: T) -> Self {
+        Redacted(value)
+    }
+}
+
+impl<T> Deref for Redacted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Redacted<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> Serialize for Redacted<T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, s)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Redacted<T> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        T::deserialize(d).map(Redacted)
+    }
+}
+
+/// Serialize the SHA-256 of a secret as `sha256:` and lowercase hex.
+///
+/// The same secret always has the same fingerprint, so a short or common
+/// secret can be found by guessing.  Use it for keys and tokens, not
+/// passwords.
+#[cfg(feature = "sha2")]
+pub mod hashed {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write;
+
+    pub fn serialize<S: Serializer, T: ?Sized + AsRef<[u8]>>(
+        v: &T,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut fingerprint = String::from("sha256:");
+        for byte in Sha256::digest(v.as_ref()) {
+            let _ = write!(fingerprint, "{:02x}", byte);
+        }
+        s.serialize_str(&fingerprint)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+        d: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize(d)
+    }
+}
+
+/// Read a `secrecy::SecretBox`, such as a `SecretString`, and serialize it
+/// as [REDACTED](super::REDACTED).
+///
+/// The deserialized value is moved into the box, but a format may have
+/// buffered a copy that isn't zeroized.
+#[cfg(feature = "secrecy")]
+pub mod secret {
+    use secrecy::zeroize::Zeroize;
+    use secrecy::SecretBox;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, T: Zeroize + ?Sized>(
+        v: &SecretBox<T>,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize(v, s)
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<SecretBox<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Zeroize + ?Sized,
+        Box<T>: Deserialize<'de>,
+    {
+        Box::<T>::deserialize(d).map(SecretBox::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::Redacted;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        pub user: String,
+        #[serde(with = "crate::redact")]
+        pub password: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub token: Option<Redacted<String>>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"user": "al", "password": "hunter2", "token": "abc"}"#;
+        let outer: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(outer.password, "hunter2");
+        assert_eq!(outer.token.as_deref().map(|t| t.as_str()), Some("abc"));
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            user: "al".to_string(),
+            password: "hunter2".to_string(),
+            token: Some(Redacted("abc".to_string())),
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(json, r#"{"user":"al","password":"***","token":"***"}"#);
+
+        let outer = Outer {
+            token: None,
+            ..outer
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(json, r#"{"user":"al","password":"***"}"#);
+    }
+
+    #[test]
+    fn debug() {
+        let token = Redacted("abc".to_string());
+        assert_eq!(format!("{:?}", token), "***");
+        assert_eq!(token.to_string(), "***");
+        assert_eq!(token.into_inner(), "abc");
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn hashed() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::redact::hashed")]
+            pub key: String,
+        }
+
+        let outer: Outer = serde_json::from_str(r#"{"key": "abc"}"#).expect("Oops!");
+        assert_eq!(outer.key, "abc");
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            json,
+            r#"{"key":"sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"}"#
+        );
+    }
+
+    #[cfg(feature = "secrecy")]
+    #[test]
+    fn secret() {
+        use secrecy::{ExposeSecret, SecretBox, SecretString};
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::redact::secret")]
+            pub password: SecretString,
+            #[serde(with = "crate::redact::secret")]
+            pub pin: SecretBox<u32>,
+        }
+
+        let json = r#"{"password": "hunter2", "pin": 1234}"#;
+        let outer: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(outer.password.expose_secret(), "hunter2");
+        assert_eq!(*outer.pin.expose_secret(), 1234);
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(json, r#"{"password":"***","pin":"***"}"#);
+    }
+}
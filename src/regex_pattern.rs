@@ -0,0 +1,70 @@
+//! Serialize and Deserialize a compiled `regex::Regex` as its pattern string
+//!
+//! Rule engine configs hold patterns as strings, but every consumer wants
+//! them compiled.  Patterns are compiled on deserialize, so a bad pattern
+//! fails with the rest of the config, and the error includes the pattern.
+//! On serialize, the original pattern is written back with `as_str()`.
+//!
+//! Requires the `regex` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use regex::Regex;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::regex_pattern")]
+//!     pub matches: Regex,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "matches": "^/api/v[0-9]+/"
+//! }
+//! ```
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(v: &Regex, s: S) -> Result<S::Ok, S::Error> {
+    str::serialize(v.as_str(), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Regex, D::Error> {
+    let pattern = String::deserialize(d)?;
+    Regex::new(&pattern)
+        .map_err(|e| serde::de::Error::custom(format!("invalid regex \"{}\": {}", pattern, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::regex_pattern")]
+        pub matches: Regex,
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{"matches":"^/api/v[0-9]+/"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert!(result.matches.is_match("/api/v2/users"));
+        assert!(!result.matches.is_match("/web/v2/users"));
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"matches":"^/api/(v[0-9]+"}"#;
+        let msg = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(msg.contains("invalid regex \"^/api/(v[0-9]+\""), "{}", msg);
+    }
+}
@@ -0,0 +1,303 @@
+//! Rename struct fields and enum variants to a case chosen at runtime
+//!
+//! `#[serde(rename_all = "...")]` fixes the case convention when the type is
+//! written.  When the same types go to partner APIs that disagree, one
+//! wanting `camelCase` and another `SCREAMING_SNAKE_CASE`, the convention
+//! has to be picked at runtime.  [Case] names the conventions serde knows,
+//! and parses from the same strings, such as `"kebab-case"`.
+//!
+//! - [serialize_with] and [WithCase] write field and variant names in the
+//!   case.  Map keys are left alone.  Structs are written as maps.
+//! - [deserialize_with] and [CaseSeed] read field and variant names in the
+//!   case, as well as the names the type already uses.
+//!
+//! Names are converted from the ones serde has, after any `rename` or
+//! `rename_all` on the type.  Since the value is buffered as
+//! [Content](crate::content::Content), the format must be self describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde::de::DeserializeSeed;
+//! use serde_stuff::rename_all::{Case, CaseSeed, WithCase};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Order {
+//!     pub order_id: u32,
+//!     pub ship_to: String,
+//! }
+//!
+//! let order = Order { order_id: 7, ship_to: "Oslo".to_string() };
+//! let case: Case = "camelCase".parse().unwrap();
+//! let json = serde_json::to_string(&WithCase(&order, case)).unwrap();
+//! assert_eq!(json, r#"{"orderId":7,"shipTo":"Oslo"}"#);
+//!
+//! let mut de = serde_json::Deserializer::from_str(&json);
+//! let order: Order = CaseSeed::new(case).deserialize(&mut de).unwrap();
+//! assert_eq!(order.ship_to, "Oslo");
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
+use serde::de::{DeserializeOwned, DeserializeSeed};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::content::{Content, ContentSerializer};
+use super::renamed::Renamed;
+
+/// The case conventions of serde's `rename_all`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Case {
+    /// `lowercase`
+    Lower,
+    /// `UPPERCASE`
+    Upper,
+    /// `PascalCase`
+    Pascal,
+    /// `camelCase`
+    Camel,
+    /// `snake_case`
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+    /// `kebab-case`
+    Kebab,
+    /// `SCREAMING-KEBAB-CASE`
+    ScreamingKebab,
+}
+
+impl Case {
+    const NAMES: [(&'static str, Case); 8] = [
+        ("lowercase", Case::Lower),
+        ("UPPERCASE", Case::Upper),
+        ("PascalCase", Case::Pascal),
+        ("camelCase", Case::Camel),
+        ("snake_case", Case::Snake),
+        ("SCREAMING_SNAKE_CASE", Case::ScreamingSnake),
+        ("kebab-case", Case::Kebab),
+        ("SCREAMING-KEBAB-CASE", Case::ScreamingKebab),
+    ];
+
+    /// Convert a `snake_case` field or `PascalCase` variant name.
+    pub fn apply(&self, name: &str) -> String {
+        let words = words(name);
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        };
+        match self {
+            Case::Lower => name.to_lowercase(),
+            Case::Upper => name.to_uppercase(),
+            Case::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+            Case::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| match i {
+                    0 => w.to_lowercase(),
+                    _ => capitalize(w),
+                })
+                .collect(),
+            Case::Snake => join(&words, "_", str::to_lowercase),
+            Case::ScreamingSnake => join(&words, "_", str::to_uppercase),
+            Case::Kebab => join(&words, "-", str::to_lowercase),
+            Case::ScreamingKebab => join(&words, "-", str::to_uppercase),
+        }
+    }
+}
+
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, _) = Case::NAMES.iter().find(|(_, case)| case == self).unwrap();
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Case {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Case, String> {
+        Case::NAMES
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, case)| *case)
+            .ok_or_else(|| format!("invalid case \"{}\"", s))
+    }
+}
+
+/// Split a name at `_`, `-` and lower to upper case changes, keeping
+/// acronyms such as `HTTPServer` together as `HTTP` and `Server`.
+fn words(name: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for part in name.split(['_', '-']).filter(|part| !part.is_empty()) {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (at, c) = chars[i];
+            let prev = chars[i - 1].1;
+            let next_lower = chars.get(i + 1).is_some_and(|(_, n)| n.is_lowercase());
+            if c.is_uppercase() && (!prev.is_uppercase() || next_lower) {
+                words.push(&part[start..at]);
+                start = at;
+            }
+        }
+        words.push(&part[start..]);
+    }
+    words
+}
+
+fn join(words: &[&str], sep: &str, convert: fn(&str) -> String) -> String {
+    words
+        .iter()
+        .map(|w| convert(w))
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+/// Serialize `v` with field and variant names in `case`.
+pub fn serialize_with<S, T>(v: &T, s: S, case: Case) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ?Sized + Serialize,
+{
+    v.serialize(ContentSerializer::<S::Error>::renaming(case))?
+        .serialize(s)
+}
+
+/// Deserialize a `T` with field and variant names in `case`.
+pub fn deserialize_with<'de, D, T>(d: D, case: Case) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let rename = |name: &str, names: &'static [&'static str]| {
+        names
+            .iter()
+            .find(|known| case.apply(known) == name)
+            .copied()
+    };
+    T::deserialize(Renamed::new(Content::deserialize(d)?, &rename))
+}
+
+/// Serializes the value with field and variant names in the case.
+pub struct WithCase<'a, T: ?Sized>(pub &'a T, pub Case);
+
+impl<T: ?Sized + Serialize> Serialize for WithCase<'_, T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_with(self.0, s, self.1)
+    }
+}
+
+/// Deserializes a `T` with field and variant names in the case.
+pub struct CaseSeed<T> {
+    case: Case,
+    marker: PhantomData<T>,
+}
+
+impl<T> CaseSeed<T> {
+    pub fn new(case: Case) -> Self {
+        CaseSeed {
+            case,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> DeserializeSeed<'de> for CaseSeed<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<T, D::Error> {
+        deserialize_with(d, self.case)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeSeed;
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::BTreeMap;
+
+    use super::{Case, CaseSeed, WithCase};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub enum Status {
+        InTransit,
+        OnHold { hold_reason: String },
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Order {
+        pub order_id: u32,
+        pub status: Status,
+        pub line_items: BTreeMap<String, u32>,
+    }
+
+    fn order() -> Order {
+        Order {
+            order_id: 7,
+            status: Status::OnHold {
+                hold_reason: "fraud_check".to_string(),
+            },
+            line_items: BTreeMap::from([("sku_id".to_string(), 2)]),
+        }
+    }
+
+    #[test]
+    fn apply() {
+        for (case, field, variant) in [
+            (Case::Lower, "order_id", "intransit"),
+            (Case::Upper, "ORDER_ID", "INTRANSIT"),
+            (Case::Pascal, "OrderId", "InTransit"),
+            (Case::Camel, "orderId", "inTransit"),
+            (Case::Snake, "order_id", "in_transit"),
+            (Case::ScreamingSnake, "ORDER_ID", "IN_TRANSIT"),
+            (Case::Kebab, "order-id", "in-transit"),
+            (Case::ScreamingKebab, "ORDER-ID", "IN-TRANSIT"),
+        ] {
+            assert_eq!(case.apply("order_id"), field);
+            assert_eq!(case.apply("InTransit"), variant);
+            assert_eq!(case.to_string().parse::<Case>(), Ok(case));
+        }
+        assert_eq!(Case::Snake.apply("HTTPServer"), "http_server");
+        assert!("Title Case".parse::<Case>().is_err());
+    }
+
+    #[test]
+    fn serialize() {
+        let result = serde_json::to_string(&WithCase(&order(), Case::Camel)).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"orderId":7,"status":{"onHold":{"holdReason":"fraud_check"}},"lineItems":{"sku_id":2}}"#
+        );
+
+        let status = Status::InTransit;
+        let result =
+            serde_json::to_string(&WithCase(&status, Case::ScreamingKebab)).expect("Oops!");
+        assert_eq!(result, r#""IN-TRANSIT""#);
+    }
+
+    #[test]
+    fn deserialize() {
+        for json in [
+            r#"{"orderId":7,"status":{"onHold":{"holdReason":"fraud_check"}},"lineItems":{"sku_id":2}}"#,
+            r#"{"order_id":7,"status":{"OnHold":{"hold_reason":"fraud_check"}},"line_items":{"sku_id":2}}"#,
+        ] {
+            let mut de = serde_json::Deserializer::from_str(json);
+            let result: Order = CaseSeed::new(Case::Camel)
+                .deserialize(&mut de)
+                .expect("Oops!");
+            assert_eq!(result, order());
+        }
+    }
+}
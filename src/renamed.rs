@@ -0,0 +1,227 @@
+//! Shared implementation for the [aliases](crate::aliases) and
+//! [rename_all](crate::rename_all) modules.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, EnumAccess, IntoDeserializer, VariantAccess, Visitor};
+use serde::Deserializer;
+
+use super::content::{Content, ContentDeserializer};
+
+/// Replays a [Content], renaming fields and variants on the way.
+pub(crate) struct Renamed<'a, E> {
+    content: Content,
+    rename: &'a dyn Fn(&str, &'static [&'static str]) -> Option<&'static str>,
+    marker: PhantomData<E>,
+}
+
+impl<'a, E> Renamed<'a, E> {
+    /// `rename` is given each field or variant name that isn't one of the
+    /// names the target knows, and returns the known name to use instead.
+    pub(crate) fn new(
+        content: Content,
+        rename: &'a dyn Fn(&str, &'static [&'static str]) -> Option<&'static str>,
+    ) -> Self {
+        Renamed {
+            content,
+            rename,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// The known name for `name`, if it is not already one of `names`.
+fn rename(
+    rename: &dyn Fn(&str, &'static [&'static str]) -> Option<&'static str>,
+    name: Content,
+    names: &'static [&'static str],
+) -> Content {
+    let known = match name.as_str() {
+        Some(name) if !names.contains(&name) => rename(name, names),
+        _ => None,
+    };
+    match known {
+        Some(known) => Content::String(known.to_string()),
+        None => name,
+    }
+}
+
+impl<'de, E: de::Error> IntoDeserializer<'de, E> for Renamed<'_, E> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for Renamed<'_, E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        // Copy `rename` out so the owned content can be moved into the
+        // children, rather than cloned at every level.
+        let rename = self.rename;
+        match self.content {
+            Content::Seq(items) => {
+                let children: Vec<Self> = items
+                    .into_iter()
+                    .map(|item| Renamed::new(item, rename))
+                    .collect();
+                let mut seq = SeqDeserializer::new(children.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Content::Map(entries) => {
+                let children: Vec<(Self, Self)> = entries
+                    .into_iter()
+                    .map(|(k, v)| (Renamed::new(k, rename), Renamed::new(v, rename)))
+                    .collect();
+                let mut map = MapDeserializer::new(children.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            content => ContentDeserializer::new(content).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Unit => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        let rename_with = self.rename;
+        match self.content {
+            Content::Map(entries) => {
+                let entries = entries
+                    .into_iter()
+                    .map(|(k, v)| (rename(rename_with, k, fields), v))
+                    .collect();
+                Renamed::new(Content::Map(entries), rename_with).deserialize_any(visitor)
+            }
+            content => Renamed::new(content, rename_with).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        let rename_with = self.rename;
+        let (variant, value) = match self.content {
+            Content::Map(mut entries) if entries.len() == 1 => {
+                let (variant, value) = entries.remove(0);
+                (variant, Some(Renamed::new(value, rename_with)))
+            }
+            Content::Map(_) => {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Map,
+                    &"a map with a single key",
+                ))
+            }
+            variant => (variant, None),
+        };
+        visitor.visit_enum(RenamedEnum {
+            variant: rename(rename_with, variant, variants),
+            value,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier
+        ignored_any
+    }
+}
+
+struct RenamedEnum<'a, E> {
+    variant: Content,
+    value: Option<Renamed<'a, E>>,
+}
+
+impl<'de, 'a, E: de::Error> EnumAccess<'de> for RenamedEnum<'a, E> {
+    type Error = E;
+    type Variant = RenamedVariant<'a, E>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), E>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(ContentDeserializer::<E>::new(self.variant))?;
+        Ok((variant, RenamedVariant(self.value)))
+    }
+}
+
+struct RenamedVariant<'a, E>(Option<Renamed<'a, E>>);
+
+impl<'de, E: de::Error> VariantAccess<'de> for RenamedVariant<'_, E> {
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), E> {
+        match self.0.map(|value| value.content) {
+            None | Some(Content::Unit) => Ok(()),
+            Some(_) => Err(de::Error::invalid_type(
+                de::Unexpected::Map,
+                &"unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, E>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.0 {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, E> {
+        match self.0 {
+            Some(value) => value.deserialize_seq(visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        match self.0 {
+            Some(value) => value.deserialize_struct("", fields, visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Serialize and Deserialize a date-time as an RFC 2822 string
+//!
+//! RFC 2822 dates, such as `"Tue, 01 Jul 2003 10:52:37 +0200"`, are found in
+//! email headers and legacy HTTP data.  On deserialize, the day of the week
+//! and seconds are optional, and the obsolete two digit years and named
+//! zones (`GMT`, `EST`, `PDT`...) are accepted.  Values are serialized with
+//! the day of the week, seconds and a numeric offset.  RFC 2822 has no
+//! fractional seconds, so any sub-second part is dropped.
+//!
+//! Any [Rfc3339Time] is supported, including `chrono` and `time` types behind
+//! their features.  Types with an offset keep the offset that was parsed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::rfc2822")]
+//!     pub date: SystemTime,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "date": "Tue, 01 Jul 2003 10:52:37 +0200"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+use super::civil;
+use super::rfc3339::Rfc3339Time;
+
+pub fn serialize<S: Serializer, T: Rfc3339Time>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let (nanos, offset) = v.to_rfc3339_parts();
+    let value = civil::format_rfc2822(nanos, offset).map_err(serde::ser::Error::custom)?;
+    String::serialize(&value, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: Rfc3339Time>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    let parsed = civil::parse_rfc2822(&value).map_err(serde::de::Error::custom)?;
+    T::from_rfc3339_parts(parsed.epoch_nanos(), parsed.offset)
+        .ok_or_else(|| serde::de::Error::custom(format!("date \"{}\" is out of range", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::rfc2822")]
+        pub date: SystemTime,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            date: UNIX_EPOCH + Duration::from_secs(1_057_049_557),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"date":"Tue, 01 Jul 2003 08:52:37 +0000"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let expected = UNIX_EPOCH + Duration::from_secs(1_057_049_557);
+        for value in [
+            "Tue, 01 Jul 2003 10:52:37 +0200",
+            "1 Jul 2003 08:52:37 GMT",
+            "Tue, 1 Jul 03 04:52:37 EDT",
+        ] {
+            let json = format!(r#"{{"date": "{}"}}"#, value);
+            let result: Outer = serde_json::from_str(&json).expect(value);
+            assert_eq!(result.date, expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for value in [
+            "2003-07-01T10:52:37Z",
+            "Tue, 31 Jun 2003 10:52:37 +0200",
+            "Tue, 01 Jul 2003 10:52:37",
+            "Tue, 01 Foo 2003 10:52:37 +0200",
+        ] {
+            let json = format!(r#"{{"date": "{}"}}"#, value);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", value);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{DateTime, FixedOffset};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Chrono {
+            #[serde(with = "crate::rfc2822")]
+            pub date: DateTime<FixedOffset>,
+        }
+
+        let json = r#"{"date":"Tue, 01 Jul 2003 10:52:37 +0200"}"#;
+        let result: Chrono = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.date.offset().local_minus_utc(), 7200);
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use time::OffsetDateTime;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Time {
+            #[serde(with = "crate::rfc2822")]
+            pub date: OffsetDateTime,
+        }
+
+        let json = r#"{"date":"Mon, 30 Jun 2003 23:52:37 -0500"}"#;
+        let result: Time = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.date.unix_timestamp(), 1_057_035_157);
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+}
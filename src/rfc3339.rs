@@ -0,0 +1,263 @@
+//! Serialize and Deserialize a date-time as an RFC 3339 string
+//!
+//! Parsing is strict: the string must be exactly
+//! `YYYY-MM-DDTHH:MM:SS[.fraction](Z|+HH:MM|-HH:MM)`.  Space separators,
+//! missing offsets and out of range fields such as `"2023-02-29"` are
+//! rejected.  Output is canonical: the fraction is written with 0, 3, 6 or 9
+//! digits as needed, and a zero offset is written as `Z`.
+//!
+//! Any [Rfc3339Time] is supported:
+//!
+//! - `SystemTime`, always in UTC.
+//! - `chrono::DateTime<Utc>` and `chrono::DateTime<FixedOffset>` with the
+//!   `chrono` feature.  `FixedOffset` keeps the offset it was parsed with.
+//! - `time::OffsetDateTime` with the `time` feature, which also keeps the
+//!   offset it was parsed with.
+//! - `jiff::Timestamp` and `jiff::Zoned` with the `jiff` feature.  A `Zoned`
+//!   is written with its current offset, and parsed into a fixed offset time
+//!   zone, since RFC 3339 has no time zone names.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::rfc3339")]
+//!     pub created: SystemTime,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "created": "2023-11-14T22:13:20.250Z"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::time::SystemTime;
+
+use super::civil;
+use super::epoch::EpochTime;
+
+/// A date-time that can be written as, and read from, RFC 3339.
+pub trait Rfc3339Time: Sized {
+    /// Nanoseconds since the Unix epoch, and the offset from UTC in seconds
+    /// to write the value in.
+    fn to_rfc3339_parts(&self) -> (i128, i32);
+
+    /// Build from nanoseconds since the Unix epoch and the parsed offset from
+    /// UTC in seconds, or `None` if out of range for the type.
+    fn from_rfc3339_parts(nanos: i128, offset: i32) -> Option<Self>;
+}
+
+impl Rfc3339Time for SystemTime {
+    fn to_rfc3339_parts(&self) -> (i128, i32) {
+        (self.to_epoch_nanos(), 0)
+    }
+
+    fn from_rfc3339_parts(nanos: i128, _offset: i32) -> Option<Self> {
+        Self::from_epoch_nanos(nanos)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Rfc3339Time for chrono::DateTime<chrono::Utc> {
+    fn to_rfc3339_parts(&self) -> (i128, i32) {
+        (self.to_epoch_nanos(), 0)
+    }
+
+    fn from_rfc3339_parts(nanos: i128, _offset: i32) -> Option<Self> {
+        Self::from_epoch_nanos(nanos)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Rfc3339Time for chrono::DateTime<chrono::FixedOffset> {
+    fn to_rfc3339_parts(&self) -> (i128, i32) {
+        let utc = self.with_timezone(&chrono::Utc);
+        (utc.to_epoch_nanos(), self.offset().local_minus_utc())
+    }
+
+    fn from_rfc3339_parts(nanos: i128, offset: i32) -> Option<Self> {
+        let utc = chrono::DateTime::<chrono::Utc>::from_epoch_nanos(nanos)?;
+        Some(utc.with_timezone(&chrono::FixedOffset::east_opt(offset)?))
+    }
+}
+
+#[cfg(feature = "time")]
+impl Rfc3339Time for time::OffsetDateTime {
+    fn to_rfc3339_parts(&self) -> (i128, i32) {
+        (self.unix_timestamp_nanos(), self.offset().whole_seconds())
+    }
+
+    fn from_rfc3339_parts(nanos: i128, offset: i32) -> Option<Self> {
+        let offset = time::UtcOffset::from_whole_seconds(offset).ok()?;
+        Some(
+            Self::from_unix_timestamp_nanos(nanos)
+                .ok()?
+                .to_offset(offset),
+        )
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl Rfc3339Time for jiff::Timestamp {
+    fn to_rfc3339_parts(&self) -> (i128, i32) {
+        (self.as_nanosecond(), 0)
+    }
+
+    fn from_rfc3339_parts(nanos: i128, _offset: i32) -> Option<Self> {
+        Self::from_nanosecond(nanos).ok()
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl Rfc3339Time for jiff::Zoned {
+    fn to_rfc3339_parts(&self) -> (i128, i32) {
+        (self.timestamp().as_nanosecond(), self.offset().seconds())
+    }
+
+    fn from_rfc3339_parts(nanos: i128, offset: i32) -> Option<Self> {
+        let offset = jiff::tz::Offset::from_seconds(offset).ok()?;
+        let timestamp = jiff::Timestamp::from_nanosecond(nanos).ok()?;
+        Some(timestamp.to_zoned(jiff::tz::TimeZone::fixed(offset)))
+    }
+}
+
+pub(crate) fn format<T: Rfc3339Time>(v: &T, digits: Option<usize>) -> Result<String, String> {
+    let (nanos, offset) = v.to_rfc3339_parts();
+    civil::format_rfc3339_offset(nanos, offset, digits)
+}
+
+pub(crate) fn parse<T: Rfc3339Time>(value: &str) -> Result<T, String> {
+    let parsed = civil::parse_rfc3339(value)?;
+    T::from_rfc3339_parts(parsed.epoch_nanos(), parsed.offset)
+        .ok_or_else(|| format!("date \"{}\" is out of range", value))
+}
+
+pub fn serialize<S: Serializer, T: Rfc3339Time>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let value = format(v, None).map_err(serde::ser::Error::custom)?;
+    String::serialize(&value, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: Rfc3339Time>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    parse(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::rfc3339")]
+        pub created: SystemTime,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_millis(1_700_000_000_250),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"created":"2023-11-14T22:13:20.250Z"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        };
+        let result: Outer =
+            serde_json::from_str(r#"{"created": "2023-11-14T17:13:20-05:00"}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_strict() {
+        for value in [
+            "2023-11-14 22:13:20Z",
+            "2023-11-14T22:13:20",
+            "2023-11-14T22:13:20+0100",
+            "2023-13-14T22:13:20Z",
+            "2023-11-14T22:13:60Z",
+            "2023-11-14T22:13:20.Z",
+        ] {
+            let json = format!(r#"{{"created": "{}"}}"#, value);
+            assert!(serde_json::from_str::<Outer>(&json).is_err(), "{}", value);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{DateTime, FixedOffset, Utc};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Chrono {
+            #[serde(with = "crate::rfc3339")]
+            pub utc: DateTime<Utc>,
+            #[serde(with = "crate::rfc3339")]
+            pub local: DateTime<FixedOffset>,
+        }
+
+        let json = r#"{"utc":"2023-11-14T22:13:20.123456Z","local":"2023-11-14T23:13:20+01:00"}"#;
+        let result: Chrono = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.utc.timestamp_subsec_micros(), 123_456);
+        assert_eq!(result.local.offset().local_minus_utc(), 3600);
+        assert_eq!(result.local.timestamp(), 1_700_000_000);
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use time::OffsetDateTime;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Time {
+            #[serde(with = "crate::rfc3339")]
+            pub local: OffsetDateTime,
+        }
+
+        let json = r#"{"local":"2023-11-14T17:13:20.5-05:00"}"#;
+        let result: Time = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.local.offset().whole_hours(), -5);
+        assert_eq!(result.local.unix_timestamp(), 1_700_000_000);
+        assert_eq!(
+            serde_json::to_string(&result).expect("Oops!"),
+            r#"{"local":"2023-11-14T17:13:20.500-05:00"}"#
+        );
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn jiff() {
+        use jiff::{Timestamp, Zoned};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Jiff {
+            #[serde(with = "crate::rfc3339")]
+            pub utc: Timestamp,
+            #[serde(with = "crate::rfc3339")]
+            pub local: Zoned,
+        }
+
+        let json = r#"{"utc":"2023-11-14T22:13:20.5Z","local":"2023-11-14T23:13:20+01:00"}"#;
+        let result: Jiff = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.utc.as_millisecond(), 1_700_000_000_500);
+        assert_eq!(result.local.offset().seconds(), 3600);
+        assert_eq!(result.local.timestamp().as_second(), 1_700_000_000);
+        assert_eq!(
+            serde_json::to_string(&result).expect("Oops!"),
+            r#"{"utc":"2023-11-14T22:13:20.500Z","local":"2023-11-14T23:13:20+01:00"}"#
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Serialize and Deserialize a date-time as RFC 3339, keeping its UTC offset
+//!
+//! Audit logs and other records of local time need the offset a value was
+//! written with, not just the instant.  This module only accepts types that
+//! carry an offset, so a field can't silently be normalized to UTC:
+//!
+//! - `chrono::DateTime<FixedOffset>` with the `chrono` feature.
+//! - `time::OffsetDateTime` with the `time` feature.
+//! - `jiff::Zoned` with the `jiff` feature, parsed into a fixed offset time
+//!   zone.
+//!
+//! The offset is always written numerically, so a zero offset is `+00:00`
+//! rather than `Z`.  On deserialize, `-00:00`, which RFC 3339 reserves for
+//! an unknown local offset, is rejected.  Otherwise parsing is as strict as
+//! [rfc3339](crate::rfc3339).
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "chrono")]
+//! # {
+//! use chrono::{DateTime, FixedOffset};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::rfc3339_offset")]
+//!     pub logged: DateTime<FixedOffset>,
+//! }
+//! # }
+//! ```
+//! The following will deserialize to `Outer`, and serialize back unchanged
+//! ```json
+//! {
+//!     "logged": "2023-11-14T17:13:20-05:00"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+
+use super::rfc3339::{self, Rfc3339Time};
+
+/// An [Rfc3339Time] that keeps the offset it was parsed with.
+pub trait OffsetTime: Rfc3339Time {}
+
+#[cfg(feature = "chrono")]
+impl OffsetTime for chrono::DateTime<chrono::FixedOffset> {}
+
+#[cfg(feature = "time")]
+impl OffsetTime for time::OffsetDateTime {}
+
+#[cfg(feature = "jiff")]
+impl OffsetTime for jiff::Zoned {}
+
+pub fn serialize<S: Serializer, T: OffsetTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let mut value = rfc3339::format(v, None).map_err(serde::ser::Error::custom)?;
+    if value.ends_with('Z') {
+        value.pop();
+        value.push_str("+00:00");
+    }
+    String::serialize(&value, s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: OffsetTime>(d: D) -> Result<T, D::Error> {
+    let value = String::deserialize(d)?;
+    if value.ends_with("-00:00") {
+        return Err(serde::de::Error::custom(format!(
+            "date \"{}\" has an unknown local offset",
+            value
+        )));
+    }
+    rfc3339::parse(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{DateTime, FixedOffset};
+        use serde::{Deserialize, Serialize};
+        use serde_json;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::rfc3339_offset")]
+            pub logged: DateTime<FixedOffset>,
+        }
+
+        for json in [
+            r#"{"logged":"2023-11-14T17:13:20-05:00"}"#,
+            r#"{"logged":"2023-11-15T03:43:20.250+05:30"}"#,
+            r#"{"logged":"2023-11-14T22:13:20+00:00"}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect(json);
+            assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+        }
+
+        let result: Outer =
+            serde_json::from_str(r#"{"logged":"2023-11-14T22:13:20Z"}"#).expect("Oops!");
+        assert_eq!(
+            serde_json::to_string(&result).expect("Oops!"),
+            r#"{"logged":"2023-11-14T22:13:20+00:00"}"#
+        );
+        let json = r#"{"logged":"2023-11-14T22:13:20-00:00"}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+
+        let logged = DateTime::from_timestamp(1_700_000_000, 0)
+            .expect("Oops!")
+            .with_timezone(&FixedOffset::east_opt(3600 + 30).expect("Oops!"));
+        let err = serde_json::to_string(&Outer { logged })
+            .expect_err("Oops!")
+            .to_string();
+        assert!(err.contains("offset of 3630 seconds"), "{}", err);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use serde::{Deserialize, Serialize};
+        use serde_json;
+        use time::macros::datetime;
+        use time::OffsetDateTime;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::rfc3339_offset")]
+            pub logged: OffsetDateTime,
+        }
+
+        let json = r#"{"logged":"2023-11-14T23:13:20+01:00"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.logged, datetime!(2023-11-14 23:13:20 +1));
+        assert_eq!(result.logged.offset().whole_hours(), 1);
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+    }
+}
@@ -0,0 +1,88 @@
+//! Deserialize a set from an array, dropping duplicate elements
+//!
+//! The explicit counterpart of [set_strict](crate::set_strict): repeated
+//! elements are dropped, keeping the first.  With a `Vec` this removes
+//! duplicates while keeping the array order, which a plain derive does not.
+//! Serializing is unchanged.  In [strict](crate::strictness) mode,
+//! a duplicate is an error, as with `set_strict`, though the element isn't
+//! named, since it need not implement `Debug`.
+//!
+//! Works with any [UniqueSet](crate::set_strict::UniqueSet).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::set_dedup")]
+//!     pub tags: Vec<String>,
+//! }
+//! ```
+//! The following will deserialize to `Outer` with tags `["red", "blue"]`
+//! ```json
+//! {
+//!     "tags": ["red", "blue", "red"]
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::set_strict::{self, UniqueSet};
+use crate::strictness;
+
+pub fn serialize<S: Serializer, C: Serialize>(v: &C, s: S) -> Result<S::Ok, S::Error> {
+    v.serialize(s)
+}
+
+pub fn deserialize<'de, D, C, T>(d: D) -> Result<C, D::Error>
+where
+    D: Deserializer<'de>,
+    C: UniqueSet<T>,
+    T: Deserialize<'de>,
+{
+    match strictness::is_strict() {
+        true => set_strict::deserialize_with(d, |_| Err("duplicate element".to_string())),
+        false => set_strict::deserialize_with(d, |_| {
+            strictness::repaired("dropped a duplicate element");
+            Ok(())
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::HashSet;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::set_dedup")]
+        pub tags: Vec<String>,
+        #[serde(with = "crate::set_dedup")]
+        pub ports: HashSet<u16>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"tags": ["red", "blue", "red"], "ports": [80, 443, 80]}"#;
+        let outer = Outer {
+            tags: vec!["red".to_string(), "blue".to_string()],
+            ports: HashSet::from([80, 443]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            tags: vec!["red".to_string(), "blue".to_string()],
+            ports: HashSet::new(),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"tags":["red","blue"],"ports":[]}"#);
+    }
+}
@@ -0,0 +1,220 @@
+//! Deserialize a set from an array, rejecting duplicate elements
+//!
+//! Serde builds a `HashSet` or `BTreeSet` from an array by silently dropping
+//! repeated elements, so `["read", "write", "read"]` hides what is probably a
+//! data-quality bug.  This module fails instead, and names the repeated
+//! element.  Serializing is unchanged.  See [set_dedup](crate::set_dedup) to
+//! drop duplicates on purpose.
+//!
+//! [UniqueSet] is implemented for `HashSet` and `BTreeSet`, for `IndexSet`
+//! with the `indexmap` feature, and for `Vec`, which keeps the array order
+//! but checks each element against all earlier ones.  That makes reading a
+//! `Vec` quadratic in its length, so prefer `IndexSet` for long arrays that
+//! need their order kept.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::BTreeSet;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::set_strict")]
+//!     pub scopes: BTreeSet<String>,
+//! }
+//! ```
+//! The following will fail with `duplicate element "read"`
+//! ```json
+//! {
+//!     "scopes": ["read", "write", "read"]
+//! }
+//! ```
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+/// Sets that can refuse to insert an element that is already present.
+pub trait UniqueSet<T>: Default {
+    /// Insert `value`, or give it back if it is already present.
+    fn insert_unique(&mut self, value: T) -> Result<(), T>;
+}
+
+impl<T: Eq + Hash, H: BuildHasher + Default> UniqueSet<T> for HashSet<T, H> {
+    fn insert_unique(&mut self, value: T) -> Result<(), T> {
+        if self.contains(&value) {
+            return Err(value);
+        }
+        self.insert(value);
+        Ok(())
+    }
+}
+
+impl<T: Ord> UniqueSet<T> for BTreeSet<T> {
+    fn insert_unique(&mut self, value: T) -> Result<(), T> {
+        if self.contains(&value) {
+            return Err(value);
+        }
+        self.insert(value);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<T: Eq + Hash, H: BuildHasher + Default> UniqueSet<T> for indexmap::IndexSet<T, H> {
+    fn insert_unique(&mut self, value: T) -> Result<(), T> {
+        if self.contains(&value) {
+            return Err(value);
+        }
+        self.insert(value);
+        Ok(())
+    }
+}
+
+/// A linear scan per element, so O(n²) to read n elements.  Only needs
+/// `PartialEq`, which is why it doesn't keep a side set.
+impl<T: PartialEq> UniqueSet<T> for Vec<T> {
+    fn insert_unique(&mut self, value: T) -> Result<(), T> {
+        if self.contains(&value) {
+            return Err(value);
+        }
+        self.push(value);
+        Ok(())
+    }
+}
+
+/// Read a sequence into `C`, handing every duplicate element to `on_dup`.
+pub(crate) fn deserialize_with<'de, D, C, T, F>(d: D, on_dup: F) -> Result<C, D::Error>
+where
+    D: Deserializer<'de>,
+    C: UniqueSet<T>,
+    T: Deserialize<'de>,
+    F: Fn(T) -> Result<(), String>,
+{
+    struct SetVisitor<C, T, F>(F, PhantomData<(C, T)>);
+
+    impl<'de, C, T, F> Visitor<'de> for SetVisitor<C, T, F>
+    where
+        C: UniqueSet<T>,
+        T: Deserialize<'de>,
+        F: Fn(T) -> Result<(), String>,
+    {
+        type Value = C;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<C, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut out = C::default();
+            while let Some(value) = seq.next_element::<T>()? {
+                if let Err(value) = out.insert_unique(value) {
+                    (self.0)(value).map_err(de::Error::custom)?;
+                }
+            }
+            Ok(out)
+        }
+    }
+
+    d.deserialize_seq(SetVisitor(on_dup, PhantomData))
+}
+
+pub fn serialize<S: Serializer, C: Serialize>(v: &C, s: S) -> Result<S::Ok, S::Error> {
+    v.serialize(s)
+}
+
+pub fn deserialize<'de, D, C, T>(d: D) -> Result<C, D::Error>
+where
+    D: Deserializer<'de>,
+    C: UniqueSet<T>,
+    T: Deserialize<'de> + Debug,
+{
+    deserialize_with(d, |value| Err(format!("duplicate element {:?}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeSet, HashSet};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::set_strict")]
+        pub scopes: BTreeSet<String>,
+        #[serde(with = "crate::set_strict")]
+        pub ports: HashSet<u16>,
+        #[serde(with = "crate::set_strict")]
+        pub order: Vec<u8>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"scopes": ["write", "read"], "ports": [80, 443], "order": [3, 1, 2]}"#;
+        let outer = Outer {
+            scopes: BTreeSet::from(["read".to_string(), "write".to_string()]),
+            ports: HashSet::from([80, 443]),
+            order: vec![3, 1, 2],
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            scopes: BTreeSet::from(["write".to_string(), "read".to_string()]),
+            ports: HashSet::new(),
+            order: vec![3, 1, 2],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"scopes":["read","write"],"ports":[],"order":[3,1,2]}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_duplicate() {
+        let json = r#"{"scopes": ["read", "write", "read"], "ports": [], "order": []}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("duplicate element \"read\""), "{}", err);
+
+        let json = r#"{"scopes": [], "ports": [80, 80], "order": []}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("duplicate element 80"), "{}", err);
+
+        let json = r#"{"scopes": [], "ports": [], "order": [1, 2, 1]}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("duplicate element 1"), "{}", err);
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn indexmap() {
+        use indexmap::IndexSet;
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct Ordered {
+            #[serde(with = "crate::set_strict")]
+            pub scopes: IndexSet<String>,
+        }
+
+        let json = r#"{"scopes":["write","read"]}"#;
+        let result: Ordered = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+
+        let json = r#"{"scopes":["write","write"]}"#;
+        let err = serde_json::from_str::<Ordered>(json)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("duplicate element \"write\""), "{}", err);
+    }
+}
@@ -0,0 +1,122 @@
+//! Deserialize a `SocketAddr` from either a string or a host and port map
+//!
+//! Config files describe addresses both ways, so both of these are accepted:
+//!
+//! - a string, such as `"10.0.0.1:8080"` or `"[::1]:8080"`.
+//! - a map with `host` and `port`, such as `{"host": "::1", "port": 8080}`.
+//!   The host is a bare IP address, without brackets.
+//!
+//! Addresses are always serialized as a string.  This is built on
+//! [string_or_struct](crate::string_or_struct).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::net::SocketAddr;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::socket_addr")]
+//!     pub listen: SocketAddr,
+//! }
+//! ```
+//! The following will both deserialize to `Outer`
+//! ```json
+//! {
+//!     "listen": "10.0.0.1:8080"
+//! }
+//! ```
+//! ```json
+//! {
+//!     "listen": { "host": "10.0.0.1", "port": 8080 }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use std::net::{AddrParseError, IpAddr, SocketAddr};
+use std::str::FromStr;
+
+use super::string_or_struct;
+
+/// The map form of an address.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HostPort {
+    host: IpAddr,
+    port: u16,
+}
+
+impl FromStr for HostPort {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let addr = SocketAddr::from_str(s.trim())?;
+        Ok(HostPort {
+            host: addr.ip(),
+            port: addr.port(),
+        })
+    }
+}
+
+pub fn serialize<S: Serializer>(v: &SocketAddr, s: S) -> Result<S::Ok, S::Error> {
+    String::serialize(&v.to_string(), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<SocketAddr, D::Error> {
+    let addr: HostPort = string_or_struct::try_deserialize(d)?;
+    Ok(SocketAddr::new(addr.host, addr.port))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::socket_addr")]
+        pub listen: SocketAddr,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            listen: SocketAddr::from((Ipv6Addr::LOCALHOST, 8080)),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"listen":"[::1]:8080"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            listen: SocketAddr::from((Ipv4Addr::new(10, 0, 0, 1), 8080)),
+        };
+        for json in [
+            r#"{"listen": "10.0.0.1:8080"}"#,
+            r#"{"listen": {"host": "10.0.0.1", "port": 8080}}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect(json);
+            assert_eq!(&outer, &result);
+        }
+        let result: Outer =
+            serde_json::from_str(r#"{"listen": {"host": "::1", "port": 53}}"#).expect("Oops!");
+        assert_eq!(result.listen, SocketAddr::from((Ipv6Addr::LOCALHOST, 53)));
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for json in [
+            r#"{"listen": "10.0.0.1"}"#,
+            r#"{"listen": "localhost:8080"}"#,
+            r#"{"listen": {"host": "10.0.0.1"}}"#,
+            r#"{"listen": {"host": "10.0.0.1", "port": 70000}}"#,
+            r#"{"listen": {"host": "10.0.0.1", "port": 80, "tls": true}}"#,
+        ] {
+            assert!(serde_json::from_str::<Outer>(json).is_err(), "{}", json);
+        }
+    }
+}
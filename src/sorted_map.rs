@@ -0,0 +1,94 @@
+//! Serialize a `HashMap` with its keys in sorted order
+//!
+//! A `HashMap` iterates in a random order, so the same map serializes
+//! differently from run to run.  That breaks diffing, caching and signing
+//! of the output.  This module writes the entries sorted by key, without
+//! changing the field to a `BTreeMap`.  Deserializing is unchanged.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::sorted_map")]
+//!     pub labels: HashMap<String, String>,
+//! }
+//! ```
+//! `Outer` will always serialize to
+//! ```json
+//! {
+//!     "labels": {"app": "api", "env": "prod", "team": "core"}
+//! }
+//! ```
+
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<S, M, K, V>(v: &M, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    for<'a> &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Ord + Serialize,
+    V: Serialize,
+{
+    let mut entries: Vec<(&K, &V)> = v.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    let mut map = s.serialize_map(Some(entries.len()))?;
+    for (key, value) in entries {
+        map.serialize_entry(key, value)?;
+    }
+    map.end()
+}
+
+pub fn deserialize<'de, D, M>(d: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: Deserialize<'de>,
+{
+    M::deserialize(d)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::HashMap;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::sorted_map")]
+        pub labels: HashMap<String, u32>,
+        #[serde(with = "crate::sorted_map")]
+        pub ids: HashMap<i64, bool>,
+    }
+
+    #[test]
+    fn serialize() {
+        let labels: HashMap<String, u32> = ('a'..='z')
+            .rev()
+            .enumerate()
+            .map(|(i, c)| (c.to_string(), i as u32))
+            .collect();
+        let outer = Outer {
+            labels,
+            ids: HashMap::from([(10, true), (-3, false), (2, true)]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert!(result.starts_with(r#"{"labels":{"a":25,"b":24,"c":23,"#));
+        assert!(result.ends_with(r#""z":0},"ids":{"-3":false,"2":true,"10":true}}"#));
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"labels": {"env": 1, "app": 2}, "ids": {"7": true}}"#;
+        let outer = Outer {
+            labels: HashMap::from([("env".to_string(), 1), ("app".to_string(), 2)]),
+            ids: HashMap::from([(7, true)]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
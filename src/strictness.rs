@@ -0,0 +1,222 @@
+//! Switch the tolerant modules to strict behavior at runtime
+//!
+//! Some modules accept more than they write, which is what ingestion wants,
+//! but not what a fixture check wants.  [strict] runs a closure with those
+//! modules in strict mode, so the same types can be read both ways:
+//!
+//! - [case_insensitive](crate::case_insensitive) requires the exact variant
+//!   name.
+//! - [lossy_string](crate::lossy_string) rejects invalid UTF-8, instead of
+//!   replacing it.
+//! - [set_dedup](crate::set_dedup) rejects duplicate elements, like
+//!   [set_strict](crate::set_strict).
+//!
+//! The mode is per thread, and is [Mode::Lenient] unless set.  [lenient]
+//! does the reverse of [strict], and [set] returns a guard for code that
+//! can't be wrapped in a closure.  The previous mode comes back when the
+//! closure returns or the guard drops, even on a panic.
+//!
+//! In lenient mode, each module reports what it repaired with [repaired],
+//! which [lenient](crate::lenient) collects as warnings.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use serde_stuff::strictness;
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::set_dedup")]
+//!     pub tags: Vec<String>,
+//! }
+//!
+//! let json = r#"{"tags": ["red", "red"]}"#;
+//! assert!(serde_json::from_str::<Outer>(json).is_ok());
+//! assert!(strictness::strict(|| serde_json::from_str::<Outer>(json)).is_err());
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+/// How the tolerant modules treat input they could repair.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Accept and repair, which is each module's documented behavior.
+    #[default]
+    Lenient,
+    /// Reject anything the module would otherwise repair.
+    Strict,
+}
+
+thread_local! {
+    static MODE: Cell<Mode> = const { Cell::new(Mode::Lenient) };
+    static REPAIRS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// The mode for the current thread.
+pub fn mode() -> Mode {
+    MODE.with(Cell::get)
+}
+
+/// Whether the current thread is in [Mode::Strict].
+pub fn is_strict() -> bool {
+    mode() == Mode::Strict
+}
+
+/// Restores the previous mode when dropped.
+#[must_use = "the mode is restored as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct ModeGuard {
+    previous: Mode,
+}
+
+impl Drop for ModeGuard {
+    fn drop(&mut self) {
+        MODE.with(|mode| mode.set(self.previous));
+    }
+}
+
+/// Set the mode for the current thread, until the guard is dropped.
+pub fn set(mode: Mode) -> ModeGuard {
+    ModeGuard {
+        previous: MODE.with(|current| current.replace(mode)),
+    }
+}
+
+/// Run `f` in [Mode::Strict].
+pub fn strict<R>(f: impl FnOnce() -> R) -> R {
+    let _guard = set(Mode::Strict);
+    f()
+}
+
+/// Run `f` in [Mode::Lenient].
+pub fn lenient<R>(f: impl FnOnce() -> R) -> R {
+    let _guard = set(Mode::Lenient);
+    f()
+}
+
+/// Report a repair made in lenient mode, such as a dropped duplicate.  It
+/// is kept only while [lenient](crate::lenient) is collecting warnings.
+pub fn repaired(message: impl fmt::Display) {
+    REPAIRS.with(|repairs| {
+        if let Some(repairs) = repairs.borrow_mut().as_mut() {
+            repairs.push(message.to_string());
+        }
+    });
+}
+
+/// The repairs reported since the last call, while collecting.
+pub(crate) fn take_repairs() -> Vec<String> {
+    REPAIRS.with(|repairs| {
+        repairs
+            .borrow_mut()
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    })
+}
+
+/// Restores the previous collector when dropped.
+struct RepairsGuard {
+    previous: Option<Vec<String>>,
+}
+
+impl Drop for RepairsGuard {
+    fn drop(&mut self) {
+        REPAIRS.with(|repairs| *repairs.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Run `f` in [Mode::Lenient], keeping the repairs for [take_repairs].
+pub(crate) fn collecting<R>(f: impl FnOnce() -> R) -> R {
+    let _repairs = RepairsGuard {
+        previous: REPAIRS.with(|repairs| repairs.borrow_mut().replace(Vec::new())),
+    };
+    lenient(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_strict, lenient, mode, set, strict, Mode};
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub enum Level {
+        Debug,
+        Info,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::case_insensitive")]
+        pub level: Level,
+        #[serde(with = "crate::lossy_string")]
+        pub message: String,
+        #[serde(with = "crate::set_dedup")]
+        pub tags: Vec<String>,
+    }
+
+    #[test]
+    fn nesting() {
+        assert_eq!(mode(), Mode::Lenient);
+        strict(|| {
+            assert!(is_strict());
+            lenient(|| assert!(!is_strict()));
+            assert!(is_strict());
+        });
+        assert!(!is_strict());
+        {
+            let _guard = set(Mode::Strict);
+            assert!(is_strict());
+        }
+        assert!(!is_strict());
+    }
+
+    #[test]
+    fn restored_on_panic() {
+        let result = std::panic::catch_unwind(|| strict(|| panic!("Oops!")));
+        assert!(result.is_err());
+        assert!(!is_strict());
+    }
+
+    #[test]
+    fn deserialize_lenient() {
+        let json = r#"{"level": "INFO", "message": [104, 255], "tags": ["a", "a"]}"#;
+        let outer = Outer {
+            level: Level::Info,
+            message: "h\u{fffd}".to_string(),
+            tags: vec!["a".to_string()],
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_strict() {
+        let json = r#"{"level": "Info", "message": "hi", "tags": ["a", "b"]}"#;
+        let result: Outer = strict(|| serde_json::from_str(json)).expect("Oops!");
+        assert_eq!(result.level, Level::Info);
+
+        for (json, msg) in [
+            (
+                r#"{"level": "INFO", "message": "hi", "tags": []}"#,
+                "unknown variant `INFO`",
+            ),
+            (
+                r#"{"level": "Info", "message": [104, 255], "tags": []}"#,
+                "invalid UTF-8 at byte 1",
+            ),
+            (
+                r#"{"level": "Info", "message": "hi", "tags": ["a", "a"]}"#,
+                "duplicate element",
+            ),
+        ] {
+            let err = strict(|| serde_json::from_str::<Outer>(json))
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+}
@@ -43,24 +43,77 @@
 //!     "inner": { "item": "value"}
 //! }
 //! ```
+//!
+//! XML deserializers hand element text over as a map, so use
+//! `string_or_struct::text` for elements such as SAML's `Issuer`, which may
+//! be `<Issuer>value</Issuer>`, or carry attributes:
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use std::str::FromStr;
+//! use void::Void;
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Issuer {
+//!     #[serde(rename = "@Format")]
+//!     pub format: Option<String>,
+//!     #[serde(rename = "$text")]
+//!     pub value: String,
+//! }
+//!
+//! impl FromStr for Issuer {
+//!     type Err = Void;
+//!
+//!     fn from_str(s: &str) -> Result<Self, Self::Err> {
+//!         Ok(Issuer {
+//!             format: None,
+//!             value: s.to_string(),
+//!         })
+//!     }
+//! }
+//!
+//! #[derive(Debug, Deserialize)]
+//! pub struct Outer {
+//!     #[serde(
+//!         rename = "Issuer",
+//!         deserialize_with = "serde_stuff::string_or_struct::text::deserialize"
+//!     )]
+//!     pub issuer: Issuer,
+//! }
+//!
+//! let outer: Outer = quick_xml::de::from_str("<Outer><Issuer>idp</Issuer></Outer>").unwrap();
+//! assert_eq!(outer.issuer.value, "idp");
+//! ```
 
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
 use serde::de::{self, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer};
-use std::fmt;
-use std::marker::PhantomData;
-use std::str::FromStr;
 use void::Void;
 
 pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
 where
     T: Deserialize<'de> + FromStr<Err = Void>,
     D: Deserializer<'de>,
+{
+    try_deserialize(deserializer)
+}
+
+/// Like [deserialize], for types whose `FromStr` can fail.  A string that
+/// doesn't parse is reported as a deserialize error.
+pub fn try_deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de> + FromStr,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
 {
     struct StringOrStruct<T>(PhantomData<T>);
 
     impl<'de, T> Visitor<'de> for StringOrStruct<T>
     where
-        T: Deserialize<'de> + FromStr<Err = Void>,
+        T: Deserialize<'de> + FromStr,
+        T::Err: fmt::Display,
     {
         type Value = T;
 
@@ -69,7 +122,7 @@ where
         where
             E: de::Error,
         {
-            Ok(FromStr::from_str(value).unwrap())
+            FromStr::from_str(value).map_err(E::custom)
         }
 
         // If the value is a map, pass it to Serde's Map deserializer
@@ -89,6 +142,50 @@ where
     deserializer.deserialize_any(StringOrStruct(PhantomData))
 }
 
+/// Read `T` from the text of an XML element, with `FromStr`, or from an
+/// element with attributes or children, as
+/// [option_xsi_nil](crate::option_xsi_nil) reads it.
+///
+/// quick-xml passes element text as a map with one `$text` (or `$value`)
+/// entry, which plain [string_or_struct](super::string_or_struct) would
+/// read as a struct.  Here the element is buffered, and a map with only
+/// text, or a bare string, such as an attribute, is parsed.
+pub mod text {
+    use core::fmt;
+    use core::str::FromStr;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer};
+    use void::Void;
+
+    use crate::content::{Content, ContentDeserializer};
+    use crate::option_xsi_nil::unwrap_text;
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de> + FromStr<Err = Void>,
+        D: Deserializer<'de>,
+    {
+        try_deserialize(deserializer)
+    }
+
+    /// Like [deserialize], for types whose `FromStr` can fail.
+    pub fn try_deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de> + FromStr,
+        T::Err: fmt::Display,
+        D: Deserializer<'de>,
+    {
+        match unwrap_text(Content::deserialize(deserializer)?) {
+            Content::String(value) => FromStr::from_str(&value).map_err(D::Error::custom),
+            content @ Content::Map(_) => T::deserialize(ContentDeserializer::lenient(content)),
+            content => Err(D::Error::invalid_type(
+                content.unexpected(),
+                &"element text or a map",
+            )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde::Deserialize;
@@ -151,4 +248,85 @@ mod tests {
         let result: Outer = serde_json::from_str(test).expect("Oops!");
         assert_eq!(&outer, &result);
     }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    pub struct Issuer {
+        #[serde(rename = "@Format")]
+        pub format: Option<String>,
+        #[serde(rename = "$text")]
+        pub value: String,
+    }
+
+    impl FromStr for Issuer {
+        type Err = Void;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Issuer {
+                format: None,
+                value: s.to_string(),
+            })
+        }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    pub struct Assertion {
+        #[serde(
+            rename = "Issuer",
+            deserialize_with = "crate::string_or_struct::text::deserialize"
+        )]
+        pub issuer: Issuer,
+        #[serde(
+            rename = "@Audience",
+            deserialize_with = "crate::string_or_struct::text::deserialize"
+        )]
+        pub audience: Inner,
+    }
+
+    #[test]
+    fn xml_text_test() {
+        let xml = r#"<Assertion Audience="sp"><Issuer> idp </Issuer></Assertion>"#;
+        let result: Assertion = quick_xml::de::from_str(xml).expect("Oops!");
+        assert_eq!(
+            result,
+            Assertion {
+                issuer: Issuer {
+                    format: None,
+                    value: "idp".to_string(),
+                },
+                audience: Inner {
+                    item: "sp".to_string(),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn xml_element_test() {
+        let xml = r#"<Assertion Audience="sp">
+            <Issuer Format="urn:oasis:names:tc:SAML:2.0:nameid-format:entity">idp</Issuer>
+        </Assertion>"#;
+        let result: Assertion = quick_xml::de::from_str(xml).expect("Oops!");
+        assert_eq!(
+            result.issuer,
+            Issuer {
+                format: Some("urn:oasis:names:tc:SAML:2.0:nameid-format:entity".to_string()),
+                value: "idp".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn xml_json_test() {
+        let json = r#"{"Issuer": {"$text": "idp"}, "@Audience": {"item": "sp"}}"#;
+        let result: Assertion = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.issuer.value, "idp");
+        assert_eq!(result.audience.item, "sp");
+
+        let json = r#"{"Issuer": 7, "@Audience": "sp"}"#;
+        let err = serde_json::from_str::<Assertion>(json)
+            .expect_err("Oops!")
+            .to_string();
+        let msg = "invalid type: integer `7`, expected element text or a map";
+        assert!(err.contains(msg), "{}", err);
+    }
 }
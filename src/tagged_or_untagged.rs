@@ -0,0 +1,364 @@
+//! Deserialize an enum from its tagged form, or else its untagged form
+//!
+//! APIs that changed their enum encoding between versions send both
+//! `{"Email": {"address": "a@b.c"}}` and plain `{"address": "a@b.c"}`.  This
+//! module first tries the usual externally tagged form, then tries the value
+//! as each variant in turn, as `#[serde(untagged)]` would.  For an
+//! internally tagged enum, `#[serde(tag = "...")]`, the untagged form is
+//! the variant's fields without the tag.  If nothing
+//! matches, the error lists why each attempt failed.  Serializing is
+//! unchanged, so the tagged form is written.
+//!
+//! The `tagged_or_untagged::untagged_first` submodule tries the untagged
+//! form first, for when the untagged form is the current one.
+//!
+//! Since the value is buffered as [Content](crate::content::Content), the
+//! format must be self describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub enum Contact {
+//!     Email { address: String },
+//!     Phone { number: String },
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::tagged_or_untagged")]
+//!     pub contact: Contact,
+//! }
+//! ```
+//! The following will both deserialize to `Outer`
+//! ```json
+//! {
+//!     "contact": {"Phone": {"number": "+15550100"}}
+//! }
+//! ```
+//! ```json
+//! {
+//!     "contact": {"number": "+15550100"}
+//! }
+//! ```
+
+use serde::de::value::MapDeserializer;
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+use super::content::{Content, ContentDeserializer};
+
+type Error = de::value::Error;
+
+/// One way of reading the buffered value.
+type Attempt<T> = fn(&Content) -> Result<T, String>;
+
+/// Why a probe stopped: what the enum asked for, or anything else.
+#[derive(Debug)]
+enum Probed {
+    /// The tag of an internally tagged enum is missing.
+    Tag(&'static str),
+    /// The enum's variants.
+    Variants(&'static [&'static str]),
+    Other,
+}
+
+impl fmt::Display for Probed {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("probed")
+    }
+}
+
+impl std::error::Error for Probed {}
+
+impl de::Error for Probed {
+    fn custom<M: fmt::Display>(_msg: M) -> Self {
+        Probed::Other
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Probed::Tag(field)
+    }
+
+    fn unknown_variant(_variant: &str, expected: &'static [&'static str]) -> Self {
+        Probed::Variants(expected)
+    }
+}
+
+/// Finds the variant names of an enum, by failing to deserialize it.  An
+/// externally tagged enum asks for them with `deserialize_enum`.  An
+/// internally tagged one reads a map, so is given one without its tag,
+/// to learn the tag, then one with an unknown variant.
+struct VariantProbe(Option<&'static str>);
+
+impl<'de> Deserializer<'de> for VariantProbe {
+    type Error = Probed;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Probed> {
+        let entries = self.0.map(|tag| (tag, ""));
+        visitor.visit_map(MapDeserializer::new(entries.into_iter()))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Probed> {
+        Err(Probed::Variants(variants))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// The tag, for an internally tagged enum, and the variants of `T`.
+fn probe<T: DeserializeOwned>() -> (Option<&'static str>, &'static [&'static str]) {
+    match T::deserialize(VariantProbe(None)) {
+        Err(Probed::Variants(variants)) => (None, variants),
+        Err(Probed::Tag(tag)) => match T::deserialize(VariantProbe(Some(tag))) {
+            Err(Probed::Variants(variants)) => (Some(tag), variants),
+            _ => (None, &[]),
+        },
+        _ => (None, &[]),
+    }
+}
+
+/// Replays a [Content] as the value of `variant`, with the `tag` entry
+/// added for an internally tagged enum.
+struct AsVariant {
+    content: Content,
+    variant: &'static str,
+    tag: Option<&'static str>,
+}
+
+impl<'de> Deserializer<'de> for AsVariant {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let content = match (self.tag, self.content) {
+            (Some(tag), Content::Map(mut entries)) => {
+                let entry = (
+                    Content::String(tag.to_string()),
+                    Content::String(self.variant.to_string()),
+                );
+                entries.insert(0, entry);
+                Content::Map(entries)
+            }
+            (_, content) => content,
+        };
+        ContentDeserializer::new(content).deserialize_any(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let content = Content::Map(vec![(
+            Content::String(self.variant.to_string()),
+            self.content,
+        )]);
+        ContentDeserializer::new(content).deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+fn tagged<T: DeserializeOwned>(content: &Content) -> Result<T, String> {
+    T::deserialize(ContentDeserializer::<Error>::new(content.clone()))
+        .map_err(|e| format!("tagged: {}", e))
+}
+
+fn untagged<T: DeserializeOwned>(content: &Content) -> Result<T, String> {
+    let (tag, variants) = probe::<T>();
+    if variants.is_empty() {
+        return Err("untagged: not an enum".to_string());
+    }
+    let mut errors = Vec::new();
+    for variant in variants {
+        let attempt = T::deserialize(AsVariant {
+            content: content.clone(),
+            variant,
+            tag,
+        });
+        match attempt {
+            Ok(value) => return Ok(value),
+            Err(e) => errors.push(format!("{}: {}", variant, e)),
+        }
+    }
+    Err(format!("untagged: {}", errors.join(", ")))
+}
+
+fn deserialize_with<'de, D, T>(d: D, untagged_first: bool) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let content = Content::deserialize(d)?;
+    let (first, second): (Attempt<T>, Attempt<T>) = match untagged_first {
+        false => (tagged, untagged),
+        true => (untagged, tagged),
+    };
+    first(&content)
+        .or_else(|e1| second(&content).map_err(|e2| format!("{}; {}", e1, e2)))
+        .map_err(|e| de::Error::custom(format!("no representation matched; {}", e)))
+}
+
+pub fn serialize<S: Serializer, T: Serialize>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    v.serialize(s)
+}
+
+pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    deserialize_with(d, false)
+}
+
+/// Try the untagged form before the tagged one.
+pub mod untagged_first {
+    use serde::de::DeserializeOwned;
+    use serde::{Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, T: Serialize>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        v.serialize(s)
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: DeserializeOwned,
+    {
+        super::deserialize_with(d, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub enum Contact {
+        Email { address: String },
+        Phone { number: String },
+        Pager(u32),
+        None,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::tagged_or_untagged")]
+        pub contact: Contact,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Flipped {
+        #[serde(with = "crate::tagged_or_untagged::untagged_first")]
+        pub contact: Contact,
+    }
+
+    fn phone() -> Contact {
+        Contact::Phone {
+            number: "+15550100".to_string(),
+        }
+    }
+
+    #[test]
+    fn deserialize() {
+        for (json, contact) in [
+            (r#"{"Phone": {"number": "+15550100"}}"#, phone()),
+            (r#"{"number": "+15550100"}"#, phone()),
+            (r#"{"Pager": 42}"#, Contact::Pager(42)),
+            (r#"42"#, Contact::Pager(42)),
+            (r#""None""#, Contact::None),
+            (r#"null"#, Contact::None),
+        ] {
+            let json = format!(r#"{{"contact": {}}}"#, json);
+            let result: Outer = serde_json::from_str(&json).expect("Oops!");
+            assert_eq!(Outer { contact }, result);
+        }
+    }
+
+    #[test]
+    fn deserialize_untagged_first() {
+        for json in [
+            r#"{"contact": {"number": "+15550100"}}"#,
+            r#"{"contact": {"Phone": {"number": "+15550100"}}}"#,
+        ] {
+            let result: Flipped = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(Flipped { contact: phone() }, result);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer { contact: phone() };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"contact":{"Phone":{"number":"+15550100"}}}"#);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"contact": {"fax": "+15550100"}}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(
+            err.contains("no representation matched; tagged: "),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("untagged: Email: missing field `address`, Phone: missing field `number`"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn internally_tagged() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        #[serde(tag = "type")]
+        pub enum Contact {
+            Email { address: String },
+            Phone { number: String },
+        }
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::tagged_or_untagged")]
+            pub contact: Contact,
+        }
+
+        let phone = Contact::Phone {
+            number: "1".to_string(),
+        };
+        for json in [
+            r#"{"contact": {"type": "Phone", "number": "1"}}"#,
+            r#"{"contact": {"number": "1"}}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(result.contact, phone, "{}", json);
+        }
+
+        let json = r#"{"contact": {"fax": "1"}}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(
+            err.contains("untagged: Email: missing field `address`, Phone: missing field `number`"),
+            "{}",
+            err
+        );
+    }
+}
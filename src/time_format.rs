@@ -0,0 +1,270 @@
+//! Serialize and Deserialize `time` crate types with a format description
+//!
+//! Requires the `time` feature.  [TimeFormat] is implemented for
+//! `OffsetDateTime`, `PrimitiveDateTime` and `Date`, so each of the modules
+//! here works with whichever of them the format can represent:
+//!
+//! - `time_format::iso8601` uses the ISO 8601 well-known format, and needs an
+//!   `OffsetDateTime`.
+//! - `time_format::primitive` uses `YYYY-MM-DDTHH:MM:SS[.fraction]`, for a
+//!   `PrimitiveDateTime` without an offset.
+//! - `time_format::date` uses `YYYY-MM-DD`, for a `Date`.
+//!
+//! RFC 3339 is handled by the [rfc3339](crate::rfc3339) module, which
+//! supports `OffsetDateTime` with this feature.
+//!
+//! For any other format, the [time_format!](crate::time_format!) macro
+//! generates a module from a custom [format description].  The module
+//! includes an `option` companion.
+//!
+//! [format description]: https://time-rs.github.io/book/api/format-description.html
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use time::{Date, PrimitiveDateTime};
+//!
+//! serde_stuff::time_format!(pub day_month_year, "[day]/[month]/[year]");
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::time_format::primitive")]
+//!     pub created: PrimitiveDateTime,
+//!     #[serde(with = "day_month_year")]
+//!     pub due: Date,
+//!     #[serde(default, with = "day_month_year::option")]
+//!     pub paid: Option<Date>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "created": "2023-11-14T22:13:20",
+//!     "due": "31/12/2023"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use time::formatting::Formattable;
+use time::parsing::Parsable;
+
+#[doc(hidden)]
+pub use time::format_description::{parse_owned, OwnedFormatItem};
+
+/// `time` types that can be formatted and parsed with a format description.
+pub trait TimeFormat: Sized {
+    fn format_with(&self, format: &(impl Formattable + ?Sized)) -> Result<String, String>;
+    fn parse_with(value: &str, format: &(impl Parsable + ?Sized)) -> Result<Self, String>;
+}
+
+macro_rules! impl_time_format {
+    ($($ty:ty),*) => {
+        $(
+            impl TimeFormat for $ty {
+                fn format_with(&self, format: &(impl Formattable + ?Sized)) -> Result<String, String> {
+                    self.format(format).map_err(|e| e.to_string())
+                }
+
+                fn parse_with(value: &str, format: &(impl Parsable + ?Sized)) -> Result<Self, String> {
+                    <$ty>::parse(value, format).map_err(|e| format!("{} in \"{}\"", e, value))
+                }
+            }
+        )*
+    };
+}
+
+impl_time_format!(time::OffsetDateTime, time::PrimitiveDateTime, time::Date);
+
+/// Serialize `v` with `format`.  This is the building block for the modules
+/// here, and for modules using your own formats.
+pub fn serialize_with<S, T, F>(v: &T, s: S, format: &F) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: TimeFormat,
+    F: Formattable + ?Sized,
+{
+    let value = v.format_with(format).map_err(serde::ser::Error::custom)?;
+    String::serialize(&value, s)
+}
+
+/// Deserialize a string with `format`.
+pub fn deserialize_with<'de, D, T, F>(d: D, format: &F) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TimeFormat,
+    F: Parsable + ?Sized,
+{
+    let value = String::deserialize(d)?;
+    T::parse_with(&value, format).map_err(serde::de::Error::custom)
+}
+
+/// Generate a serde `with` module for a custom `time` format description.
+///
+/// The format description is parsed once, on first use.  An invalid
+/// description is reported as a (de)serialization error.
+///
+/// ```rust
+/// serde_stuff::time_format!(pub compact, "[year][month][day]");
+/// ```
+#[macro_export]
+macro_rules! time_format {
+    ($vis:vis $name:ident, $format:literal) => {
+        $vis mod $name {
+            use $crate::time_format::TimeFormat;
+
+            fn format() -> Result<&'static $crate::time_format::OwnedFormatItem, String> {
+                static FORMAT: ::std::sync::OnceLock<
+                    Result<$crate::time_format::OwnedFormatItem, String>,
+                > = ::std::sync::OnceLock::new();
+                FORMAT
+                    .get_or_init(|| {
+                        $crate::time_format::parse_owned::<2>($format).map_err(|e| e.to_string())
+                    })
+                    .as_ref()
+                    .map_err(Clone::clone)
+            }
+
+            #[allow(dead_code)]
+            pub fn serialize<S: serde::Serializer, T: TimeFormat>(
+                v: &T,
+                s: S,
+            ) -> Result<S::Ok, S::Error> {
+                let format = format().map_err(serde::ser::Error::custom)?;
+                $crate::time_format::serialize_with(v, s, format)
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D: serde::Deserializer<'de>, T: TimeFormat>(
+                d: D,
+            ) -> Result<T, D::Error> {
+                let format = format().map_err(serde::de::Error::custom)?;
+                $crate::time_format::deserialize_with(d, format)
+            }
+
+            /// The `Option` companion.  Use with `default`.
+            #[allow(dead_code)]
+            pub mod option {
+                use serde::{Deserialize, Serialize};
+                use $crate::time_format::TimeFormat;
+
+                pub fn serialize<S: serde::Serializer, T: TimeFormat>(
+                    v: &Option<T>,
+                    s: S,
+                ) -> Result<S::Ok, S::Error> {
+                    let format = super::format().map_err(serde::ser::Error::custom)?;
+                    let value = v
+                        .as_ref()
+                        .map(|v| v.format_with(format))
+                        .transpose()
+                        .map_err(serde::ser::Error::custom)?;
+                    <Option<String>>::serialize(&value, s)
+                }
+
+                pub fn deserialize<'de, D: serde::Deserializer<'de>, T: TimeFormat>(
+                    d: D,
+                ) -> Result<Option<T>, D::Error> {
+                    let format = super::format().map_err(serde::de::Error::custom)?;
+                    <Option<String>>::deserialize(d)?
+                        .map(|v| T::parse_with(&v, format))
+                        .transpose()
+                        .map_err(serde::de::Error::custom)
+                }
+            }
+        }
+    };
+}
+
+/// The ISO 8601 well-known format, for `OffsetDateTime`.
+pub mod iso8601 {
+    use serde::{Deserializer, Serializer};
+    use time::format_description::well_known::Iso8601;
+
+    use super::TimeFormat;
+
+    pub fn serialize<S: Serializer, T: TimeFormat>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        super::serialize_with(v, s, &Iso8601::DEFAULT)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: TimeFormat>(d: D) -> Result<T, D::Error> {
+        super::deserialize_with(d, &Iso8601::DEFAULT)
+    }
+}
+
+crate::time_format!(
+    pub primitive,
+    "[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]"
+);
+
+crate::time_format!(pub date, "[year]-[month]-[day]");
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use time::macros::{date, datetime};
+    use time::{Date, OffsetDateTime, PrimitiveDateTime};
+
+    crate::time_format!(day_month_year, "[day]/[month]/[year]");
+    crate::time_format!(broken, "[not_a_component]");
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::time_format::iso8601")]
+        pub created: OffsetDateTime,
+        #[serde(with = "crate::time_format::primitive")]
+        pub local: PrimitiveDateTime,
+        #[serde(with = "crate::time_format::date")]
+        pub date: Date,
+        #[serde(with = "day_month_year")]
+        pub due: Date,
+        #[serde(default, with = "day_month_year::option")]
+        pub paid: Option<Date>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let outer = Outer {
+            created: datetime!(2023-11-14 22:13:20 +01:00),
+            local: datetime!(2023-11-14 22:13:20.5),
+            date: date!(2023 - 11 - 14),
+            due: date!(2023 - 12 - 31),
+            paid: None,
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            json,
+            r#"{"created":"2023-11-14T22:13:20.000000000+01:00","local":"2023-11-14T22:13:20.5","date":"2023-11-14","due":"31/12/2023","paid":null}"#
+        );
+        let result: Outer = serde_json::from_str(&json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_custom() {
+        let json = r#"{
+            "created": "2023-11-14T22:13:20Z",
+            "local": "2023-11-14T22:13:20",
+            "date": "2023-11-14",
+            "due": "01/02/2024",
+            "paid": "15/01/2024"
+        }"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.due, date!(2024 - 02 - 01));
+        assert_eq!(result.paid, Some(date!(2024 - 01 - 15)));
+    }
+
+    #[test]
+    fn invalid_format_description() {
+        #[derive(Deserialize, Debug)]
+        pub struct Broken {
+            #[serde(with = "broken")]
+            #[allow(dead_code)]
+            pub date: Date,
+        }
+
+        let result = serde_json::from_str::<Broken>(r#"{"date": "2023"}"#);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! Serialize and Deserialize a time as milliseconds since the Unix epoch
+//!
+//! Any [EpochTime](crate::epoch::EpochTime) is supported, which includes
+//! `SystemTime`, and `chrono` and `time` types behind their features.
+//! JavaScript `Date` values use milliseconds.
+//!
+//! Times are serialized as whole milliseconds, rounding towards the past.
+//! On deserialize, both integers and floats are accepted.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::timestamp_millis")]
+//!     pub created: SystemTime,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "created": 1700000000123
+//! }
+//! ```
+
+use serde::{Deserializer, Serializer};
+
+use super::epoch::{self, EpochTime};
+
+pub fn serialize<S: Serializer, T: EpochTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    epoch::serialize(v, s, epoch::NANOS_PER_MILLI)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(d: D) -> Result<T, D::Error> {
+    epoch::deserialize(d, epoch::NANOS_PER_MILLI)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::timestamp_millis")]
+        pub created: SystemTime,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"created":1700000000123}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        };
+        let result: Outer = serde_json::from_str(r#"{"created": 1700000000123}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_500),
+        };
+        let result: Outer = serde_json::from_str(r#"{"created": 1700000000123.5}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{DateTime, TimeZone, Utc};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Chrono {
+            #[serde(with = "crate::timestamp_millis")]
+            pub created: DateTime<Utc>,
+        }
+
+        let outer = Chrono {
+            created: Utc.timestamp_millis_opt(-1_500).unwrap(),
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(json, r#"{"created":-1500}"#);
+        let result: Chrono = serde_json::from_str(&json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use time::OffsetDateTime;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Time {
+            #[serde(with = "crate::timestamp_millis")]
+            pub created: OffsetDateTime,
+        }
+
+        let outer = Time {
+            created: OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_000_000).unwrap(),
+        };
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(json, r#"{"created":1700000000123}"#);
+        let result: Time = serde_json::from_str(&json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
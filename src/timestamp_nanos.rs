@@ -0,0 +1,72 @@
+//! Serialize and Deserialize a time as nanoseconds since the Unix epoch
+//!
+//! Any [EpochTime](crate::epoch::EpochTime) is supported, which includes
+//! `SystemTime`, and `chrono` and `time` types behind their features.
+//! Nanoseconds cover roughly the years 1677 to 2262 in an `i64`.
+//!
+//! Times are serialized as whole nanoseconds, rounding towards the past.
+//! On deserialize, integers are expected; floats are accepted but are
+//! rounded to the nearest nanosecond.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::timestamp_nanos")]
+//!     pub created: SystemTime,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "created": 1700000000123456789
+//! }
+//! ```
+
+use serde::{Deserializer, Serializer};
+
+use super::epoch::{self, EpochTime};
+
+pub fn serialize<S: Serializer, T: EpochTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    epoch::serialize(v, s, epoch::NANOS_PER_NANO)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(d: D) -> Result<T, D::Error> {
+    epoch::deserialize(d, epoch::NANOS_PER_NANO)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::timestamp_nanos")]
+        pub created: SystemTime,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_nanos(1_700_000_000_123_456_789),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"created":1700000000123456789}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_nanos(1_700_000_000_123_456_789),
+        };
+        let result: Outer =
+            serde_json::from_str(r#"{"created": 1700000000123456789}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
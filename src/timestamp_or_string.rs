@@ -0,0 +1,366 @@
+//! Deserialize a time from either an epoch number or an RFC 3339 string
+//!
+//! Feeds aggregated from several vendors rarely agree on a time format.  This
+//! module accepts, for the same field:
+//!
+//! - an integer or float epoch timestamp.  Values with a magnitude of at least
+//!   [DEFAULT_MILLIS_THRESHOLD] are taken as milliseconds, and smaller values
+//!   as seconds.  Use `deserialize_with_threshold` to choose another cut-off.
+//! - an RFC 3339 string, such as `"2023-11-14T22:13:20Z"`.
+//!
+//! The canonical form written on serialize is chosen by the module:
+//!
+//! - `timestamp_or_string` writes an RFC 3339 string in UTC, with 0, 3, 6 or
+//!   9 fractional digits, whichever is the shortest that is exact.
+//! - `serialize_with_precision` writes an RFC 3339 string in UTC with exactly
+//!   `DIGITS` fractional digits, so normalized output has a fixed shape.
+//! - `timestamp_or_string::secs` writes integer seconds.
+//! - `timestamp_or_string::millis` writes integer milliseconds.
+//!
+//! Any [EpochTime](crate::epoch::EpochTime) is supported.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::timestamp_or_string")]
+//!     pub created: SystemTime,
+//!     #[serde(with = "serde_stuff::timestamp_or_string::millis")]
+//!     pub updated: SystemTime,
+//!     #[serde(
+//!         serialize_with = "serde_stuff::timestamp_or_string::serialize_with_precision::<_, _, 3>",
+//!         deserialize_with = "serde_stuff::timestamp_or_string::deserialize"
+//!     )]
+//!     pub published: SystemTime,
+//! }
+//! ```
+//! All of the following values are the same time
+//! ```json
+//! {
+//!     "created": 1700000000,
+//!     "updated": 1700000000000,
+//!     "published": 1700000000
+//! }
+//! ```
+//! ```json
+//! {
+//!     "created": "2023-11-14T22:13:20Z",
+//!     "updated": "2023-11-14T23:13:20+01:00",
+//!     "published": "2023-11-14T22:13:20.000Z"
+//! }
+//! ```
+//! and will all serialize `published` as `"2023-11-14T22:13:20.000Z"`.
+
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::civil;
+use super::epoch::{self, EpochTime};
+
+/// Epoch numbers with at least this magnitude are read as milliseconds.
+///
+/// `100_000_000_000` seconds is in the year 5138, while the same number of
+/// milliseconds is in 1973.
+pub const DEFAULT_MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+fn unit(magnitude: f64, threshold: i64) -> i128 {
+    if magnitude >= threshold as f64 {
+        epoch::NANOS_PER_MILLI
+    } else {
+        epoch::NANOS_PER_SEC
+    }
+}
+
+/// Nanoseconds for an integer epoch, read as seconds or milliseconds by
+/// magnitude.
+pub(crate) fn int_nanos(value: i128, threshold: i64) -> Option<i128> {
+    value.checked_mul(unit((value as f64).abs(), threshold))
+}
+
+/// Nanoseconds for a float epoch, read as seconds or milliseconds by
+/// magnitude.
+pub(crate) fn float_nanos(value: f64, threshold: i64) -> Option<i128> {
+    if !value.is_finite() || value.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    let unit = unit(value.abs(), threshold);
+    let whole = value.trunc();
+    let fraction = ((value - whole) * unit as f64).round() as i128;
+    (whole as i128).checked_mul(unit).map(|n| n + fraction)
+}
+
+pub(crate) fn deserialize_any<'de, D, T>(d: D, threshold: i64) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: EpochTime,
+{
+    struct TimestampOrString<T>(i64, PhantomData<T>);
+
+    impl<T: EpochTime> TimestampOrString<T> {
+        fn to_time<E: de::Error>(&self, nanos: Option<i128>) -> Result<T, E> {
+            nanos
+                .and_then(T::from_epoch_nanos)
+                .ok_or_else(|| E::custom("timestamp out of range"))
+        }
+    }
+
+    impl<'de, T: EpochTime> Visitor<'de> for TimestampOrString<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an epoch timestamp or an RFC 3339 string")
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            self.to_time(int_nanos(value as i128, self.0))
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            self.to_time(int_nanos(value as i128, self.0))
+        }
+
+        fn visit_f64<E>(self, value: f64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            if !value.is_finite() {
+                return Err(E::invalid_value(de::Unexpected::Float(value), &self));
+            }
+            self.to_time(float_nanos(value, self.0))
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            let parsed = civil::parse_rfc3339(value).map_err(E::custom)?;
+            self.to_time(Some(parsed.epoch_nanos()))
+        }
+    }
+
+    d.deserialize_any(TimestampOrString(threshold, PhantomData))
+}
+
+pub fn serialize<S: Serializer, T: EpochTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let value =
+        civil::format_rfc3339(v.to_epoch_nanos(), None).map_err(serde::ser::Error::custom)?;
+    s.serialize_str(&value)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(d: D) -> Result<T, D::Error> {
+    deserialize_any(d, DEFAULT_MILLIS_THRESHOLD)
+}
+
+/// Serialize as RFC 3339 in UTC with exactly `DIGITS` (at most 9) fractional
+/// digits, truncating any extra precision.  Use with
+/// `serialize_with = "serde_stuff::timestamp_or_string::serialize_with_precision::<_, _, 3>"`.
+pub fn serialize_with_precision<S, T, const DIGITS: usize>(v: &T, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: EpochTime,
+{
+    if DIGITS > 9 {
+        return Err(serde::ser::Error::custom(format!(
+            "RFC 3339 precision of {} digits is more than nanoseconds",
+            DIGITS
+        )));
+    }
+    let value = civil::format_rfc3339(v.to_epoch_nanos(), Some(DIGITS))
+        .map_err(serde::ser::Error::custom)?;
+    s.serialize_str(&value)
+}
+
+/// Deserialize with a caller chosen seconds/milliseconds cut-off.  Use with
+/// `deserialize_with = "serde_stuff::timestamp_or_string::deserialize_with_threshold::<_, _, 10_000_000_000>"`.
+pub fn deserialize_with_threshold<'de, D, T, const MILLIS_THRESHOLD: i64>(
+    d: D,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: EpochTime,
+{
+    deserialize_any(d, MILLIS_THRESHOLD)
+}
+
+/// Accept either form, and serialize as integer seconds.
+pub mod secs {
+    use serde::{Deserializer, Serializer};
+
+    use crate::epoch::EpochTime;
+
+    pub fn serialize<S: Serializer, T: EpochTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        crate::timestamp_secs::serialize(v, s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(d: D) -> Result<T, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+/// Accept either form, and serialize as integer milliseconds.
+pub mod millis {
+    use serde::{Deserializer, Serializer};
+
+    use crate::epoch::EpochTime;
+
+    pub fn serialize<S: Serializer, T: EpochTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        crate::timestamp_millis::serialize(v, s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(d: D) -> Result<T, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::timestamp_or_string")]
+        pub created: SystemTime,
+        #[serde(with = "crate::timestamp_or_string::secs")]
+        pub updated: SystemTime,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    pub struct Threshold {
+        #[serde(
+            deserialize_with = "crate::timestamp_or_string::deserialize_with_threshold::<_, _, 1000>"
+        )]
+        pub created: SystemTime,
+    }
+
+    fn time(millis: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn deserialize_numbers() {
+        let json = r#"{"created": 1700000000, "updated": 1700000000000}"#;
+        let outer = Outer {
+            created: time(1_700_000_000_000),
+            updated: time(1_700_000_000_000),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+
+        let json = r#"{"created": 1700000000.5, "updated": 1700000000123}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.created, time(1_700_000_000_500));
+        assert_eq!(result.updated, time(1_700_000_000_123));
+    }
+
+    #[test]
+    fn deserialize_strings() {
+        let json = r#"{
+            "created": "2023-11-14T22:13:20.250Z",
+            "updated": "2023-11-14T23:13:20+01:00"
+        }"#;
+        let outer = Outer {
+            created: time(1_700_000_000_250),
+            updated: time(1_700_000_000_000),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"created": "2023-02-29T00:00:00Z", "updated": 0}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+        let json = r#"{"created": "yesterday", "updated": 0}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+    }
+
+    #[test]
+    fn deserialize_threshold() {
+        let result: Threshold = serde_json::from_str(r#"{"created": 999}"#).expect("Oops!");
+        assert_eq!(result.created, time(999_000));
+        let result: Threshold = serde_json::from_str(r#"{"created": 1000}"#).expect("Oops!");
+        assert_eq!(result.created, time(1_000));
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Precision {
+        #[serde(
+            serialize_with = "crate::timestamp_or_string::serialize_with_precision::<_, _, 3>",
+            deserialize_with = "crate::timestamp_or_string::deserialize"
+        )]
+        pub millis: SystemTime,
+        #[serde(
+            serialize_with = "crate::timestamp_or_string::serialize_with_precision::<_, _, 0>",
+            deserialize_with = "crate::timestamp_or_string::deserialize"
+        )]
+        pub secs: SystemTime,
+    }
+
+    #[test]
+    fn serialize_precision() {
+        let json = r#"{"millis": 1700000000, "secs": "2023-11-14T22:13:20.999999Z"}"#;
+        let result: Precision = serde_json::from_str(json).expect("Oops!");
+        let result = serde_json::to_string(&result).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"millis":"2023-11-14T22:13:20.000Z","secs":"2023-11-14T22:13:20Z"}"#
+        );
+
+        let json = r#"{"millis": "2023-11-14T22:13:20.123456789Z", "secs": 0}"#;
+        let result: Precision = serde_json::from_str(json).expect("Oops!");
+        let result = serde_json::to_string(&result).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"millis":"2023-11-14T22:13:20.123Z","secs":"1970-01-01T00:00:00Z"}"#
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            created: time(1_700_000_000_250),
+            updated: time(1_700_000_000_250),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"created":"2023-11-14T22:13:20.250Z","updated":1700000000}"#
+        );
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn jiff() {
+        use jiff::Timestamp;
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Jiff {
+            #[serde(with = "crate::timestamp_or_string")]
+            pub created: Timestamp,
+            #[serde(with = "crate::timestamp_or_string::millis")]
+            pub updated: Timestamp,
+        }
+
+        let json = r#"{"created": 1700000000, "updated": "2023-11-14T22:13:20Z"}"#;
+        let result: Jiff = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.created, result.updated);
+        assert_eq!(
+            serde_json::to_string(&result).expect("Oops!"),
+            r#"{"created":"2023-11-14T22:13:20Z","updated":1700000000000}"#
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Serialize and Deserialize a time as seconds since the Unix epoch
+//!
+//! Any [EpochTime](crate::epoch::EpochTime) is supported, which includes
+//! `SystemTime`, and `chrono` and `time` types behind their features.
+//!
+//! Times are serialized as whole seconds.  Any sub-second part is dropped,
+//! rounding towards the past.  On deserialize, both integers and floats are
+//! accepted, so `1700000000.25` keeps its fractional second.  Times before
+//! the epoch are negative.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::timestamp_secs")]
+//!     pub created: SystemTime,
+//! }
+//! ```
+//! The following will both deserialize to `Outer`
+//! ```json
+//! {
+//!     "created": 1700000000
+//! }
+//! ```
+//! ```json
+//! {
+//!     "created": 1700000000.5
+//! }
+//! ```
+
+use serde::{Deserializer, Serializer};
+
+use super::epoch::{self, EpochTime};
+
+pub fn serialize<S: Serializer, T: EpochTime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    epoch::serialize(v, s, epoch::NANOS_PER_SEC)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: EpochTime>(d: D) -> Result<T, D::Error> {
+    epoch::deserialize(d, epoch::NANOS_PER_SEC)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::timestamp_secs")]
+        pub created: SystemTime,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_millis(1_700_000_000_900),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"created":1700000000}"#);
+
+        let outer = Outer {
+            created: UNIX_EPOCH - Duration::from_millis(1_500),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"created":-2}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        };
+        let result: Outer = serde_json::from_str(r#"{"created": 1700000000}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+
+        let outer = Outer {
+            created: UNIX_EPOCH + Duration::from_millis(1_700_000_000_250),
+        };
+        let result: Outer = serde_json::from_str(r#"{"created": 1700000000.25}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+
+        let outer = Outer {
+            created: UNIX_EPOCH - Duration::from_secs(10),
+        };
+        let result: Outer = serde_json::from_str(r#"{"created": -10}"#).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        assert!(serde_json::from_str::<Outer>(r#"{"created": "yesterday"}"#).is_err());
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let outer = Outer {
+            created: UNIX_EPOCH - Duration::from_secs(2),
+        };
+        let bytes = bincode::serialize(&outer).expect("Oops!");
+        let result: Outer = bincode::deserialize(&bytes).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
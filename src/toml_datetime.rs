@@ -0,0 +1,574 @@
+//! Serialize and Deserialize `chrono` and `time` types as TOML datetimes
+//!
+//! Requires the `toml` feature.  TOML has its own datetime type, which the
+//! `toml` crate hands over as a private map, so a `chrono` or `time` field
+//! can't read it, and a config struct can't be shared with a JSON backend,
+//! which writes the same value as a string.  This module reads either, and
+//! converts between the four kinds of TOML datetime and [TomlDatetime]
+//! types, with `chrono` and `time` types behind their features:
+//!
+//! | TOML            | Example                     | Types                                                      |
+//! |-----------------|-----------------------------|------------------------------------------------------------|
+//! | offset datetime | `1979-05-27T07:32:00-07:00` | `DateTime<FixedOffset>`, `DateTime<Utc>`, `OffsetDateTime` |
+//! | local datetime  | `1979-05-27T07:32:00`       | `NaiveDateTime`, `PrimitiveDateTime`                       |
+//! | local date      | `1979-05-27`                | `NaiveDate`, `time::Date`                                  |
+//! | local time      | `07:32:00.999999`           | `NaiveTime`, `time::Time`                                  |
+//!
+//! Reading a value of the wrong kind is an error, rather than guessing an
+//! offset or dropping one, but `DateTime<Utc>` reads any offset datetime.
+//!
+//! - `toml_datetime` writes a string, which TOML quotes, and JSON keeps.
+//! - `toml_datetime::native` writes a TOML datetime, for TOML only.
+//! - [option_toml_datetime](crate::option_toml_datetime) is the `Option`
+//!   companion, which writes a string.  Use it with `default`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(all(feature = "toml", feature = "chrono"))]
+//! # {
+//! use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::toml_datetime::native")]
+//!     pub released: DateTime<Utc>,
+//!     #[serde(with = "serde_stuff::toml_datetime")]
+//!     pub holiday: NaiveDate,
+//!     #[serde(default, with = "serde_stuff::option_toml_datetime")]
+//!     pub backup: Option<NaiveTime>,
+//! }
+//!
+//! let toml = "released = 1979-05-27T07:32:00-07:00\nholiday = 1979-12-25\n";
+//! let outer: Outer = toml::from_str(toml).unwrap();
+//! assert_eq!(outer.released.to_rfc3339(), "1979-05-27T14:32:00+00:00");
+//!
+//! let json = r#"{"released": "1979-05-27T14:32:00Z", "holiday": "1979-12-25"}"#;
+//! let outer: Outer = serde_json::from_str(json).unwrap();
+//! assert_eq!(outer.holiday, NaiveDate::from_ymd_opt(1979, 12, 25).unwrap());
+//! # }
+//! ```
+
+use ::toml_datetime::Datetime;
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serializer};
+use std::fmt;
+
+/// Types that convert to and from a TOML datetime.
+pub trait TomlDatetime: Sized {
+    fn to_toml(&self) -> Result<Datetime, String>;
+    fn from_toml(value: Datetime) -> Result<Self, String>;
+}
+
+/// Building blocks for the [TomlDatetime] impls.
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod parts {
+    use ::toml_datetime::{Date, Datetime, Offset};
+
+    fn kind(value: &Datetime) -> &'static str {
+        match (value.date, value.time, value.offset) {
+            (Some(_), Some(_), Some(_)) => "an offset datetime",
+            (Some(_), Some(_), None) => "a local datetime",
+            (Some(_), None, _) => "a local date",
+            _ => "a local time",
+        }
+    }
+
+    /// The error for a TOML datetime of the wrong kind.
+    pub(super) fn mismatch(value: &Datetime, expected: &str) -> String {
+        format!("expected {}, found {} {}", expected, kind(value), value)
+    }
+
+    pub(super) fn toml_date(year: i32, month: u8, day: u8) -> Result<Date, String> {
+        let year = u16::try_from(year)
+            .ok()
+            .filter(|year| *year <= 9999)
+            .ok_or_else(|| format!("year {} is out of range for TOML", year))?;
+        Ok(Date { year, month, day })
+    }
+
+    pub(super) fn toml_offset(seconds: i32) -> Result<Offset, String> {
+        match seconds {
+            0 => Ok(Offset::Z),
+            _ if seconds % 60 == 0 => Ok(Offset::Custom {
+                minutes: (seconds / 60) as i16,
+            }),
+            _ => Err(format!(
+                "offset of {} seconds is not whole minutes",
+                seconds
+            )),
+        }
+    }
+
+    pub(super) fn offset_seconds(offset: Offset) -> i32 {
+        match offset {
+            Offset::Z => 0,
+            Offset::Custom { minutes } => i32::from(minutes) * 60,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use ::toml_datetime::{Datetime, Time};
+    use chrono::{
+        DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc,
+    };
+
+    use super::parts::{mismatch, offset_seconds, toml_date, toml_offset};
+    use super::TomlDatetime;
+
+    fn date(value: &Datetime) -> Option<NaiveDate> {
+        let date = value.date?;
+        NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())
+    }
+
+    fn time(value: &Datetime) -> Option<NaiveTime> {
+        let time = value.time?;
+        NaiveTime::from_hms_nano_opt(
+            time.hour.into(),
+            time.minute.into(),
+            time.second.into(),
+            time.nanosecond,
+        )
+    }
+
+    fn toml_time(time: &NaiveTime) -> Time {
+        Time {
+            hour: time.hour() as u8,
+            minute: time.minute() as u8,
+            second: time.second() as u8,
+            nanosecond: time.nanosecond(),
+        }
+    }
+
+    impl TomlDatetime for DateTime<FixedOffset> {
+        fn to_toml(&self) -> Result<Datetime, String> {
+            Ok(Datetime {
+                date: Some(toml_date(
+                    self.year(),
+                    self.month() as u8,
+                    self.day() as u8,
+                )?),
+                time: Some(toml_time(&self.time())),
+                offset: Some(toml_offset(self.offset().local_minus_utc())?),
+            })
+        }
+
+        fn from_toml(value: Datetime) -> Result<Self, String> {
+            let expected = "an offset datetime";
+            let offset = value.offset.ok_or_else(|| mismatch(&value, expected))?;
+            let offset = FixedOffset::east_opt(offset_seconds(offset))
+                .ok_or_else(|| mismatch(&value, expected))?;
+            match (date(&value), time(&value)) {
+                (Some(date), Some(time)) => date
+                    .and_time(time)
+                    .and_local_timezone(offset)
+                    .single()
+                    .ok_or_else(|| mismatch(&value, expected)),
+                _ => Err(mismatch(&value, expected)),
+            }
+        }
+    }
+
+    impl TomlDatetime for DateTime<Utc> {
+        fn to_toml(&self) -> Result<Datetime, String> {
+            DateTime::<FixedOffset>::from(*self).to_toml()
+        }
+
+        fn from_toml(value: Datetime) -> Result<Self, String> {
+            DateTime::<FixedOffset>::from_toml(value).map(|v| v.with_timezone(&Utc))
+        }
+    }
+
+    impl TomlDatetime for NaiveDateTime {
+        fn to_toml(&self) -> Result<Datetime, String> {
+            Ok(Datetime {
+                date: Some(toml_date(
+                    self.year(),
+                    self.month() as u8,
+                    self.day() as u8,
+                )?),
+                time: Some(toml_time(&self.time())),
+                offset: None,
+            })
+        }
+
+        fn from_toml(value: Datetime) -> Result<Self, String> {
+            match (date(&value), time(&value)) {
+                (Some(date), Some(time)) if value.offset.is_none() => Ok(date.and_time(time)),
+                _ => Err(mismatch(&value, "a local datetime")),
+            }
+        }
+    }
+
+    impl TomlDatetime for NaiveDate {
+        fn to_toml(&self) -> Result<Datetime, String> {
+            Ok(Datetime {
+                date: Some(toml_date(
+                    self.year(),
+                    self.month() as u8,
+                    self.day() as u8,
+                )?),
+                time: None,
+                offset: None,
+            })
+        }
+
+        fn from_toml(value: Datetime) -> Result<Self, String> {
+            match date(&value) {
+                Some(date) if value.time.is_none() => Ok(date),
+                _ => Err(mismatch(&value, "a local date")),
+            }
+        }
+    }
+
+    impl TomlDatetime for NaiveTime {
+        fn to_toml(&self) -> Result<Datetime, String> {
+            Ok(Datetime {
+                date: None,
+                time: Some(toml_time(self)),
+                offset: None,
+            })
+        }
+
+        fn from_toml(value: Datetime) -> Result<Self, String> {
+            match time(&value) {
+                Some(time) if value.date.is_none() => Ok(time),
+                _ => Err(mismatch(&value, "a local time")),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use ::toml_datetime::Datetime;
+
+    use super::parts::{mismatch, offset_seconds, toml_date, toml_offset};
+    use super::TomlDatetime;
+
+    fn date(value: &Datetime) -> Option<time::Date> {
+        let date = value.date?;
+        let month = time::Month::try_from(date.month).ok()?;
+        time::Date::from_calendar_date(date.year.into(), month, date.day).ok()
+    }
+
+    fn time(value: &Datetime) -> Option<time::Time> {
+        let time = value.time?;
+        time::Time::from_hms_nano(time.hour, time.minute, time.second, time.nanosecond).ok()
+    }
+
+    fn toml_time(time: time::Time) -> ::toml_datetime::Time {
+        ::toml_datetime::Time {
+            hour: time.hour(),
+            minute: time.minute(),
+            second: time.second(),
+            nanosecond: time.nanosecond(),
+        }
+    }
+
+    impl TomlDatetime for time::OffsetDateTime {
+        fn to_toml(&self) -> Result<Datetime, String> {
+            Ok(Datetime {
+                offset: Some(toml_offset(self.offset().whole_seconds())?),
+                ..time::PrimitiveDateTime::new(self.date(), self.time()).to_toml()?
+            })
+        }
+
+        fn from_toml(value: Datetime) -> Result<Self, String> {
+            let expected = "an offset datetime";
+            let offset = value.offset.ok_or_else(|| mismatch(&value, expected))?;
+            let offset = time::UtcOffset::from_whole_seconds(offset_seconds(offset))
+                .map_err(|_| mismatch(&value, expected))?;
+            match (date(&value), time(&value)) {
+                (Some(date), Some(time)) => {
+                    Ok(time::OffsetDateTime::new_in_offset(date, time, offset))
+                }
+                _ => Err(mismatch(&value, expected)),
+            }
+        }
+    }
+
+    impl TomlDatetime for time::PrimitiveDateTime {
+        fn to_toml(&self) -> Result<Datetime, String> {
+            Ok(Datetime {
+                time: Some(toml_time(self.time())),
+                ..self.date().to_toml()?
+            })
+        }
+
+        fn from_toml(value: Datetime) -> Result<Self, String> {
+            match (date(&value), time(&value)) {
+                (Some(date), Some(time)) if value.offset.is_none() => {
+                    Ok(time::PrimitiveDateTime::new(date, time))
+                }
+                _ => Err(mismatch(&value, "a local datetime")),
+            }
+        }
+    }
+
+    impl TomlDatetime for time::Date {
+        fn to_toml(&self) -> Result<Datetime, String> {
+            Ok(Datetime {
+                date: Some(toml_date(self.year(), self.month().into(), self.day())?),
+                time: None,
+                offset: None,
+            })
+        }
+
+        fn from_toml(value: Datetime) -> Result<Self, String> {
+            match date(&value) {
+                Some(date) if value.time.is_none() => Ok(date),
+                _ => Err(mismatch(&value, "a local date")),
+            }
+        }
+    }
+
+    impl TomlDatetime for time::Time {
+        fn to_toml(&self) -> Result<Datetime, String> {
+            Ok(Datetime {
+                date: None,
+                time: Some(toml_time(*self)),
+                offset: None,
+            })
+        }
+
+        fn from_toml(value: Datetime) -> Result<Self, String> {
+            match time(&value) {
+                Some(time) if value.date.is_none() => Ok(time),
+                _ => Err(mismatch(&value, "a local time")),
+            }
+        }
+    }
+}
+
+struct DatetimeVisitor;
+
+impl<'de> Visitor<'de> for DatetimeVisitor {
+    type Value = Datetime;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a TOML datetime, or a string of one")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Datetime, E>
+    where
+        E: de::Error,
+    {
+        value
+            .parse()
+            .map_err(|e| E::custom(format!("{} in \"{}\"", e, value)))
+    }
+
+    // The `toml` crate hands over a native datetime as a private map.
+    fn visit_map<M>(self, map: M) -> Result<Datetime, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        Datetime::deserialize(de::value::MapAccessDeserializer::new(map))
+    }
+}
+
+pub fn serialize<S: Serializer, T: TomlDatetime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let value = v.to_toml().map_err(serde::ser::Error::custom)?;
+    s.collect_str(&value)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: TomlDatetime>(d: D) -> Result<T, D::Error> {
+    let value = d.deserialize_any(DatetimeVisitor)?;
+    T::from_toml(value).map_err(de::Error::custom)
+}
+
+/// Write a native TOML datetime.  Other formats see a private map.
+pub mod native {
+    use serde::{Deserializer, Serialize, Serializer};
+
+    use super::TomlDatetime;
+
+    pub fn serialize<S: Serializer, T: TomlDatetime>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        let value = v.to_toml().map_err(serde::ser::Error::custom)?;
+        value.serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: TomlDatetime>(d: D) -> Result<T, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::toml_datetime::native")]
+            pub offset: DateTime<FixedOffset>,
+            #[serde(with = "crate::toml_datetime::native")]
+            pub utc: DateTime<Utc>,
+            #[serde(with = "crate::toml_datetime::native")]
+            pub local: NaiveDateTime,
+            #[serde(with = "crate::toml_datetime::native")]
+            pub date: NaiveDate,
+            #[serde(with = "crate::toml_datetime::native")]
+            pub time: NaiveTime,
+        }
+
+        let toml = "offset = 1979-05-27T00:32:00.999999-07:00\n\
+                    utc = 1979-05-27 07:32:00+01:00\n\
+                    local = 1979-05-27T07:32:00\n\
+                    date = 1979-05-27\n\
+                    time = 00:32:00.5\n";
+        let outer: Outer = toml::from_str(toml).expect("Oops!");
+        let date = NaiveDate::from_ymd_opt(1979, 5, 27).expect("Oops!");
+        assert_eq!(
+            outer,
+            Outer {
+                offset: date
+                    .and_hms_micro_opt(0, 32, 0, 999999)
+                    .expect("Oops!")
+                    .and_local_timezone(FixedOffset::west_opt(7 * 3600).expect("Oops!"))
+                    .unwrap(),
+                utc: date.and_hms_opt(6, 32, 0).expect("Oops!").and_utc(),
+                local: date.and_hms_opt(7, 32, 0).expect("Oops!"),
+                date,
+                time: NaiveTime::from_hms_milli_opt(0, 32, 0, 500).expect("Oops!"),
+            }
+        );
+
+        let result = toml::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            "offset = 1979-05-27T00:32:00.999999-07:00\n\
+             utc = 1979-05-27T06:32:00Z\n\
+             local = 1979-05-27T07:32:00\n\
+             date = 1979-05-27\n\
+             time = 00:32:00.5\n"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn json() {
+        use chrono::{NaiveDate, NaiveDateTime};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::toml_datetime")]
+            pub local: NaiveDateTime,
+            #[serde(
+                default,
+                with = "crate::option_toml_datetime",
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub date: Option<NaiveDate>,
+        }
+
+        let toml = "local = 1979-05-27T07:32:00\ndate = 1979-05-27\n";
+        let outer: Outer = toml::from_str(toml).expect("Oops!");
+        let json = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            json,
+            r#"{"local":"1979-05-27T07:32:00","date":"1979-05-27"}"#
+        );
+        let result: Outer = serde_json::from_str(&json).expect("Oops!");
+        assert_eq!(outer, result);
+
+        let outer: Outer =
+            serde_json::from_str(r#"{"local": "1979-05-27 07:32:00"}"#).expect("Oops!");
+        assert_eq!(outer.date, None);
+        let result = toml::to_string(&outer).expect("Oops!");
+        assert_eq!(result, "local = \"1979-05-27T07:32:00\"\n");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn mismatch() {
+        use chrono::{DateTime, NaiveDate, Utc};
+        use serde::Deserialize;
+
+        #[derive(Deserialize, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::toml_datetime")]
+            pub date: NaiveDate,
+        }
+
+        #[derive(Deserialize, Debug)]
+        pub struct Instant {
+            #[serde(with = "crate::toml_datetime")]
+            pub at: DateTime<Utc>,
+        }
+
+        let outer: Outer = toml::from_str("date = 1979-05-27").expect("Oops!");
+        assert_eq!(outer.date.to_string(), "1979-05-27");
+        let instant: Instant = toml::from_str("at = 1979-05-27T07:32:00Z").expect("Oops!");
+        assert_eq!(instant.at.to_rfc3339(), "1979-05-27T07:32:00+00:00");
+
+        let err = toml::from_str::<Outer>("date = 1979-05-27T07:32:00Z")
+            .expect_err("Oops!")
+            .to_string();
+        let msg = "expected a local date, found an offset datetime 1979-05-27T07:32:00Z";
+        assert!(err.contains(msg), "{}", err);
+
+        let err = toml::from_str::<Instant>("at = 1979-05-27T07:32:00")
+            .expect_err("Oops!")
+            .to_string();
+        let msg = "expected an offset datetime, found a local datetime 1979-05-27T07:32:00";
+        assert!(err.contains(msg), "{}", err);
+
+        let err = serde_json::from_str::<Outer>(r#"{"date": "27/05/1979"}"#)
+            .expect_err("Oops!")
+            .to_string();
+        assert!(err.contains("in \"27/05/1979\""), "{}", err);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use serde::{Deserialize, Serialize};
+        use time::macros::{date, datetime, time};
+        use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        pub struct Outer {
+            #[serde(with = "crate::toml_datetime::native")]
+            pub offset: OffsetDateTime,
+            #[serde(with = "crate::toml_datetime")]
+            pub local: PrimitiveDateTime,
+            #[serde(with = "crate::toml_datetime::native")]
+            pub date: Date,
+            #[serde(with = "crate::toml_datetime")]
+            pub time: Time,
+        }
+
+        let toml = "offset = 1979-05-27T07:32:00+05:30\n\
+                    local = 1979-05-27T07:32:00.25\n\
+                    date = 1979-05-27\n\
+                    time = 07:32:00\n";
+        let outer: Outer = toml::from_str(toml).expect("Oops!");
+        assert_eq!(
+            outer,
+            Outer {
+                offset: datetime!(1979-05-27 07:32:00 +05:30),
+                local: datetime!(1979-05-27 07:32:00.25),
+                date: date!(1979 - 05 - 27),
+                time: time!(07:32:00),
+            }
+        );
+
+        let result = toml::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            "offset = 1979-05-27T07:32:00+05:30\n\
+             local = \"1979-05-27T07:32:00.25\"\n\
+             date = 1979-05-27\n\
+             time = \"07:32:00\"\n"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Deserialize a `String` with leading and trailing whitespace removed
+//!
+//! Human-edited data is full of stray spaces that break exact matches further
+//! downstream.  Use `trimmed` to strip them at the boundary, or
+//! `trimmed::collapse` to also collapse every internal run of whitespace into
+//! a single space.  Values are serialized unchanged.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::trimmed")]
+//!     pub name: String,
+//!     #[serde(with = "serde_stuff::trimmed::collapse")]
+//!     pub title: String,
+//! }
+//! ```
+//! The following will deserialize to `Outer { name: "Jane", title: "Chief Cook" }`
+//! ```json
+//! {
+//!     "name": "  Jane ",
+//!     "title": " Chief \t  Cook "
+//! }
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::Deserialize;
+use serde::{Deserializer, Serializer};
+
+/// Collapse every run of whitespace into a single space, dropping any at
+/// either end.
+pub(crate) fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(v)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let value = String::deserialize(d)?;
+    Ok(value.trim().to_string())
+}
+
+/// Trim, and collapse internal whitespace.
+pub mod collapse {
+    use alloc::string::String;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+        super::serialize(v, s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+        let value = String::deserialize(d)?;
+        Ok(super::collapse_whitespace(&value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::trimmed")]
+        pub name: String,
+        #[serde(with = "crate::trimmed::collapse")]
+        pub title: String,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "name": "  Jane  Doe\n",
+            "title": " Chief \t  Cook "
+        }"#;
+        let outer = Outer {
+            name: "Jane  Doe".to_string(),
+            title: "Chief Cook".to_string(),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            name: "Jane".to_string(),
+            title: "Chief Cook".to_string(),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"name":"Jane","title":"Chief Cook"}"#);
+    }
+}
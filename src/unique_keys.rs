@@ -0,0 +1,209 @@
+//! Deserialize a map, rejecting duplicate keys
+//!
+//! Serde keeps the last value when a key is repeated, so
+//! `{"role": "user", "role": "admin"}` quietly becomes an admin.  In a
+//! security sensitive config a repeated key is a mistake or tampering, so
+//! this module fails instead, and names the repeated key.  Serializing is
+//! unchanged.
+//!
+//! [UniqueMap] is implemented for `HashMap` and `BTreeMap`, and for
+//! `IndexMap` with the `indexmap` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::unique_keys")]
+//!     pub grants: HashMap<String, String>,
+//! }
+//! ```
+//! The following will fail with `duplicate key "alice"`
+//! ```json
+//! {
+//!     "grants": {"alice": "read", "bob": "read", "alice": "admin"}
+//! }
+//! ```
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+/// Maps that can refuse to replace an existing key.
+pub trait UniqueMap<K, V>: Default {
+    /// Insert `key` and `value`, or give `key` back if it is already present.
+    fn insert_unique(&mut self, key: K, value: V) -> Result<(), K>;
+}
+
+impl<K: Eq + Hash, V, H: BuildHasher + Default> UniqueMap<K, V> for HashMap<K, V, H> {
+    fn insert_unique(&mut self, key: K, value: V) -> Result<(), K> {
+        if self.contains_key(&key) {
+            return Err(key);
+        }
+        self.insert(key, value);
+        Ok(())
+    }
+}
+
+impl<K: Ord, V> UniqueMap<K, V> for BTreeMap<K, V> {
+    fn insert_unique(&mut self, key: K, value: V) -> Result<(), K> {
+        if self.contains_key(&key) {
+            return Err(key);
+        }
+        self.insert(key, value);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K: Eq + Hash, V, H: BuildHasher + Default> UniqueMap<K, V> for indexmap::IndexMap<K, V, H> {
+    fn insert_unique(&mut self, key: K, value: V) -> Result<(), K> {
+        if self.contains_key(&key) {
+            return Err(key);
+        }
+        self.insert(key, value);
+        Ok(())
+    }
+}
+
+pub fn serialize<S: Serializer, M: Serialize>(v: &M, s: S) -> Result<S::Ok, S::Error> {
+    v.serialize(s)
+}
+
+pub fn deserialize<'de, D, M, K, V>(d: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: UniqueMap<K, V>,
+    K: Deserialize<'de> + Debug,
+    V: Deserialize<'de>,
+{
+    struct UniqueVisitor<M, K, V>(PhantomData<(M, K, V)>);
+
+    impl<'de, M, K, V> Visitor<'de> for UniqueVisitor<M, K, V>
+    where
+        M: UniqueMap<K, V>,
+        K: Deserialize<'de> + Debug,
+        V: Deserialize<'de>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map without duplicate keys")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<M, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut out = M::default();
+            while let Some((key, value)) = map.next_entry::<K, V>()? {
+                out.insert_unique(key, value)
+                    .map_err(|key| de::Error::custom(format!("duplicate key {:?}", key)))?;
+            }
+            Ok(out)
+        }
+    }
+
+    d.deserialize_map(UniqueVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UniqueMap;
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::unique_keys")]
+        pub grants: HashMap<String, String>,
+        #[serde(with = "crate::unique_keys")]
+        pub limits: BTreeMap<u32, u32>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"grants": {"alice": "read", "bob": "admin"}, "limits": {"1": 10}}"#;
+        let outer = Outer {
+            grants: HashMap::from([
+                ("alice".to_string(), "read".to_string()),
+                ("bob".to_string(), "admin".to_string()),
+            ]),
+            limits: BTreeMap::from([(1, 10)]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+        assert_eq!(
+            serde_json::to_string(&result.limits).expect("Oops!"),
+            r#"{"1":10}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_duplicate() {
+        let json =
+            r#"{"grants": {"alice": "read", "bob": "read", "alice": "admin"}, "limits": {}}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("duplicate key \"alice\""), "{}", err);
+
+        let json = r#"{"grants": {}, "limits": {"1": 10, "2": 20, "1": 30}}"#;
+        let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(err.contains("duplicate key 1"), "{}", err);
+    }
+
+    #[test]
+    fn insert_unique_keeps_first() {
+        let mut grants = HashMap::from([("alice".to_string(), "read".to_string())]);
+        assert_eq!(
+            grants.insert_unique("alice".to_string(), "admin".to_string()),
+            Err("alice".to_string())
+        );
+        assert_eq!(grants.get("alice").map(String::as_str), Some("read"));
+
+        let mut limits = BTreeMap::from([(1, 10)]);
+        assert_eq!(limits.insert_unique(1, 30), Err(1));
+        assert_eq!(limits.get(&1), Some(&10));
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn indexmap() {
+        use indexmap::IndexMap;
+
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct Ordered {
+            #[serde(with = "crate::unique_keys")]
+            pub grants: IndexMap<String, String>,
+        }
+
+        let json = r#"{"grants":{"zed":"read","alice":"admin"}}"#;
+        let result: Ordered = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(serde_json::to_string(&result).expect("Oops!"), json);
+
+        let json = r#"{"grants":{"zed":"read","zed":"admin"}}"#;
+        let err = serde_json::from_str::<Ordered>(json)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("duplicate key \"zed\""), "{}", err);
+
+        let mut grants = IndexMap::from([
+            ("zed".to_string(), "read".to_string()),
+            ("alice".to_string(), "admin".to_string()),
+            ("bob".to_string(), "read".to_string()),
+        ]);
+        assert_eq!(
+            grants.insert_unique("zed".to_string(), "admin".to_string()),
+            Err("zed".to_string())
+        );
+        let keys: Vec<_> = grants.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["zed", "alice", "bob"]);
+        assert_eq!(grants["zed"], "read");
+    }
+}
@@ -0,0 +1,124 @@
+//! Normalize a `String` to uppercase on deserialize
+//!
+//! Use this for case-insensitive identifiers such as region or currency
+//! codes.  By default only the deserialized value is normalized, and values
+//! serialize unchanged.  The companion modules cover the other cases:
+//!
+//! - `uppercase::both` also normalizes on serialize.
+//! - `uppercase::keys` normalizes the keys of a map with `String` keys, such
+//!   as `HashMap<String, V>`.  If two keys normalize to the same value, the
+//!   map's own semantics decide which entry is kept.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::collections::HashMap;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::uppercase")]
+//!     pub id: String,
+//!     #[serde(with = "serde_stuff::uppercase::keys")]
+//!     pub tags: HashMap<String, String>,
+//! }
+//! ```
+//! The following will deserialize `id` to `"US-EAST-1"`
+//! ```json
+//! {
+//!     "id": "us-east-1",
+//!     "tags": {}
+//! }
+//! ```
+
+use serde::{Deserializer, Serializer};
+
+fn convert(value: &str) -> String {
+    value.to_uppercase()
+}
+
+pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(v)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    crate::case::deserialize(d, convert)
+}
+
+/// Normalize on both deserialize and serialize.
+pub mod both {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+        crate::case::serialize(v, s, super::convert)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+/// Normalize the keys of a map on deserialize.
+pub mod keys {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, M: Serialize>(v: &M, s: S) -> Result<S::Ok, S::Error> {
+        v.serialize(s)
+    }
+
+    pub fn deserialize<'de, D, V, M>(d: D) -> Result<M, D::Error>
+    where
+        D: Deserializer<'de>,
+        V: Deserialize<'de>,
+        M: FromIterator<(String, V)>,
+    {
+        crate::case::deserialize_keys(d, super::convert)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::uppercase")]
+        pub id: String,
+        #[serde(with = "crate::uppercase::both")]
+        pub other: String,
+        #[serde(with = "crate::uppercase::keys")]
+        pub map: BTreeMap<String, u32>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "id": "us-east-1",
+            "other": "us-east-1",
+            "map": { "eur": 1 }
+        }"#;
+        let outer = Outer {
+            id: "US-EAST-1".to_string(),
+            other: "US-EAST-1".to_string(),
+            map: BTreeMap::from([("EUR".to_string(), 1)]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            id: "us-east-1".to_string(),
+            other: "us-east-1".to_string(),
+            map: BTreeMap::from([("eur".to_string(), 1)]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"id":"us-east-1","other":"US-EAST-1","map":{"eur":1}}"#
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Deserialize a single URL string, or a list of them, to `Vec<Url>`
+//!
+//! Metadata documents, such as OAuth client registrations, often allow a
+//! field like `redirect_uris` to be either one URL or a list of URLs.  This
+//! combines [vec_or_one](crate::vec_or_one) with
+//! [url_string](crate::url_string): either shape is accepted, and each URL
+//! is parsed with the invalid text in any error.
+//!
+//! Like `vec_or_one`, a single URL is serialized as a string, and any other
+//! number as a list.  To always write a list, use
+//! `serialize_with = "serde_stuff::url_string::vec::serialize"`.
+//!
+//! Requires the `url` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use url::Url;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::url_or_urls")]
+//!     pub redirect_uris: Vec<Url>,
+//! }
+//! ```
+//! The following will both deserialize to `Outer`
+//! ```json
+//! {
+//!     "redirect_uris": "https://app.example.com/cb"
+//! }
+//! ```
+//! ```json
+//! {
+//!     "redirect_uris": ["https://app.example.com/cb", "https://app.example.com/cb2"]
+//! }
+//! ```
+
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use url::Url;
+
+use super::url_string;
+use super::vec_or_one::VecOrOne;
+
+pub fn serialize<S: Serializer>(v: &[Url], s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        [url] => str::serialize(url.as_str(), s),
+        _ => {
+            let mut seq = s.serialize_seq(Some(v.len()))?;
+            for url in v {
+                seq.serialize_element(url.as_str())?;
+            }
+            seq.end()
+        }
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Url>, D::Error> {
+    let values = match VecOrOne::<String>::deserialize(d)? {
+        VecOrOne::Vec(v) => v,
+        VecOrOne::One(v) => vec![v],
+    };
+    values
+        .iter()
+        .map(|v| url_string::parse(v))
+        .collect::<Result<_, _>>()
+        .map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use url::Url;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::url_or_urls")]
+        pub redirect_uris: Vec<Url>,
+    }
+
+    fn url(value: &str) -> Url {
+        Url::parse(value).unwrap()
+    }
+
+    #[test]
+    fn deserialize_one() {
+        let json = r#"{"redirect_uris": "https://app.example.com/cb"}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(
+            result.redirect_uris,
+            vec![url("https://app.example.com/cb")]
+        );
+    }
+
+    #[test]
+    fn deserialize_many() {
+        let json = r#"{"redirect_uris": ["https://a.example.com/", "https://b.example.com/"]}"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(
+            result.redirect_uris,
+            vec![url("https://a.example.com/"), url("https://b.example.com/")]
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"redirect_uris": ["https://a.example.com/", "/cb"]}"#;
+        let msg = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(msg.contains("invalid URL \"/cb\""), "{}", msg);
+        assert!(serde_json::from_str::<Outer>(r#"{"redirect_uris": 1}"#).is_err());
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            redirect_uris: vec![url("https://a.example.com/")],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"redirect_uris":"https://a.example.com/"}"#);
+
+        let outer = Outer {
+            redirect_uris: vec![url("https://a.example.com/"), url("https://b.example.com/")],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"redirect_uris":["https://a.example.com/","https://b.example.com/"]}"#
+        );
+    }
+}
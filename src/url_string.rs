@@ -0,0 +1,124 @@
+//! Serialize and Deserialize a `url::Url` as a string
+//!
+//! URLs are written in their serialized string form, and parsed as absolute
+//! URLs.  A parse error includes the invalid URL text, such as
+//! `invalid URL "example.com/cb": relative URL without a base`.  Use
+//! `url_string::vec` for a list of URLs.
+//!
+//! Requires the `url` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use url::Url;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::url_string")]
+//!     pub issuer: Url,
+//!     #[serde(with = "serde_stuff::url_string::vec")]
+//!     pub mirrors: Vec<Url>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "issuer": "https://auth.example.com/",
+//!     "mirrors": ["https://a.example.com/", "https://b.example.com/"]
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use url::Url;
+
+pub(crate) fn parse(value: &str) -> Result<Url, String> {
+    Url::parse(value).map_err(|e| format!("invalid URL \"{}\": {}", value, e))
+}
+
+pub fn serialize<S: Serializer>(v: &Url, s: S) -> Result<S::Ok, S::Error> {
+    str::serialize(v.as_str(), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Url, D::Error> {
+    let value = String::deserialize(d)?;
+    parse(&value).map_err(serde::de::Error::custom)
+}
+
+/// A `Vec` of URLs, as a list of strings.
+pub mod vec {
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use url::Url;
+
+    pub fn serialize<S: Serializer>(v: &[Url], s: S) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(v.len()))?;
+        for url in v {
+            seq.serialize_element(url.as_str())?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Url>, D::Error> {
+        <Vec<String>>::deserialize(d)?
+            .iter()
+            .map(|v| super::parse(v))
+            .collect::<Result<_, _>>()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use url::Url;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::url_string")]
+        pub issuer: Url,
+        #[serde(with = "crate::url_string::vec")]
+        pub mirrors: Vec<Url>,
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            issuer: Url::parse("https://auth.example.com").unwrap(),
+            mirrors: vec![Url::parse("https://a.example.com/x?y=1").unwrap()],
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"issuer":"https://auth.example.com/","mirrors":["https://a.example.com/x?y=1"]}"#
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "issuer": "https://auth.example.com/",
+            "mirrors": ["https://a.example.com/", "https://b.example.com/"]
+        }"#;
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(result.issuer.host_str(), Some("auth.example.com"));
+        assert_eq!(result.mirrors.len(), 2);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"issuer": "auth.example.com/cb", "mirrors": []}"#;
+        let msg = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(
+            msg.contains("invalid URL \"auth.example.com/cb\""),
+            "{}",
+            msg
+        );
+
+        let json = r#"{"issuer": "https://auth.example.com/", "mirrors": ["http://[::1"]}"#;
+        let msg = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+        assert!(msg.contains("\"http://[::1\""), "{}", msg);
+    }
+}
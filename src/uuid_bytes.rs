@@ -0,0 +1,152 @@
+//! Serialize and Deserialize a `Uuid` as 16 raw bytes, or base64 in text formats
+//!
+//! Compact storage protocols don't want a 36 character string for every id.
+//! Binary formats, such as bincode or CBOR, get the 16 bytes of the `Uuid`
+//! directly.  Human readable formats, such as JSON, get the bytes as unpadded
+//! URL safe base64, which is 22 characters.
+//!
+//! On deserialize, human readable formats accept the standard or URL safe
+//! base64 alphabet, with or without padding.  Binary formats accept bytes,
+//! or a sequence of 16 integers.
+//!
+//! Requires the `uuid` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use uuid::Uuid;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::uuid_bytes")]
+//!     pub id: Uuid,
+//! }
+//! ```
+//! The following will deserialize to
+//! `Outer { id: uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8") }`
+//! ```json
+//! {
+//!     "id": "Z-VQRBCxQm-SR7toDl_gyA"
+//! }
+//! ```
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+use uuid::Uuid;
+
+fn decode(value: &str) -> Option<Vec<u8>> {
+    let value = value.trim_end_matches('=');
+    general_purpose::URL_SAFE_NO_PAD
+        .decode(value)
+        .or_else(|_| general_purpose::STANDARD_NO_PAD.decode(value))
+        .ok()
+}
+
+pub fn serialize<S: Serializer>(v: &Uuid, s: S) -> Result<S::Ok, S::Error> {
+    if s.is_human_readable() {
+        s.serialize_str(&general_purpose::URL_SAFE_NO_PAD.encode(v.as_bytes()))
+    } else {
+        s.serialize_bytes(v.as_bytes())
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Uuid, D::Error> {
+    struct UuidBytes;
+
+    impl<'de> Visitor<'de> for UuidBytes {
+        type Value = Uuid;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("16 bytes, or a base64 string of 16 bytes")
+        }
+
+        fn visit_bytes<E>(self, value: &[u8]) -> Result<Uuid, E>
+        where
+            E: de::Error,
+        {
+            Uuid::from_slice(value).map_err(|_| E::invalid_length(value.len(), &self))
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Uuid, E>
+        where
+            E: de::Error,
+        {
+            let bytes =
+                decode(value).ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))?;
+            self.visit_bytes(&bytes)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Uuid, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = [0u8; 16];
+            for (idx, byte) in bytes.iter_mut().enumerate() {
+                *byte = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(idx, &self))?;
+            }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(de::Error::invalid_length(17, &self));
+            }
+            Ok(Uuid::from_bytes(bytes))
+        }
+    }
+
+    if d.is_human_readable() {
+        d.deserialize_str(UuidBytes)
+    } else {
+        d.deserialize_bytes(UuidBytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use uuid::Uuid;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::uuid_bytes")]
+        pub id: Uuid,
+    }
+
+    const ID: Uuid = Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8);
+
+    #[test]
+    fn serialize() {
+        let outer = Outer { id: ID };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"id":"Z-VQRBCxQm-SR7toDl_gyA"}"#);
+    }
+
+    #[test]
+    fn deserialize() {
+        let outer = Outer { id: ID };
+        for json in [
+            r#"{"id": "Z-VQRBCxQm-SR7toDl_gyA"}"#,
+            r#"{"id": "Z-VQRBCxQm-SR7toDl_gyA=="}"#,
+            r#"{"id": "Z+VQRBCxQm+SR7toDl/gyA=="}"#,
+        ] {
+            let result: Outer = serde_json::from_str(json).expect(json);
+            assert_eq!(&outer, &result);
+        }
+        assert!(serde_json::from_str::<Outer>(r#"{"id": "Z-VQRBCxQm"}"#).is_err());
+        assert!(serde_json::from_str::<Outer>(r#"{"id": "not base64!"}"#).is_err());
+    }
+
+    #[test]
+    fn binary() {
+        let outer = Outer { id: ID };
+        let result = bincode::serialize(&outer).expect("Oops!");
+        // bincode writes a u64 length prefix before the bytes
+        assert_eq!(result.len(), 8 + 16);
+        assert_eq!(&result[8..], ID.as_bytes());
+        let result: Outer = bincode::deserialize(&result).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+}
@@ -0,0 +1,151 @@
+//! Serialize and Deserialize a `Uuid` as a string, in a chosen form
+//!
+//! Deserialize is lenient, and accepts any of the common forms, in any case:
+//!
+//! - hyphenated: `"67e55044-10b1-426f-9247-bb680e5fe0c8"`
+//! - simple: `"67e5504410b1426f9247bb680e5fe0c8"`
+//! - URN: `"urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"`
+//! - braced: `"{67e55044-10b1-426f-9247-bb680e5fe0c8}"`
+//!
+//! Serialize is canonical, and always writes lower case in the form chosen
+//! by the module:
+//!
+//! - `uuid_string` writes the hyphenated form.
+//! - `uuid_string::simple` writes the simple form.
+//! - `uuid_string::urn` writes the URN form.
+//!
+//! Requires the `uuid` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use uuid::Uuid;
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::uuid_string")]
+//!     pub id: Uuid,
+//!     #[serde(with = "serde_stuff::uuid_string::urn")]
+//!     pub parent: Uuid,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "id": "67E5504410B1426F9247BB680E5FE0C8",
+//!     "parent": "67e55044-10b1-426f-9247-bb680e5fe0c8"
+//! }
+//! ```
+//! and serialize as
+//! ```json
+//! {
+//!     "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+//!     "parent": "urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
+use uuid::Uuid;
+
+pub fn serialize<S: Serializer>(v: &Uuid, s: S) -> Result<S::Ok, S::Error> {
+    String::serialize(&v.hyphenated().to_string(), s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Uuid, D::Error> {
+    let value = String::deserialize(d)?;
+    Uuid::parse_str(value.trim())
+        .map_err(|e| serde::de::Error::custom(format!("invalid UUID \"{}\": {}", value, e)))
+}
+
+/// Accept any form, and serialize without hyphens.
+pub mod simple {
+    use serde::Serialize;
+    use serde::{Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S: Serializer>(v: &Uuid, s: S) -> Result<S::Ok, S::Error> {
+        String::serialize(&v.simple().to_string(), s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Uuid, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+/// Accept any form, and serialize as a `urn:uuid:` URN.
+pub mod urn {
+    use serde::Serialize;
+    use serde::{Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S: Serializer>(v: &Uuid, s: S) -> Result<S::Ok, S::Error> {
+        String::serialize(&v.urn().to_string(), s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Uuid, D::Error> {
+        super::deserialize(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use uuid::Uuid;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::uuid_string")]
+        pub id: Uuid,
+        #[serde(with = "crate::uuid_string::simple")]
+        pub simple: Uuid,
+        #[serde(with = "crate::uuid_string::urn")]
+        pub urn: Uuid,
+    }
+
+    const ID: Uuid = Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8);
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            id: ID,
+            simple: ID,
+            urn: ID,
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"id":"67e55044-10b1-426f-9247-bb680e5fe0c8","simple":"67e5504410b1426f9247bb680e5fe0c8","urn":"urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"}"#
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "id": "urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "simple": "{67e55044-10b1-426f-9247-bb680e5fe0c8}",
+            "urn": "67E5504410B1426F9247BB680E5FE0C8"
+        }"#;
+        let outer = Outer {
+            id: ID,
+            simple: ID,
+            urn: ID,
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{
+            "id": "67e55044-10b1-426f-9247",
+            "simple": "67e5504410b1426f9247bb680e5fe0c8",
+            "urn": "67e5504410b1426f9247bb680e5fe0c8"
+        }"#;
+        let result = serde_json::from_str::<Outer>(json);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("invalid UUID"));
+    }
+}
@@ -0,0 +1,195 @@
+//! Apply a `serialize`/`deserialize` module to each element of a sequence
+//!
+//! A module handles exactly its field's type, so [base64](crate::base64)
+//! works on a `Vec<u8>`, but not on a `Vec<Vec<u8>>`.  The
+//! [vec_of!](crate::vec_of!) macro generates a module that runs the given
+//! module on each element of a sequence.
+//!
+//! The arguments are the visibility and name of the module to declare, the
+//! path of the module to apply, and either the element type or, for modules
+//! that are generic over a trait, `T:` and the trait:
+//!
+//! ```rust
+//! # #[cfg(feature = "base64")]
+//! serde_stuff::vec_of!(pub base64_list, serde_stuff::base64, Vec<u8>);
+//! serde_stuff::vec_of!(
+//!     pub epoch_list,
+//!     serde_stuff::timestamp_secs,
+//!     T: serde_stuff::epoch::EpochTime
+//! );
+//! ```
+//!
+//! The path must start from `crate` or a crate name, since it is used from
+//! inside the new module.  Types declared in the enclosing module are in
+//! scope.  The generated module serializes anything that iterates by
+//! reference, such as a `Vec`, a slice or a `VecDeque`, and deserializes
+//! into anything that collects, such as a `Vec` or a `BTreeSet`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! # #[cfg(feature = "base64")]
+//! serde_stuff::vec_of!(base64_list, serde_stuff::base64, Vec<u8>);
+//!
+//! # #[cfg(feature = "base64")]
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "base64_list")]
+//!     pub keys: Vec<Vec<u8>>,
+//! }
+//! ```
+//! The following will deserialize to `Outer`
+//! ```json
+//! {
+//!     "keys": ["3q2-7w==", "AQI="]
+//! }
+//! ```
+
+/// Generate a serde `with` module that applies a module to each element.
+#[macro_export]
+macro_rules! vec_of {
+    ($vis:vis $name:ident, $($module:ident)::+, T: $bound:path) => {
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            struct Element<'a, T>(&'a T);
+
+            impl<T: $bound> serde::Serialize for Element<'_, T> {
+                fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                    $($module)::+::serialize(self.0, s)
+                }
+            }
+
+            struct OwnedElement<T>(T);
+
+            impl<'de, T: $bound> serde::Deserialize<'de> for OwnedElement<T> {
+                fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                    $($module)::+::deserialize(d).map(OwnedElement)
+                }
+            }
+
+            #[allow(dead_code)]
+            pub fn serialize<S, C, T>(v: &C, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                C: ?Sized,
+                for<'a> &'a C: IntoIterator<Item = &'a T>,
+                T: $bound,
+            {
+                s.collect_seq(v.into_iter().map(Element))
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D, C, T>(d: D) -> Result<C, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                C: FromIterator<T>,
+                T: $bound,
+            {
+                let items = <Vec<OwnedElement<T>> as serde::Deserialize>::deserialize(d)?;
+                Ok(items.into_iter().map(|v| v.0).collect())
+            }
+        }
+    };
+    ($vis:vis $name:ident, $($module:ident)::+, $ty:ty) => {
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            struct Element<'a>(&'a $ty);
+
+            impl serde::Serialize for Element<'_> {
+                fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                    $($module)::+::serialize(self.0, s)
+                }
+            }
+
+            struct OwnedElement($ty);
+
+            impl<'de> serde::Deserialize<'de> for OwnedElement {
+                fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                    $($module)::+::deserialize(d).map(OwnedElement)
+                }
+            }
+
+            #[allow(dead_code)]
+            pub fn serialize<S, C>(v: &C, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                C: ?Sized,
+                for<'a> &'a C: IntoIterator<Item = &'a $ty>,
+            {
+                s.collect_seq(v.into_iter().map(Element))
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D, C>(d: D) -> Result<C, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                C: FromIterator<$ty>,
+            {
+                let items = <Vec<OwnedElement> as serde::Deserialize>::deserialize(d)?;
+                Ok(items.into_iter().map(|v| v.0).collect())
+            }
+        }
+    };
+}
+
+#[cfg(all(test, feature = "base64"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeSet, VecDeque};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    crate::vec_of!(base64_list, crate::base64, Vec<u8>);
+    crate::vec_of!(epoch_list, crate::timestamp_secs, T: crate::epoch::EpochTime);
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "base64_list")]
+        pub keys: Vec<Vec<u8>>,
+        #[serde(with = "base64_list")]
+        pub unique: BTreeSet<Vec<u8>>,
+        #[serde(with = "epoch_list")]
+        pub seen: VecDeque<SystemTime>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"keys": ["3q2-7w==", "AQI="], "unique": ["AQI=", "AA=="], "seen": [60]}"#;
+        let outer = Outer {
+            keys: vec![vec![0xde, 0xad, 0xbe, 0xef], vec![1, 2]],
+            unique: BTreeSet::from([vec![0], vec![1, 2]]),
+            seen: VecDeque::from([UNIX_EPOCH + Duration::from_secs(60)]),
+        };
+        let result: Outer = serde_json::from_str(json).expect("Oops!");
+        assert_eq!(&outer, &result);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            keys: vec![vec![1, 2]],
+            unique: BTreeSet::new(),
+            seen: VecDeque::from([UNIX_EPOCH]),
+        };
+        let result = serde_json::to_string(&outer).expect("Oops!");
+        assert_eq!(result, r#"{"keys":["AQI="],"unique":[],"seen":[0]}"#);
+    }
+
+    #[test]
+    fn slice() {
+        let keys: &[Vec<u8>] = &[vec![0]];
+        let result = base64_list::serialize(keys, serde_json::value::Serializer).expect("Oops!");
+        assert_eq!(result, serde_json::json!(["AA=="]));
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let json = r#"{"keys": ["!"], "unique": [], "seen": []}"#;
+        assert!(serde_json::from_str::<Outer>(json).is_err());
+    }
+}
@@ -30,6 +30,8 @@
 //! }
 //! ```
 
+use alloc::vec;
+use alloc::vec::Vec;
 use serde::{self, de, Deserialize, Serialize, Serializer};
 
 #[derive(Deserialize, Debug)]
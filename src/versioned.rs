@@ -0,0 +1,258 @@
+//! Read old versions of a document, and upgrade them to the current struct
+//!
+//! Stored documents outlive the structs that wrote them.  Rather than keep
+//! a struct for every old shape, a type implements [Versioned] with its
+//! current version and a list of [Migration]s, each of which upgrades the
+//! fields of a document by one version.  [deserialize] reads the version
+//! field, runs every migration from there to the current version, and then
+//! deserializes the type as usual.  [serialize] writes the current version
+//! alongside the fields.
+//!
+//! Migrations work on the top level fields as an [Extra], in order, so they
+//! can rename, remove, add or rewrite fields without a struct for the old
+//! shape.  The version field is removed before the type sees the document,
+//! so the type should not declare it.  Documents from before versioning,
+//! with no version field, are read as [Versioned::UNVERSIONED], if set.
+//!
+//! Since the document is buffered as [Content], the format must be self
+//! describing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use serde_stuff::content::Content;
+//! use serde_stuff::extra::Extra;
+//! use serde_stuff::versioned::{Migration, Versioned};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Settings {
+//!     pub host: String,
+//!     pub port: u16,
+//! }
+//!
+//! // Version 1 had a single "address" of "host:port".
+//! fn split_address(doc: &mut Extra) -> Result<(), String> {
+//!     let address = doc.remove("address").ok_or("missing address")?;
+//!     let address = address.as_str().ok_or("address is not a string")?;
+//!     let (host, port) = address.split_once(':').ok_or("address has no port")?;
+//!     let port: u16 = port.parse().map_err(|e| format!("invalid port: {}", e))?;
+//!     doc.insert("host", Content::String(host.to_string()));
+//!     doc.insert("port", Content::U64(port as u64));
+//!     Ok(())
+//! }
+//!
+//! impl Versioned for Settings {
+//!     const CURRENT: u64 = 2;
+//!     const UNVERSIONED: Option<u64> = Some(1);
+//!     const MIGRATIONS: &'static [(u64, Migration)] = &[(1, split_address)];
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(with = "serde_stuff::versioned")]
+//!     pub settings: Settings,
+//! }
+//! ```
+//! The following will both deserialize to `Outer`, and it serializes as the
+//! second
+//! ```json
+//! {
+//!     "settings": {"address": "localhost:8080"}
+//! }
+//! ```
+//! ```json
+//! {
+//!     "settings": {"version": 2, "host": "localhost", "port": 8080}
+//! }
+//! ```
+
+use serde::de::{self, DeserializeOwned};
+use serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::content::Content;
+use super::extra::Extra;
+
+/// Upgrade the fields of a document by one version.
+pub type Migration = fn(&mut Extra) -> Result<(), String>;
+
+/// A type whose documents carry a version, and can be upgraded.
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// The field that holds the version.
+    const FIELD: &'static str = "version";
+    /// The version this type reads and writes.
+    const CURRENT: u64;
+    /// The version of a document with no version field.  If `None`, the
+    /// field is required.
+    const UNVERSIONED: Option<u64> = None;
+    /// The migrations, as the version each upgrades from.  Every version
+    /// from the oldest supported to `CURRENT - 1` needs one.
+    const MIGRATIONS: &'static [(u64, Migration)];
+}
+
+/// Upgrade `doc` to `T::CURRENT`, and remove its version field.
+pub fn migrate<T: Versioned>(doc: &mut Extra) -> Result<(), String> {
+    let mut version = match doc.remove(T::FIELD) {
+        Some(Content::U64(v)) => v,
+        Some(Content::I64(v)) if v >= 0 => v as u64,
+        Some(other) => return Err(format!("invalid {} {:?}", T::FIELD, other)),
+        None => T::UNVERSIONED.ok_or_else(|| format!("missing field `{}`", T::FIELD))?,
+    };
+    if version > T::CURRENT {
+        return Err(format!(
+            "unsupported {} {}, the newest is {}",
+            T::FIELD,
+            version,
+            T::CURRENT
+        ));
+    }
+    while version < T::CURRENT {
+        let (_, migration) = T::MIGRATIONS
+            .iter()
+            .find(|(from, _)| *from == version)
+            .ok_or_else(|| format!("no migration from {} {}", T::FIELD, version))?;
+        migration(doc).map_err(|e| format!("migration from {} {}: {}", T::FIELD, version, e))?;
+        version += 1;
+    }
+    Ok(())
+}
+
+pub fn serialize<S: Serializer, T: Versioned>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    let fields: Extra = Content::from_value(v)
+        .and_then(Content::deserialize_into)
+        .map_err(ser::Error::custom)?;
+    let mut doc = Extra::new();
+    doc.insert(T::FIELD, Content::U64(T::CURRENT));
+    for (key, value) in fields.iter() {
+        doc.insert(key, value.clone());
+    }
+    doc.serialize(s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: Versioned>(d: D) -> Result<T, D::Error> {
+    let mut doc = Extra::deserialize(d)?;
+    migrate::<T>(&mut doc).map_err(de::Error::custom)?;
+    Content::from_value(&doc)
+        .and_then(Content::deserialize_into)
+        .map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    use super::{Migration, Versioned};
+    use crate::content::Content;
+    use crate::extra::Extra;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    #[serde(deny_unknown_fields)]
+    pub struct Settings {
+        pub host: String,
+        pub port: u16,
+        pub tls: bool,
+    }
+
+    fn split_address(doc: &mut Extra) -> Result<(), String> {
+        let address = doc.remove("address").ok_or("missing address")?;
+        let address = address.as_str().ok_or("address is not a string")?;
+        let (host, port) = address.split_once(':').ok_or("address has no port")?;
+        let port: u16 = port.parse().map_err(|e| format!("invalid port: {}", e))?;
+        doc.insert("host", Content::String(host.to_string()));
+        doc.insert("port", Content::U64(port as u64));
+        Ok(())
+    }
+
+    fn add_tls(doc: &mut Extra) -> Result<(), String> {
+        doc.insert("tls", Content::Bool(false));
+        Ok(())
+    }
+
+    impl Versioned for Settings {
+        const FIELD: &'static str = "schema";
+        const CURRENT: u64 = 3;
+        const UNVERSIONED: Option<u64> = Some(1);
+        const MIGRATIONS: &'static [(u64, Migration)] = &[(1, split_address), (2, add_tls)];
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(with = "crate::versioned")]
+        pub settings: Settings,
+    }
+
+    fn outer(tls: bool) -> Outer {
+        Outer {
+            settings: Settings {
+                host: "localhost".to_string(),
+                port: 8080,
+                tls,
+            },
+        }
+    }
+
+    #[test]
+    fn deserialize() {
+        for (json, tls) in [
+            (r#"{"settings": {"address": "localhost:8080"}}"#, false),
+            (
+                r#"{"settings": {"schema": 1, "address": "localhost:8080"}}"#,
+                false,
+            ),
+            (
+                r#"{"settings": {"schema": 2, "host": "localhost", "port": 8080}}"#,
+                false,
+            ),
+            (
+                r#"{"settings": {"schema": 3, "host": "localhost", "port": 8080, "tls": true}}"#,
+                true,
+            ),
+        ] {
+            let result: Outer = serde_json::from_str(json).expect("Oops!");
+            assert_eq!(&outer(tls), &result);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let result = serde_json::to_string(&outer(true)).expect("Oops!");
+        assert_eq!(
+            result,
+            r#"{"settings":{"schema":3,"host":"localhost","port":8080,"tls":true}}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_top_level() {
+        let json = r#"{"address": "localhost:8080"}"#;
+        let mut d = serde_json::Deserializer::from_str(json);
+        let result: Settings = super::deserialize(&mut d).expect("Oops!");
+        assert_eq!(result, outer(false).settings);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        for (json, msg) in [
+            (
+                r#"{"settings": {"schema": 4}}"#,
+                "unsupported schema 4, the newest is 3",
+            ),
+            (
+                r#"{"settings": {"schema": 0}}"#,
+                "no migration from schema 0",
+            ),
+            (
+                r#"{"settings": {"schema": "2"}}"#,
+                "invalid schema String(\"2\")",
+            ),
+            (
+                r#"{"settings": {"address": "localhost"}}"#,
+                "migration from schema 1: address has no port",
+            ),
+        ] {
+            let err = serde_json::from_str::<Outer>(json).unwrap_err().to_string();
+            assert!(err.contains(msg), "{}", err);
+        }
+    }
+}
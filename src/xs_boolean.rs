@@ -0,0 +1,206 @@
+//! Serialize and Deserialize `bool` as an XML Schema `xs:boolean`
+//!
+//! SAML, SOAP and other XSD derived documents may write a boolean as
+//! `"true"`, `"false"`, `"1"` or `"0"`, but XML deserializers, such as
+//! quick-xml and serde-xml-rs, only hand over the text.  This module reads
+//! any of the four, with surrounding whitespace, as XSD collapses it, and
+//! always writes `"true"` or `"false"`.  Other spellings, such as `"True"`
+//! or `"yes"`, are an error, as they are in XSD.
+//!
+//! An element is read from its text, which quick-xml passes as a `$text`
+//! (or `$value`) map entry.  Native booleans, and the integers `0` and `1`,
+//! are accepted too, so the same struct can be read from JSON.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! pub struct Outer {
+//!     #[serde(rename = "@WantAuthnRequestsSigned", with = "serde_stuff::xs_boolean")]
+//!     pub want_signed: bool,
+//!     #[serde(rename = "@isDefault", with = "serde_stuff::xs_boolean")]
+//!     pub is_default: bool,
+//! }
+//!
+//! let xml = r#"<Outer WantAuthnRequestsSigned="1" isDefault="false"/>"#;
+//! let outer: Outer = quick_xml::de::from_str(xml).unwrap();
+//! assert!(outer.want_signed);
+//! assert!(!outer.is_default);
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+use serde::de::{self, MapAccess, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Parse the XSD lexical forms of a boolean.
+pub fn parse(value: &str) -> Option<bool> {
+    match value.trim_matches(|c| matches!(c, ' ' | '\t' | '\n' | '\r')) {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// An `xs:boolean`, for reading map values.
+struct XsBoolean(bool);
+
+impl<'de> Deserialize<'de> for XsBoolean {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        deserialize(d).map(XsBoolean)
+    }
+}
+
+struct XsBooleanVisitor;
+
+impl<'de> Visitor<'de> for XsBooleanVisitor {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("true, false, 1 or 0")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        Ok(value)
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        parse(value).ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        match value {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+        }
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        match value {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(E::invalid_value(Unexpected::Unsigned(value), &self)),
+        }
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<bool, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut value = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "$text" | "$value" if value.is_none() => {
+                    value = Some(map.next_value::<XsBoolean>()?.0);
+                }
+                _ => return Err(de::Error::invalid_type(Unexpected::Map, &self)),
+            }
+        }
+        value.ok_or_else(|| de::Error::invalid_type(Unexpected::Map, &self))
+    }
+}
+
+pub fn serialize<S: Serializer>(v: &bool, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(if *v { "true" } else { "false" })
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<bool, D::Error> {
+    d.deserialize_any(XsBooleanVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Outer {
+        #[serde(rename = "@signed", with = "crate::xs_boolean")]
+        pub signed: bool,
+        #[serde(with = "crate::xs_boolean")]
+        pub persistent: bool,
+    }
+
+    #[test]
+    fn lexical_forms() {
+        for (text, expected) in [
+            ("true", true),
+            ("false", false),
+            ("1", true),
+            ("0", false),
+            (" \n true\t", true),
+        ] {
+            assert_eq!(super::parse(text), Some(expected), "{:?}", text);
+        }
+        for text in ["True", "FALSE", "yes", "", "01"] {
+            assert_eq!(super::parse(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn deserialize_xml() {
+        let xml = r#"<Outer signed="1"><persistent> false </persistent></Outer>"#;
+        let outer: Outer = quick_xml::de::from_str(xml).expect("Oops!");
+        assert_eq!(
+            outer,
+            Outer {
+                signed: true,
+                persistent: false,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_json() {
+        let json = r#"{"@signed": 0, "persistent": true}"#;
+        let outer: Outer = serde_json::from_str(json).expect("Oops!");
+        assert!(!outer.signed);
+        assert!(outer.persistent);
+    }
+
+    #[test]
+    fn serialize() {
+        let outer = Outer {
+            signed: true,
+            persistent: false,
+        };
+        let xml = quick_xml::se::to_string(&outer).expect("Oops!");
+        assert_eq!(
+            xml,
+            r#"<Outer signed="true"><persistent>false</persistent></Outer>"#
+        );
+    }
+
+    #[test]
+    fn empty_element() {
+        let xml = r#"<Outer signed="1"><persistent/></Outer>"#;
+        let err = quick_xml::de::from_str::<Outer>(xml)
+            .expect_err("Oops!")
+            .to_string();
+        assert!(err.contains("expected true, false, 1 or 0"), "{}", err);
+    }
+
+    #[test]
+    fn invalid() {
+        let xml = r#"<Outer signed="yes"><persistent>0</persistent></Outer>"#;
+        let err = quick_xml::de::from_str::<Outer>(xml)
+            .expect_err("Oops!")
+            .to_string();
+        let msg = "invalid value: string \"yes\", expected true, false, 1 or 0";
+        assert!(err.contains(msg), "{}", err);
+    }
+}